@@ -0,0 +1,186 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Verification cost of the routines scanning the owned state, as the number of allocations grows.
+//!
+//! Gives the baseline for performance-motivated rewrites of the scripts: the fungible sums and the
+//! collection issue are linear in the number of allocations, while the divisible token issue scans
+//! all the outputs for each of the tokens, and thus is quadratic in the number of tokens.
+
+#[macro_use]
+extern crate amplify;
+
+use std::collections::BTreeMap;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use hypersonic::{AuthToken, Instr, StateCell, StateData, StateValue, VmContext};
+use issuers::{
+    collection, divisible, fungible, nft_allocation, nft_lib, shared_lib, FN_FUNGIBLE_SUM_OUTPUTS,
+    FN_RGB21_ISSUE, G_COLLECTION_SIZE, G_DETAILS, G_NAME, G_NFT, G_PRECISION, O_AMOUNT,
+};
+use strict_types::StrictDumb;
+use zkaluvm::alu::{CoreConfig, Lib, LibId, LibSite, Vm};
+use zkaluvm::{GfaConfig, FIELD_ORDER_SECP};
+
+/// Libraries compiled once and resolved by their ids, such that the benchmarks measure the
+/// verification and not the compilation of the scripts.
+struct Libs(BTreeMap<LibId, Lib>);
+
+impl Libs {
+    fn compile() -> Self {
+        let libs = [
+            shared_lib(),
+            fungible(),
+            nft_lib(),
+            divisible(),
+            collection(),
+        ]
+        .into_iter()
+        .map(|lib| {
+            let lib = lib.into_lib();
+            (lib.lib_id(), lib)
+        })
+        .collect();
+        Self(libs)
+    }
+
+    fn exec(&self, site: LibSite, context: &VmContext) -> bool {
+        let mut vm = Vm::<Instr<LibId>>::with(
+            CoreConfig {
+                halt: true,
+                complexity_lim: None,
+            },
+            GfaConfig {
+                field_order: FIELD_ORDER_SECP,
+            },
+        );
+        vm.exec(site, context, |id| self.0.get(&id).cloned())
+            .is_ok()
+    }
+}
+
+fn cell(data: StateValue) -> StateCell {
+    StateCell {
+        data,
+        auth: AuthToken::strict_dumb(),
+        lock: None,
+    }
+}
+
+fn fungible_sum_outputs(c: &mut Criterion) {
+    let libs = Libs::compile();
+    let site = fungible().routine(FN_FUNGIBLE_SUM_OUTPUTS);
+
+    let mut group = c.benchmark_group("fungible_sum_outputs");
+    for count in [1u64, 10, 100, 1000] {
+        let outputs = (0..count)
+            .map(|_| cell(StateValue::new(O_AMOUNT, 100u64)))
+            .collect::<Vec<_>>();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &outputs,
+            immutable_output: &[],
+        };
+        assert!(libs.exec(site, &context));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(count),
+            &context,
+            |b, context| b.iter(|| libs.exec(site, context)),
+        );
+    }
+    group.finish();
+}
+
+fn collection_issue(c: &mut Criterion) {
+    let libs = Libs::compile();
+    let site = collection().routine(FN_RGB21_ISSUE);
+
+    let mut group = c.benchmark_group("collection_issue");
+    for count in [1u64, 10, 50, 100] {
+        let mut globals = vec![
+            StateData::new(G_DETAILS, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 1u8),
+            StateData::new(G_COLLECTION_SIZE, count),
+        ];
+        globals.extend((1..=count).map(|id| StateData::new(G_NFT, id)));
+        let outputs = (1..=count)
+            .map(|id| cell(nft_allocation(id, 1)))
+            .collect::<Vec<_>>();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &outputs,
+            immutable_output: &globals,
+        };
+        assert!(libs.exec(site, &context));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(count),
+            &context,
+            |b, context| b.iter(|| libs.exec(site, context)),
+        );
+    }
+    group.finish();
+}
+
+fn divisible_issue(c: &mut Criterion) {
+    let libs = Libs::compile();
+    let site = divisible().routine(FN_RGB21_ISSUE);
+
+    let mut group = c.benchmark_group("divisible_issue");
+    for count in [1u64, 10, 50, 100] {
+        let mut globals = vec![
+            StateData::new(G_DETAILS, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 1u8),
+        ];
+        globals.extend((1..=count).map(|id| StateData::new(G_NFT, id)));
+        let outputs = (1..=count)
+            .map(|id| cell(nft_allocation(id, 1)))
+            .collect::<Vec<_>>();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &outputs,
+            immutable_output: &globals,
+        };
+        assert!(libs.exec(site, &context));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(count),
+            &context,
+            |b, context| b.iter(|| libs.exec(site, context)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    sums,
+    fungible_sum_outputs,
+    collection_issue,
+    divisible_issue
+);
+criterion_main!(sums);
@@ -0,0 +1,35 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::fs;
+
+use issuers::manifest;
+
+fn main() {
+    const FILE: &str = "compiled/manifest.json";
+
+    let manifest = manifest();
+    let json = serde_json::to_string_pretty(&manifest).expect("unable to serialize the manifest");
+    let _ = fs::remove_file(FILE);
+    fs::write(FILE, json).expect("unable to save the manifest to the file");
+    println!("Created manifest listing {} issuers", manifest.len());
+}
@@ -0,0 +1,992 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Helpers for constructing API declarations shared across multiple interfaces.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::{BitOr, BitOrAssign};
+
+use amplify::confinement::{SmallOrdSet, TinyOrdMap, TinyOrdSet};
+use amplify::num::u256;
+use hypersonic::{
+    Aggregator, Api, CallState, Codex, CodexId, GlobalApi, Instr, Issuer, OwnedApi, RawBuilder,
+    RawConvertor, Semantics, StateBuilder, StateConvertor, SubAggregator, VmContext,
+};
+use ifaces::CommonTypes;
+use strict_types::{SemId, TypeSystem, VariantName};
+use zkaluvm::alu::{CoreConfig, CoreExt, Lib, LibId, Supercore, Vm};
+use zkaluvm::{GfaConfig, GfaCore, RegE};
+
+use crate::{reachable_errnos, G_NAME, G_PRECISION, G_REFERENCE, G_SUPPLY, G_TICKER};
+
+/// Constructs the set of the standard interfaces an API conforms to from their numeric ids (i.e.
+/// `20` for RGB20).
+///
+/// All API declarations of the crate must construct their `conforms` field with this function, such
+/// that the shape of the field is defined in a single place. APIs not conforming to any standard
+/// interface provide an empty list.
+///
+/// # Panics
+///
+/// If the number of the interfaces exceeds the confinement of the `conforms` field.
+pub fn conforms(ifaces: &[u16]) -> TinyOrdSet<u16> {
+    TinyOrdSet::from_iter_checked(ifaces.iter().copied())
+}
+
+/// Constructs global state API for a field which has no raw (non-verifiable) data.
+pub fn typed_global(sem_id: SemId, field: u256) -> GlobalApi {
+    GlobalApi {
+        published: true,
+        sem_id,
+        convertor: StateConvertor::TypedEncoder(field),
+        builder: StateBuilder::TypedEncoder(field),
+        raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
+        raw_builder: RawBuilder::StrictEncode(SemId::unit()),
+    }
+}
+
+/// Constructs global state API entries for the asset specification (`ticker`, `name`,
+/// `precision`) and the issued supply (`issued`), as used by the fungible asset interfaces.
+///
+/// Interface-specific API declarations are expected to extend the returned map with their own
+/// fields.
+///
+/// The values of the `RGBContract.Precision` type used for the `precision` range up to
+/// [`MAX_PRECISION`](crate::MAX_PRECISION), which is the bound enforced by the fungible issue
+/// verifiers.
+pub fn asset_spec_globals(types: &CommonTypes) -> BTreeMap<VariantName, GlobalApi> {
+    bmap! {
+        vname!("ticker") => typed_global(types.get("RGBContract.Ticker"), G_TICKER),
+        vname!("name") => typed_global(types.get("RGBContract.AssetName"), G_NAME),
+        vname!("precision") => typed_global(types.get("RGBContract.Precision"), G_PRECISION),
+        vname!("issued") => GlobalApi {
+            published: true,
+            sem_id: types.get("RGBContract.Amount"),
+            convertor: StateConvertor::TypedEncoder(G_SUPPLY),
+            builder: StateBuilder::TypedEncoder(G_SUPPLY),
+            raw_convertor: RawConvertor::StrictDecode(types.get("RGBContract.ProofOfReserves")),
+            raw_builder: RawBuilder::StrictEncode(types.get("RGBContract.ProofOfReserves")),
+        },
+    }
+}
+
+/// Constructs global state API for the optional issuer-internal `reference`, which is committed
+/// to in genesis, but is not published.
+pub fn reference_global(types: &CommonTypes) -> GlobalApi {
+    GlobalApi {
+        published: false,
+        sem_id: SemId::unit(),
+        convertor: StateConvertor::TypedEncoder(G_REFERENCE),
+        builder: StateBuilder::TypedEncoder(G_REFERENCE),
+        raw_convertor: RawConvertor::StrictDecode(types.get("RGBContract.Details")),
+        raw_builder: RawBuilder::StrictEncode(types.get("RGBContract.Details")),
+    }
+}
+
+/// Constructs the default `transfer` call of an API from its owned state declaration.
+///
+/// The call transfers the first of the declared owned states, such that the default call always
+/// refers to an existing owned state; `None` is returned if the API has no owned state.
+pub fn transfer_call(owned: &TinyOrdMap<VariantName, OwnedApi>) -> Option<CallState> {
+    owned
+        .keys()
+        .next()
+        .map(|state| CallState::with("transfer", state.clone()))
+}
+
+/// Information about the API of an issuer, used by tooling constructing contract operations.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IssuerInfo {
+    verifiers: BTreeMap<String, u16>,
+}
+
+impl IssuerInfo {
+    /// Collects the information from the API declaration.
+    pub fn with(api: &Api) -> Self {
+        let verifiers = api
+            .verifiers
+            .iter()
+            .map(|(call, id)| (call.to_string(), *id))
+            .collect();
+        Self { verifiers }
+    }
+
+    /// Returns id of the codex verifier which implements the named call, if the call is known.
+    pub fn verifier_for(&self, call: &str) -> Option<u16> {
+        self.verifiers.get(call).copied()
+    }
+}
+
+/// Set of optional codex configuration rules which may be activated by an issuer.
+///
+/// Features are applied by the codex constructors to the codex configuration (the `features` field
+/// of the codex itself is reserved by the consensus and is left empty), and can be read back from a
+/// codex with [`IssuerFeatures::of`].
+///
+/// The features configure only the execution of the lock scripts (the codex `input_config`, see
+/// [`Self::input_config`]); they are not read by the scripts. The optional verification rules of
+/// the scripts are selected by the codex verifiers and the library constructors instead (like the
+/// per-output cap of [`crate::FN_FUNGIBLE_ISSUE_CAPPED`] or the input and output limit of
+/// [`crate::fungible_with_max_io`]).
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct IssuerFeatures(u8);
+
+impl IssuerFeatures {
+    /// No optional rules are active.
+    pub const NONE: Self = Self(0);
+    /// Lock scripts of the spent allocations are run, allowing to restrict their spending (like
+    /// sealing in the demo sealable fungible asset). Otherwise, the complexity limit of the lock
+    /// scripts is set to zero.
+    pub const LOCK_SCRIPTS: Self = Self(0x01);
+
+    /// Returns raw bits of the feature set.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Checks whether all the `other` features are active.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Constructs the configuration for running the lock scripts of the codex inputs.
+    ///
+    /// The codex `input_config` applies only to the lock scripts of the spent allocations, each of
+    /// which is run separately before the operation verifier (as approximated by
+    /// [`IssuerValidate::validate_inputs`]). Thus, its complexity limit is a per-input budget
+    /// which doesn't depend on the number of inputs and doesn't reduce the budget of the verifier,
+    /// which is limited by the codex `verification_config`.
+    ///
+    /// Without [`Self::LOCK_SCRIPTS`] the limit is zero: any input having a lock script can't be
+    /// spent, while inputs without lock scripts (and operations without inputs, like genesis)
+    /// remain unaffected.
+    pub fn input_config(self) -> CoreConfig {
+        if self.contains(Self::LOCK_SCRIPTS) {
+            CoreConfig::default()
+        } else {
+            CoreConfig {
+                halt: true,
+                complexity_lim: Some(0),
+            }
+        }
+    }
+
+    /// Detects the features applied to the codex configuration.
+    pub fn of(codex: &Codex) -> Self {
+        let mut features = Self::NONE;
+        if codex.input_config.complexity_lim != Some(0) {
+            features |= Self::LOCK_SCRIPTS;
+        }
+        features
+    }
+}
+
+impl BitOr for IssuerFeatures {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for IssuerFeatures {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// API declaration doesn't match the codex it is issued with.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, Error)]
+#[display("API is declared for codex {found}, while the issuer codex id is {expected}")]
+pub struct IssuerMismatch {
+    /// Id of the codex.
+    pub expected: CodexId,
+    /// Codex id stored in the API.
+    pub found: CodexId,
+}
+
+/// Errors constructing an issuer with [`build_issuer`].
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+pub enum BuildIssuerError {
+    /// API declaration doesn't match the codex.
+    #[display("{0}")]
+    CodexMismatch(IssuerMismatch),
+
+    /// The default call of the API refers to an owned state which is not declared by the API.
+    #[display("default call refers to the undeclared owned state {0}")]
+    UnknownCallState(VariantName),
+
+    /// The witness builder of an owned state doesn't match its witness type: only the unit witness
+    /// type may be built with [`StateBuilder::Unit`], and vice versa.
+    #[display("owned state {0} has a witness builder inconsistent with its witness type")]
+    WitnessMismatch(VariantName),
+}
+
+impl From<IssuerMismatch> for BuildIssuerError {
+    fn from(err: IssuerMismatch) -> Self {
+        BuildIssuerError::CodexMismatch(err)
+    }
+}
+
+/// Constructs an issuer, building its default API from the actual id of the `codex`, and checking
+/// that the API refers to that codex, that the API default call refers to a declared owned state
+/// (see [`transfer_call`]), and that the witness builders of the owned states match their witness
+/// types.
+///
+/// All the interfaces of the crate use unit witnesses for the owned state, such that the operations
+/// are compatible across the interface variants.
+///
+/// # Panics
+///
+/// If the semantics doesn't match the codex (for instance, some of the codex libraries are
+/// missing).
+pub fn build_issuer(
+    codex: Codex,
+    api_fn: impl FnOnce(CodexId) -> Api,
+    codex_libs: SmallOrdSet<Lib>,
+    types: TypeSystem,
+) -> Result<Issuer, BuildIssuerError> {
+    let expected = codex.codex_id();
+    let api = api_fn(expected);
+    if api.codex_id != expected {
+        return Err(IssuerMismatch {
+            expected,
+            found: api.codex_id,
+        }
+        .into());
+    }
+    if let Some(CallState {
+        destructible: Some(state),
+        ..
+    }) = &api.default_call
+    {
+        if !api.owned.contains_key(state) {
+            return Err(BuildIssuerError::UnknownCallState(state.clone()));
+        }
+    }
+    for (name, state) in &api.owned {
+        let unit_builder = matches!(state.witness_builder, StateBuilder::Unit);
+        if unit_builder != (state.witness_sem_id == SemId::unit()) {
+            return Err(BuildIssuerError::WitnessMismatch(name.clone()));
+        }
+    }
+    let semantics = Semantics {
+        version: 0,
+        default: api,
+        custom: none!(),
+        codex_libs,
+        api_libs: none!(),
+        types,
+    };
+    Ok(Issuer::new(codex, semantics).expect("invalid issuer"))
+}
+
+/// Errors of the operation validation with [`IssuerValidate::validate_op`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, Error)]
+pub enum IssuerError {
+    /// The codex has no verifier with the given id.
+    #[display("codex has no verifier {0}")]
+    UnknownVerifier(u16),
+
+    /// The default API declares no `issue` method, thus the genesis verifier is not known.
+    #[display("API declares no genesis verifier")]
+    NoGenesisVerifier,
+
+    /// The verifier has rejected the operation, leaving the error code (if any) in `E1`.
+    #[display("operation is rejected by the verifier with error code {0:?}")]
+    Rejected(Option<u256>),
+
+    /// The lock script of the spent allocation has rejected the operation, leaving the error code
+    /// (if any) in `E1`.
+    #[display("input #{input} is rejected by its lock script with error code {errno:?}")]
+    LockRejected { input: usize, errno: Option<u256> },
+}
+
+/// Validation of a single operation against the codex of an issuer, without a full node.
+pub trait IssuerValidate {
+    /// Runs the codex `verifier` on the operation context `ctx`.
+    ///
+    /// The VM is configured with the codex `verification_config` and field order, and the script
+    /// libraries are resolved from the codex libraries of the issuer semantics.
+    fn validate_op(&self, verifier: u16, ctx: &VmContext) -> Result<(), IssuerError>;
+
+    /// Runs the genesis verifier of the codex on the genesis context `ctx`, checking all the
+    /// genesis rules (the asset specification, the issued supply and the owned state) together.
+    ///
+    /// The genesis verifier is the one the default API assigns to the `issue` method.
+    fn validate_genesis(&self, ctx: &VmContext) -> Result<(), IssuerError>;
+
+    /// Runs the lock scripts of the destructible inputs of the operation context `ctx`,
+    /// approximating the checks a validator does before running the operation verifier.
+    ///
+    /// Each lock script is run by a separate VM configured with the codex `input_config`, such
+    /// that its complexity limit applies to each of the inputs independently. Inputs without a
+    /// lock script are not checked.
+    ///
+    /// This is an approximation for testing the lock scripts, not the consensus validation: it
+    /// doesn't check the seals or the authentication tokens of the inputs, and runs each lock
+    /// script on the whole operation context, which may differ from the context provided by the
+    /// validator. Passing this check doesn't guarantee the operation is valid.
+    fn validate_inputs(&self, ctx: &VmContext) -> Result<(), IssuerError>;
+}
+
+impl IssuerValidate for Issuer {
+    fn validate_op(&self, verifier: u16, ctx: &VmContext) -> Result<(), IssuerError> {
+        let codex = self.codex();
+        let site = *codex
+            .verifiers
+            .get(&verifier)
+            .ok_or(IssuerError::UnknownVerifier(verifier))?;
+        let libs = &self.semantics().codex_libs;
+        let resolver = |id: LibId| libs.iter().find(|lib| lib.lib_id() == id).cloned();
+        let mut vm = Vm::<Instr<LibId>>::with(
+            codex.verification_config,
+            GfaConfig {
+                field_order: codex.field_order,
+            },
+        );
+        if vm.exec(site, ctx, resolver).is_ok() {
+            return Ok(());
+        }
+        let gfa: GfaCore = vm.core.cx.subcore();
+        Err(IssuerError::Rejected(
+            gfa.get(RegE::E1).map(|fe| fe.to_u256()),
+        ))
+    }
+
+    fn validate_genesis(&self, ctx: &VmContext) -> Result<(), IssuerError> {
+        let verifier = *self
+            .default_api()
+            .verifiers
+            .get(&vname!("issue"))
+            .ok_or(IssuerError::NoGenesisVerifier)?;
+        self.validate_op(verifier, ctx)
+    }
+
+    fn validate_inputs(&self, ctx: &VmContext) -> Result<(), IssuerError> {
+        let codex = self.codex();
+        let libs = &self.semantics().codex_libs;
+        let resolver = |id: LibId| libs.iter().find(|lib| lib.lib_id() == id).cloned();
+        for (input, (_, cell)) in ctx.destructible_input.iter().enumerate() {
+            let Some(lock) = cell.lock else {
+                continue;
+            };
+            let mut vm = Vm::<Instr<LibId>>::with(
+                codex.input_config,
+                GfaConfig {
+                    field_order: codex.field_order,
+                },
+            );
+            if vm.exec(lock, ctx, resolver).is_err() {
+                let gfa: GfaCore = vm.core.cx.subcore();
+                let errno = gfa.get(RegE::E1).map(|fe| fe.to_u256());
+                return Err(IssuerError::LockRejected { input, errno });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Catalog of the error codes declared by the default API of an issuer.
+pub trait IssuerErrors {
+    /// Enumerates the error codes together with their messages, ordered by the error code.
+    ///
+    /// Allows tooling to render a catalog of the errors which may be reported by the codex
+    /// verifiers.
+    fn errors(&self) -> impl Iterator<Item = (u256, &str)>;
+}
+
+impl IssuerErrors for Issuer {
+    fn errors(&self) -> impl Iterator<Item = (u256, &str)> {
+        self.semantics()
+            .default
+            .errors
+            .iter()
+            .map(|(errno, msg)| (*errno, msg.as_str()))
+    }
+}
+
+/// Inconsistency between the codex and the default API of an issuer, reported by [`lint`].
+#[derive(Clone, PartialEq, Eq, Debug, Display)]
+pub enum LintWarning {
+    /// An API method refers to a verifier which is absent from the codex.
+    #[display("API method {method} refers to verifier {verifier}, which is absent from the codex")]
+    UnknownVerifier { method: VariantName, verifier: u16 },
+
+    /// The default call of the API refers to a method which is not declared by the API.
+    #[display("default call refers to the undeclared method {0}")]
+    UnknownCallMethod(VariantName),
+
+    /// The default call of the API refers to an owned state which is not declared by the API.
+    #[display("default call refers to the undeclared owned state {0}")]
+    UnknownCallState(VariantName),
+
+    /// An aggregator refers to a state which is neither a global state nor an aggregator declared
+    /// by the API.
+    #[display("aggregator {aggregator} refers to the undeclared state {state}")]
+    UnknownAggregatedState {
+        aggregator: VariantName,
+        state: VariantName,
+    },
+
+    /// A codex verifier may report an error code which has no message in the API error map.
+    #[display(
+        "verifier {verifier} may report error code {errno:#x}, which has no message in the API"
+    )]
+    UndeclaredErrno { verifier: u16, errno: u256 },
+}
+
+/// Checks the consistency of the issuer codex and its default API, returning the list of the
+/// detected mismatches (which is empty for a consistent issuer).
+///
+/// The checks cover the verifier ids referenced by the API methods, the state names referenced by
+/// the API default call and aggregators, and the messages for the error codes which may be
+/// reported by the codex verifiers. Since the script libraries are shared across codices using
+/// different subsets of their routines, the error codes are detected per verifier with
+/// [`reachable_errnos`]; each undeclared error code is reported once, for the first verifier
+/// reaching it.
+pub fn lint(issuer: &Issuer) -> Vec<LintWarning> {
+    let codex = issuer.codex();
+    let api = issuer.default_api();
+    let mut warnings = vec![];
+
+    for (method, verifier) in &api.verifiers {
+        if !codex.verifiers.contains_key(verifier) {
+            warnings.push(LintWarning::UnknownVerifier {
+                method: method.clone(),
+                verifier: *verifier,
+            });
+        }
+    }
+
+    if let Some(call) = &api.default_call {
+        if !api.verifiers.contains_key(&call.method) {
+            warnings.push(LintWarning::UnknownCallMethod(call.method.clone()));
+        }
+        if let Some(state) = &call.destructible {
+            if !api.owned.contains_key(state) {
+                warnings.push(LintWarning::UnknownCallState(state.clone()));
+            }
+        }
+    }
+
+    for (aggregator, def) in &api.aggregators {
+        let state = match def {
+            Aggregator::Take(
+                SubAggregator::TheOnly(state)
+                | SubAggregator::Copy(state)
+                | SubAggregator::SumOrDefault(state)
+                | SubAggregator::CountUnique(state)
+                | SubAggregator::MapV2U(state),
+            ) => state,
+            _ => continue,
+        };
+        if !api.global.contains_key(state) && !api.aggregators.contains_key(state) {
+            warnings.push(LintWarning::UnknownAggregatedState {
+                aggregator: aggregator.clone(),
+                state: state.clone(),
+            });
+        }
+    }
+
+    let libs = &issuer.semantics().codex_libs;
+    let mut reported = BTreeSet::new();
+    for (verifier, site) in &codex.verifiers {
+        for errno in reachable_errnos(*site, libs) {
+            if !api.errors.contains_key(&errno) && reported.insert(errno) {
+                warnings.push(LintWarning::UndeclaredErrno {
+                    verifier: *verifier,
+                    errno,
+                });
+            }
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use strict_types::StrictDumb;
+
+    use super::*;
+    use crate::rgb20::fna;
+    use crate::{scripts, ERRNO_SUM_MISMATCH};
+
+    #[test]
+    fn matches_fna() {
+        let types = CommonTypes::new();
+        let globals = asset_spec_globals(&types);
+        let expected = bmap! {
+            vname!("ticker") => GlobalApi {
+                published: true,
+                sem_id: types.get("RGBContract.Ticker"),
+                convertor: StateConvertor::TypedEncoder(G_TICKER),
+                builder: StateBuilder::TypedEncoder(G_TICKER),
+                raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
+                raw_builder: RawBuilder::StrictEncode(SemId::unit())
+            },
+            vname!("name") => GlobalApi {
+                published: true,
+                sem_id: types.get("RGBContract.AssetName"),
+                convertor: StateConvertor::TypedEncoder(G_NAME),
+                builder: StateBuilder::TypedEncoder(G_NAME),
+                raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
+                raw_builder: RawBuilder::StrictEncode(SemId::unit())
+            },
+            vname!("precision") => GlobalApi {
+                published: true,
+                sem_id: types.get("RGBContract.Precision"),
+                convertor: StateConvertor::TypedEncoder(G_PRECISION),
+                builder: StateBuilder::TypedEncoder(G_PRECISION),
+                raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
+                raw_builder: RawBuilder::StrictEncode(SemId::unit())
+            },
+            vname!("issued") => GlobalApi {
+                published: true,
+                sem_id: types.get("RGBContract.Amount"),
+                convertor: StateConvertor::TypedEncoder(G_SUPPLY),
+                builder: StateBuilder::TypedEncoder(G_SUPPLY),
+                raw_convertor: RawConvertor::StrictDecode(types.get("RGBContract.ProofOfReserves")),
+                raw_builder: RawBuilder::StrictEncode(types.get("RGBContract.ProofOfReserves"))
+            },
+        };
+        assert_eq!(globals.len(), expected.len());
+        for (name, api) in expected {
+            let built = globals
+                .get(&name)
+                .unwrap_or_else(|| panic!("missing global {name}"));
+            assert_eq!(built.published, api.published, "{name}");
+            assert_eq!(built.sem_id, api.sem_id, "{name}");
+            assert_eq!(built.convertor, api.convertor, "{name}");
+            assert_eq!(built.builder, api.builder, "{name}");
+            assert_eq!(built.raw_convertor, api.raw_convertor, "{name}");
+            assert_eq!(built.raw_builder, api.raw_builder, "{name}");
+        }
+
+        let fna = crate::rgb20::fna::api(crate::rgb20::fna::codex().codex_id());
+        for (name, api) in globals {
+            assert_eq!(fna.global.get(&name), Some(&api), "{name}");
+        }
+    }
+
+    #[test]
+    fn verifier_for() {
+        let info = IssuerInfo::with(&fna::api(fna::codex().codex_id()));
+        assert_eq!(info.verifier_for("issue"), Some(fna::VERIFIER_GENESIS));
+        assert_eq!(info.verifier_for("transfer"), Some(fna::VERIFIER_TRANSFER));
+        assert_eq!(info.verifier_for("burn"), None);
+    }
+
+    #[test]
+    fn errors_catalog() {
+        use crate::{ERRNO_NO_NAME, ERRNO_NO_PRECISION, ERRNO_NO_TICKER, ERRNO_SUM_MISMATCH};
+
+        let issuer = fna::issuer();
+        let errors = issuer.errors().collect::<BTreeMap<_, _>>();
+        for errno in [
+            ERRNO_NO_TICKER,
+            ERRNO_NO_NAME,
+            ERRNO_NO_PRECISION,
+            ERRNO_SUM_MISMATCH,
+        ] {
+            let msg = errors
+                .get(&errno)
+                .unwrap_or_else(|| panic!("no message for {errno}"));
+            assert!(!msg.is_empty());
+        }
+        assert_eq!(errors.len(), issuer.semantics().default.errors.len());
+    }
+
+    #[test]
+    fn issuer_features() {
+        assert!(IssuerFeatures::NONE.contains(IssuerFeatures::NONE));
+        assert!(!IssuerFeatures::NONE.contains(IssuerFeatures::LOCK_SCRIPTS));
+        assert_eq!(
+            IssuerFeatures::NONE | IssuerFeatures::LOCK_SCRIPTS,
+            IssuerFeatures::LOCK_SCRIPTS
+        );
+
+        let sealable = crate::demo::sfa::issuer();
+        assert_eq!(
+            IssuerFeatures::of(sealable.codex()),
+            IssuerFeatures::LOCK_SCRIPTS
+        );
+        assert_eq!(
+            IssuerFeatures::of(fna::issuer().codex()),
+            IssuerFeatures::NONE
+        );
+    }
+
+    #[test]
+    fn lock_scripts_feature() {
+        // A lock script which accepts any operation runs only with the feature active
+        let lock = scripts::shared_lib().routine(scripts::FN_GLOBAL_ABSENT);
+        let lib = scripts::shared_lib().into_lib();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[],
+            immutable_output: &[],
+        };
+        let run = |features: IssuerFeatures| {
+            let mut vm = Vm::<Instr<LibId>>::with(
+                features.input_config(),
+                GfaConfig {
+                    field_order: zkaluvm::FIELD_ORDER_SECP,
+                },
+            );
+            vm.exec(lock, &context, |_| Some(lib.clone())).is_ok()
+        };
+        assert!(run(IssuerFeatures::LOCK_SCRIPTS));
+        assert!(!run(IssuerFeatures::NONE));
+    }
+
+    #[test]
+    fn validate_op() {
+        use hypersonic::StateData;
+
+        use crate::test_helpers::fungible_ctx;
+        use crate::ERRNO_SUM_MISMATCH;
+
+        let issuer = fna::issuer();
+        let genesis = fungible_ctx(&[], &[600, 400]).with_globals([
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 2u8),
+            StateData::new(G_SUPPLY, 1000u64),
+        ]);
+        assert_eq!(
+            issuer.validate_op(fna::VERIFIER_GENESIS, &genesis.as_context()),
+            Ok(())
+        );
+
+        let inflation = fungible_ctx(&[600, 400], &[1001]);
+        assert_eq!(
+            issuer.validate_op(fna::VERIFIER_TRANSFER, &inflation.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_SUM_MISMATCH)))
+        );
+        assert_eq!(
+            issuer.validate_op(0xFFFF, &inflation.as_context()),
+            Err(IssuerError::UnknownVerifier(0xFFFF))
+        );
+    }
+
+    #[test]
+    fn validate_genesis() {
+        use hypersonic::StateData;
+
+        use crate::test_helpers::fungible_ctx;
+        use crate::{ERRNO_NO_ISSUED, ERRNO_NO_PRECISION, ERRNO_SUM_ISSUE_MISMATCH};
+
+        let issuer = fna::issuer();
+        let spec = [
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 2u8),
+        ];
+        let supply = StateData::new(G_SUPPLY, 1000u64);
+
+        let genesis = fungible_ctx(&[], &[600, 400])
+            .with_globals(spec.iter().cloned().chain([supply.clone()]));
+        assert_eq!(issuer.validate_genesis(&genesis.as_context()), Ok(()));
+
+        // The composed rules are checked: the specification, the supply and the allocations
+        let cases = [
+            (&spec[..2], true, &[1000][..], ERRNO_NO_PRECISION),
+            (&spec[..], false, &[1000], ERRNO_NO_ISSUED),
+            (&spec[..], true, &[600, 401], ERRNO_SUM_ISSUE_MISMATCH),
+        ];
+        for (spec, with_supply, outputs, errno) in cases {
+            let supply = with_supply.then(|| supply.clone());
+            let genesis =
+                fungible_ctx(&[], outputs).with_globals(spec.iter().cloned().chain(supply));
+            assert_eq!(
+                issuer.validate_genesis(&genesis.as_context()),
+                Err(IssuerError::Rejected(Some(errno)))
+            );
+        }
+
+        let issuer = build_issuer(
+            fna::codex(),
+            |codex_id| {
+                let mut api = fna::api(codex_id);
+                let _ = api.verifiers.remove(&vname!("issue"));
+                api
+            },
+            small_bset![
+                scripts::shared_lib_v(fna::SCRIPT_VERSION).into_lib(),
+                scripts::fungible_v(fna::SCRIPT_VERSION).into_lib()
+            ],
+            CommonTypes::new().type_system(),
+        )
+        .expect("valid issuer");
+        assert_eq!(
+            issuer.validate_genesis(&genesis.as_context()),
+            Err(IssuerError::NoGenesisVerifier)
+        );
+    }
+
+    #[test]
+    fn validate_inputs() {
+        use crate::demo::sfa;
+        use crate::test_helpers::fungible_ctx;
+        use crate::ERRNO_SEALED_INPUT;
+
+        // The helper approximates the validator (see its docs): these checks cover only the
+        // behaviour of the lock scripts under the codex input budget, not the validity of the
+        // operations
+
+        // Inputs without lock scripts don't consume the input budget, which is zero for `fna`, and
+        // don't reduce the verification budget, regardless of their number
+        let issuer = fna::issuer();
+        assert_eq!(issuer.codex().input_config.complexity_lim, Some(0));
+        let mut transfer = fungible_ctx(&[1; 16], &[10, 6]);
+        assert_eq!(issuer.validate_inputs(&transfer.as_context()), Ok(()));
+        assert_eq!(
+            issuer.validate_op(fna::VERIFIER_TRANSFER, &transfer.as_context()),
+            Ok(())
+        );
+
+        // Genesis has no inputs to run lock scripts for
+        let genesis = fungible_ctx(&[], &[16]);
+        assert_eq!(issuer.validate_inputs(&genesis.as_context()), Ok(()));
+
+        // With a zero input budget, a lock script fails before it makes any check
+        transfer.destructible_input[3].1.lock = Some(sfa::seal());
+        assert!(matches!(
+            issuer.validate_inputs(&transfer.as_context()),
+            Err(IssuerError::LockRejected { input: 3, .. })
+        ));
+        assert_eq!(
+            issuer.validate_op(fna::VERIFIER_TRANSFER, &transfer.as_context()),
+            Ok(())
+        );
+
+        // With lock scripts enabled, the lock script itself rejects the input
+        let sealable = sfa::issuer();
+        assert_eq!(
+            sealable.validate_inputs(&transfer.as_context()),
+            Err(IssuerError::LockRejected {
+                input: 3,
+                errno: Some(ERRNO_SEALED_INPUT),
+            })
+        );
+        transfer.destructible_input[3].1.lock = None;
+        assert_eq!(sealable.validate_inputs(&transfer.as_context()), Ok(()));
+    }
+
+    #[test]
+    fn issuer_mismatch() {
+        let codex = fna::codex();
+        let expected = codex.codex_id();
+        let libs = || {
+            small_bset![
+                scripts::shared_lib_v(fna::SCRIPT_VERSION).into_lib(),
+                scripts::fungible_v(fna::SCRIPT_VERSION).into_lib()
+            ]
+        };
+
+        let issuer = build_issuer(
+            codex.clone(),
+            fna::api,
+            libs(),
+            CommonTypes::new().type_system(),
+        )
+        .expect("matching codex id");
+        assert_eq!(issuer.codex().codex_id(), expected);
+
+        let wrong = CodexId::strict_dumb();
+        assert_ne!(wrong, expected);
+        let err = build_issuer(
+            codex,
+            |_| fna::api(wrong),
+            libs(),
+            CommonTypes::new().type_system(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            BuildIssuerError::CodexMismatch(IssuerMismatch {
+                expected,
+                found: wrong
+            })
+        );
+    }
+
+    #[test]
+    fn default_call_mismatch() {
+        let libs = small_bset![
+            scripts::shared_lib_v(fna::SCRIPT_VERSION).into_lib(),
+            scripts::fungible_v(fna::SCRIPT_VERSION).into_lib()
+        ];
+        let err = build_issuer(
+            fna::codex(),
+            |codex_id| {
+                let mut api = fna::api(codex_id);
+                api.default_call = Some(CallState::with("transfer", "amount"));
+                api
+            },
+            libs,
+            CommonTypes::new().type_system(),
+        )
+        .unwrap_err();
+        assert_eq!(err, BuildIssuerError::UnknownCallState(vname!("amount")));
+    }
+
+    #[test]
+    fn witness_mismatch() {
+        let libs = small_bset![
+            scripts::shared_lib_v(fna::SCRIPT_VERSION).into_lib(),
+            scripts::fungible_v(fna::SCRIPT_VERSION).into_lib()
+        ];
+        let err = build_issuer(
+            fna::codex(),
+            |codex_id| {
+                let mut api = fna::api(codex_id);
+                let balance = api.owned.get_mut(&vname!("balance")).expect("no balance");
+                balance.witness_builder = StateBuilder::TypedEncoder(crate::O_AMOUNT);
+                api
+            },
+            libs,
+            CommonTypes::new().type_system(),
+        )
+        .unwrap_err();
+        assert_eq!(err, BuildIssuerError::WitnessMismatch(vname!("balance")));
+    }
+
+    #[test]
+    fn rgb21_witness_builders() {
+        let nfu = crate::rgb21::nfu::issuer();
+        let nfc = crate::rgb21::nfc::issuer();
+        let nfu = &nfu.default_api().owned;
+        let nfc = &nfc.default_api().owned;
+        assert_eq!(
+            nfu.keys().collect::<Vec<_>>(),
+            nfc.keys().collect::<Vec<_>>()
+        );
+        for (name, state) in nfu.iter() {
+            let other = nfc.get(name).expect("missing owned state");
+            assert!(
+                matches!(state.witness_builder, StateBuilder::Unit),
+                "{name}"
+            );
+            assert!(
+                matches!(other.witness_builder, StateBuilder::Unit),
+                "{name}"
+            );
+            assert_eq!(state.witness_sem_id, other.witness_sem_id, "{name}");
+        }
+    }
+
+    #[test]
+    fn lint_mismatches() {
+        let libs = small_bset![
+            scripts::shared_lib_v(fna::SCRIPT_VERSION).into_lib(),
+            scripts::fungible_v(fna::SCRIPT_VERSION).into_lib()
+        ];
+        let issuer = build_issuer(
+            fna::codex(),
+            |codex_id| {
+                let mut api = fna::api(codex_id);
+                api.default_call = Some(CallState::with("send", "balance"));
+                api.aggregators
+                    .insert(
+                        vname!("burned"),
+                        Aggregator::Take(SubAggregator::SumOrDefault(vname!("burnt"))),
+                    )
+                    .expect("too many aggregators");
+                let _ = api.errors.remove(&ERRNO_SUM_MISMATCH);
+                api
+            },
+            libs,
+            CommonTypes::new().type_system(),
+        )
+        .unwrap();
+        assert_eq!(
+            lint(&issuer),
+            vec![
+                LintWarning::UnknownCallMethod(vname!("send")),
+                LintWarning::UnknownAggregatedState {
+                    aggregator: vname!("burned"),
+                    state: vname!("burnt"),
+                },
+                LintWarning::UndeclaredErrno {
+                    verifier: fna::VERIFIER_TRANSFER,
+                    errno: ERRNO_SUM_MISMATCH,
+                },
+            ]
+        );
+        assert_eq!(lint(&fna::issuer()), vec![]);
+    }
+
+    #[test]
+    fn default_call_derived() {
+        let fna = fna::api(fna::codex().codex_id());
+        assert_eq!(
+            fna.default_call,
+            Some(CallState::with("transfer", "balance"))
+        );
+        assert_eq!(transfer_call(&fna.owned), fna.default_call);
+        assert_eq!(transfer_call(&none!()), None);
+
+        let pfa = crate::demo::pfa::api(crate::demo::pfa::codex().codex_id());
+        assert_eq!(
+            pfa.default_call,
+            Some(CallState::with("transfer", "balanceA"))
+        );
+    }
+
+    #[test]
+    fn conforms_unified() {
+        let rgb20 = fna::api(fna::codex().codex_id());
+        assert_eq!(rgb20.conforms, conforms(&[20]));
+        assert!(rgb20.conforms.contains(&20));
+
+        let rgb21 = crate::rgb21::nfu::issuer();
+        let rgb21 = rgb21.default_api();
+        assert_eq!(rgb21.conforms, conforms(&[21]));
+        assert!(rgb21.conforms.contains(&21));
+
+        let pfa = crate::demo::pfa::api(crate::demo::pfa::codex().codex_id());
+        assert!(pfa.conforms.is_empty());
+        assert_eq!(pfa.conforms, conforms(&[]));
+    }
+
+    #[test]
+    fn reference_unpublished() {
+        let version = scripts::ScriptVersion::LATEST;
+        let fna = fna::api_v(version, fna::codex_v(version).codex_id());
+        let reference = fna
+            .global
+            .get(&vname!("reference"))
+            .expect("no reference global");
+        assert!(!reference.published);
+        assert_eq!(reference, &reference_global(&CommonTypes::new()));
+        assert!(fna
+            .global
+            .iter()
+            .filter(|(name, _)| *name != &vname!("reference"))
+            .all(|(_, api)| api.published));
+    }
+}
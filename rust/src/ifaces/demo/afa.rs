@@ -0,0 +1,155 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Airdrop Fungible Asset: an RGB20-compatible asset which genesis allocations are tagged with a
+//! recipient class from the allowlist declared in genesis (see [`FN_FUNGIBLE_ISSUE_CLASSED`]).
+//!
+//! The class tags only the genesis allocations; the transfers spend the tagged allocations and
+//! produce plain ones (see [`FN_FUNGIBLE_TRANSFER_CLASSED`]).
+//!
+//! The allowlist ([`crate::G_RECIPIENT_CLASSES`]) and the tagged allocations have no matching
+//! types in the common interface types; thus they are not exposed by the API and must be
+//! constructed by the issuers directly.
+
+use hypersonic::{Codex, Identity, Issuer, Semantics};
+use ifaces::CommonTypes;
+use zkaluvm::alu::CoreConfig;
+use zkaluvm::FIELD_ORDER_SECP;
+
+use crate::api_builder::IssuerFeatures;
+use crate::rgb20::fna;
+use crate::scripts::{ScriptVersion, FN_FUNGIBLE_ISSUE_CLASSED, FN_FUNGIBLE_TRANSFER_CLASSED};
+use crate::{scripts, ERRNO_NO_RECIPIENT_CLASSES, ERRNO_UNLISTED_RECIPIENT_CLASS, PANDORA};
+
+pub const VERIFIER_GENESIS: u16 = fna::VERIFIER_GENESIS;
+pub const VERIFIER_TRANSFER: u16 = fna::VERIFIER_TRANSFER;
+pub const VERIFIER_BLANK: u16 = fna::VERIFIER_BLANK;
+
+pub fn issuer() -> Issuer {
+    let types = CommonTypes::new();
+    let codex = codex();
+    let mut api = fna::api_v(ScriptVersion::LATEST, codex.codex_id());
+    api.errors
+        .insert(
+            ERRNO_NO_RECIPIENT_CLASSES,
+            tiny_s!("the recipient class allowlist must be declared after the issued supply and list at least one class"),
+        )
+        .expect("too many errors");
+    api.errors
+        .insert(
+            ERRNO_UNLISTED_RECIPIENT_CLASS,
+            tiny_s!(
+                "a genesis allocation is tagged with a recipient class missing from the allowlist"
+            ),
+        )
+        .expect("too many errors");
+
+    let semantics = Semantics {
+        version: 0,
+        default: api,
+        custom: none!(),
+        codex_libs: small_bset![
+            scripts::shared_lib().into_lib(),
+            scripts::fungible().into_lib(),
+        ],
+        api_libs: none!(),
+        types: types.type_system(),
+    };
+    Issuer::new(codex, semantics).expect("invalid issuer")
+}
+
+pub fn codex() -> Codex {
+    let lib = scripts::fungible();
+    Codex {
+        name: tiny_s!("Airdrop Fungible Asset"),
+        developer: Identity::from(PANDORA),
+        version: default!(),
+        features: none!(),
+        timestamp: 1732529307,
+        field_order: FIELD_ORDER_SECP,
+        input_config: IssuerFeatures::NONE.input_config(),
+        verification_config: CoreConfig::default(),
+        verifiers: tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(FN_FUNGIBLE_ISSUE_CLASSED),
+            VERIFIER_TRANSFER => lib.routine(FN_FUNGIBLE_TRANSFER_CLASSED),
+            VERIFIER_BLANK => lib.routine(FN_FUNGIBLE_TRANSFER_CLASSED),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::{StateData, StateValue};
+
+    use super::*;
+    use crate::api_builder::{IssuerError, IssuerValidate};
+    use crate::test_helpers::TestContext;
+    use crate::{
+        ERRNO_INVALID_BALANCE_OUT, G_NAME, G_PRECISION, G_RECIPIENT_CLASSES, G_SUPPLY, G_TICKER,
+        O_AMOUNT,
+    };
+
+    fn classed(amount: u64, class: u64) -> StateValue {
+        StateValue::Triple {
+            first: O_AMOUNT.into(),
+            second: amount.into(),
+            third: class.into(),
+        }
+    }
+
+    #[test]
+    fn airdrop_spendable() {
+        let issuer = issuer();
+        let allocations = [classed(600, 10), classed(400, 20)];
+        let allowlist = StateValue::Triple {
+            first: G_RECIPIENT_CLASSES.into(),
+            second: 10u64.into(),
+            third: 20u64.into(),
+        };
+        let genesis = TestContext::with(&[], &allocations).with_globals([
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 2u8),
+            StateData::new(G_SUPPLY, 1000u64),
+            StateData {
+                value: allowlist,
+                raw: None,
+            },
+        ]);
+        assert_eq!(issuer.validate_genesis(&genesis.as_context()), Ok(()));
+
+        let plain = [
+            StateValue::new(O_AMOUNT, 100u64),
+            StateValue::new(O_AMOUNT, 500u64),
+        ];
+        let transfer = TestContext::with(&allocations[..1], &plain);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER, &transfer.as_context()),
+            Ok(())
+        );
+        let reclassed = TestContext::with(&allocations[..1], &[classed(600, 20)]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER, &reclassed.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_INVALID_BALANCE_OUT)))
+        );
+    }
+}
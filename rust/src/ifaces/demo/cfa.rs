@@ -0,0 +1,106 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Change-enforcing Fungible Asset: an RGB20-compatible asset which transfers spending several
+//! allocations must have a change output (see [`FN_FUNGIBLE_TRANSFER_WITH_CHANGE`]).
+
+use hypersonic::{Codex, Identity, Issuer, Semantics};
+use ifaces::CommonTypes;
+use zkaluvm::alu::CoreConfig;
+use zkaluvm::FIELD_ORDER_SECP;
+
+use crate::api_builder::IssuerFeatures;
+use crate::rgb20::fna;
+use crate::scripts::{
+    ScriptVersion, FN_FUNGIBLE_BLANK, FN_FUNGIBLE_ISSUE, FN_FUNGIBLE_TRANSFER_WITH_CHANGE,
+};
+use crate::{scripts, ERRNO_NO_CHANGE_OUTPUT, PANDORA};
+
+pub const VERIFIER_GENESIS: u16 = fna::VERIFIER_GENESIS;
+pub const VERIFIER_TRANSFER: u16 = fna::VERIFIER_TRANSFER;
+pub const VERIFIER_BLANK: u16 = fna::VERIFIER_BLANK;
+
+pub fn issuer() -> Issuer {
+    let types = CommonTypes::new();
+    let codex = codex();
+    let mut api = fna::api_v(ScriptVersion::LATEST, codex.codex_id());
+    api.errors
+        .insert(
+            ERRNO_NO_CHANGE_OUTPUT,
+            tiny_s!("a transfer spending several allocations must have a change output"),
+        )
+        .expect("too many errors");
+
+    let semantics = Semantics {
+        version: 0,
+        default: api,
+        custom: none!(),
+        codex_libs: small_bset![
+            scripts::shared_lib().into_lib(),
+            scripts::fungible().into_lib(),
+        ],
+        api_libs: none!(),
+        types: types.type_system(),
+    };
+    Issuer::new(codex, semantics).expect("invalid issuer")
+}
+
+pub fn codex() -> Codex {
+    let lib = scripts::fungible();
+    Codex {
+        name: tiny_s!("Change-enforcing Fungible Asset"),
+        developer: Identity::from(PANDORA),
+        version: default!(),
+        features: none!(),
+        timestamp: 1732529307,
+        field_order: FIELD_ORDER_SECP,
+        input_config: IssuerFeatures::NONE.input_config(),
+        verification_config: CoreConfig::default(),
+        verifiers: tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(FN_FUNGIBLE_ISSUE),
+            VERIFIER_TRANSFER => lib.routine(FN_FUNGIBLE_TRANSFER_WITH_CHANGE),
+            VERIFIER_BLANK => lib.routine(FN_FUNGIBLE_BLANK),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_builder::{IssuerError, IssuerValidate};
+    use crate::test_helpers::fungible_ctx;
+
+    #[test]
+    fn transfer_requires_change() {
+        let issuer = issuer();
+        let consolidation = fungible_ctx(&[100, 200], &[300]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER, &consolidation.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_NO_CHANGE_OUTPUT)))
+        );
+        let with_change = fungible_ctx(&[100, 200], &[250, 50]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER, &with_change.as_context()),
+            Ok(())
+        );
+    }
+}
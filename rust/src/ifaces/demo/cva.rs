@@ -0,0 +1,328 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Certified Value Asset: an RGB20-compatible asset paired with a single non-fungible certificate
+//! of issuance.
+//!
+//! The codex composes the fungible and the unique token libraries through separate verifiers:
+//! the value is issued in genesis and transferred by the fungible library, while the certificate
+//! is issued by the `certify` operation and transferred by the unique token library, compiled for
+//! the [`certificate_layout`]. Each library rejects the state of the other one, thus the value and
+//! the certificate are conserved by different transfers.
+//!
+//! The unique token issue doesn't spend anything, thus the codex can't prevent the certificate
+//! from being issued more than once; the `certificate` aggregator of the API fails in that case.
+
+use hypersonic::{
+    Aggregator, Codex, GlobalApi, Identity, Issuer, OwnedApi, RawBuilder, RawConvertor, Semantics,
+    StateArithm, StateBuilder, StateConvertor, StateValue, SubAggregator,
+};
+use ifaces::Rgb21Types;
+use strict_types::SemId;
+use zkaluvm::alu::CoreConfig;
+use zkaluvm::FIELD_ORDER_SECP;
+
+use crate::api_builder::IssuerFeatures;
+use crate::rgb20::fna;
+use crate::scripts::{
+    FieldLayout, ScriptVersion, FN_FUNGIBLE_BLANK, FN_FUNGIBLE_ISSUE, FN_FUNGIBLE_TRANSFER,
+    FN_RGB21_ISSUE, FN_UNIQUE_TRANSFER,
+};
+use crate::{
+    scripts, ERRNO_GLOBAL_FRACTIONS_NOT_ONE, ERRNO_INVALID_TOKEN_ID, ERRNO_NO_INPUT,
+    ERRNO_NO_OUTPUT, ERRNO_NO_TOKEN_ID, ERRNO_OWNED_FRACTIONS_NOT_ONE, ERRNO_TOKEN_EXCESS,
+    ERRNO_TOKEN_EXCESS_IN, ERRNO_TOKEN_EXCESS_OUT, ERRNO_ZERO_FRACTION, G_CERTIFICATE,
+    G_CERTIFICATE_FRACTIONS, G_CERTIFICATE_NAME, G_CERTIFICATE_TICKER, O_CERTIFICATE, PANDORA,
+};
+
+pub const VERIFIER_GENESIS: u16 = fna::VERIFIER_GENESIS;
+pub const VERIFIER_TRANSFER: u16 = fna::VERIFIER_TRANSFER;
+pub const VERIFIER_BLANK: u16 = fna::VERIFIER_BLANK;
+pub const VERIFIER_CERTIFY: u16 = 3;
+pub const VERIFIER_CERTIFICATE_TRANSFER: u16 = 4;
+
+/// Layout of the certificate state, for which the unique token library is compiled.
+///
+/// The certificate specification and the certificate itself use their own state types, such that
+/// they don't mix with the specification and the balances of the asset.
+pub fn certificate_layout() -> FieldLayout {
+    FieldLayout {
+        name: G_CERTIFICATE_NAME,
+        ticker: G_CERTIFICATE_TICKER,
+        precision: G_CERTIFICATE_FRACTIONS,
+        nft: G_CERTIFICATE,
+        amount: O_CERTIFICATE,
+        ..FieldLayout::default()
+    }
+}
+
+/// Constructs owned state for the certificate allocation, which has a single fraction.
+pub fn certificate_allocation(number: u64) -> StateValue {
+    StateValue::Triple {
+        first: O_CERTIFICATE.into(),
+        second: number.into(),
+        third: 1u64.into(),
+    }
+}
+
+pub fn issuer() -> Issuer {
+    let types = Rgb21Types::new();
+    let codex = codex();
+    let mut api = fna::api_v(ScriptVersion::LATEST, codex.codex_id());
+    for (name, sem_id, ty) in [
+        (
+            vname!("certificateTicker"),
+            types.get("RGBContract.Ticker"),
+            G_CERTIFICATE_TICKER,
+        ),
+        (
+            vname!("certificateName"),
+            types.get("RGBContract.AssetName"),
+            G_CERTIFICATE_NAME,
+        ),
+        (
+            vname!("certificateFractions"),
+            types.get("RGB21.TokenFractions"),
+            G_CERTIFICATE_FRACTIONS,
+        ),
+    ] {
+        api.global
+            .insert(
+                name,
+                GlobalApi {
+                    published: true,
+                    sem_id,
+                    convertor: StateConvertor::TypedEncoder(ty),
+                    builder: StateBuilder::TypedEncoder(ty),
+                    raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
+                    raw_builder: RawBuilder::StrictEncode(SemId::unit()),
+                },
+            )
+            .expect("too many globals");
+    }
+    api.global
+        .insert(
+            vname!("certificate"),
+            GlobalApi {
+                published: true,
+                sem_id: types.get("RGB21.TokenNo"),
+                convertor: StateConvertor::TypedFieldEncoder(G_CERTIFICATE),
+                builder: StateBuilder::TypedFieldEncoder(G_CERTIFICATE),
+                raw_convertor: RawConvertor::StrictDecode(types.get("RGB21.NftSpec")),
+                raw_builder: RawBuilder::StrictEncode(types.get("RGB21.NftSpec")),
+            },
+        )
+        .expect("too many globals");
+    api.owned
+        .insert(
+            vname!("certificate"),
+            OwnedApi {
+                sem_id: types.get("RGB21.OwnedNft"),
+                arithmetics: StateArithm::NonFungible,
+                convertor: StateConvertor::TypedFieldEncoder(O_CERTIFICATE),
+                builder: StateBuilder::TypedFieldEncoder(O_CERTIFICATE),
+                witness_sem_id: SemId::unit(),
+                witness_builder: StateBuilder::Unit,
+            },
+        )
+        .expect("too many owned states");
+    api.aggregators
+        .insert(
+            vname!("certificate"),
+            Aggregator::Take(SubAggregator::TheOnly(vname!("certificate"))),
+        )
+        .expect("too many aggregators");
+    api.verifiers
+        .insert(vname!("certify"), VERIFIER_CERTIFY)
+        .expect("too many verifiers");
+    api.verifiers
+        .insert(vname!("transferCertificate"), VERIFIER_CERTIFICATE_TRANSFER)
+        .expect("too many verifiers");
+    for (errno, message) in [
+        (
+            ERRNO_GLOBAL_FRACTIONS_NOT_ONE,
+            tiny_s!("the certificate must have exactly one fraction"),
+        ),
+        (
+            ERRNO_INVALID_TOKEN_ID,
+            tiny_s!("invalid certificate number data"),
+        ),
+        (ERRNO_NO_TOKEN_ID, tiny_s!("no certificate number is set")),
+        (
+            ERRNO_TOKEN_EXCESS,
+            tiny_s!("the certification must declare a single certificate"),
+        ),
+        (
+            ERRNO_NO_INPUT,
+            tiny_s!("the certificate transfer must spend the certificate"),
+        ),
+        (
+            ERRNO_NO_OUTPUT,
+            tiny_s!("the certificate must be allocated"),
+        ),
+        (
+            ERRNO_TOKEN_EXCESS_IN,
+            tiny_s!("the certificate transfer must spend only the certificate"),
+        ),
+        (
+            ERRNO_TOKEN_EXCESS_OUT,
+            tiny_s!("the certificate must be allocated to a single output, keeping its number"),
+        ),
+        (
+            ERRNO_OWNED_FRACTIONS_NOT_ONE,
+            tiny_s!("the certificate allocation must hold exactly one fraction"),
+        ),
+        (
+            ERRNO_ZERO_FRACTION,
+            tiny_s!("the certificate allocation must hold a non-zero number of fractions"),
+        ),
+    ] {
+        api.errors.insert(errno, message).expect("too many errors");
+    }
+
+    let layout = certificate_layout();
+    let semantics = Semantics {
+        version: 0,
+        default: api,
+        custom: none!(),
+        codex_libs: small_bset![
+            scripts::shared_lib().into_lib(),
+            scripts::fungible().into_lib(),
+            scripts::shared_lib_with(layout).into_lib(),
+            scripts::nft_lib_with(layout).into_lib(),
+            scripts::unique_with(layout).into_lib(),
+        ],
+        api_libs: none!(),
+        types: types.type_system(),
+    };
+    Issuer::new(codex, semantics).expect("invalid issuer")
+}
+
+pub fn codex() -> Codex {
+    let fungible = scripts::fungible();
+    let unique = scripts::unique_with(certificate_layout());
+    Codex {
+        name: tiny_s!("Certified Value Asset"),
+        developer: Identity::from(PANDORA),
+        version: default!(),
+        features: none!(),
+        timestamp: 1732529307,
+        field_order: FIELD_ORDER_SECP,
+        input_config: IssuerFeatures::NONE.input_config(),
+        verification_config: CoreConfig::default(),
+        verifiers: tiny_bmap! {
+            VERIFIER_GENESIS => fungible.routine(FN_FUNGIBLE_ISSUE),
+            VERIFIER_TRANSFER => fungible.routine(FN_FUNGIBLE_TRANSFER),
+            VERIFIER_BLANK => fungible.routine(FN_FUNGIBLE_BLANK),
+            VERIFIER_CERTIFY => unique.routine(FN_RGB21_ISSUE),
+            VERIFIER_CERTIFICATE_TRANSFER => unique.routine(FN_UNIQUE_TRANSFER),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::StateData;
+
+    use super::*;
+    use crate::api_builder::{IssuerError, IssuerValidate};
+    use crate::test_helpers::{fungible_ctx, TestContext};
+    use crate::{
+        ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT, G_NAME, G_PRECISION,
+        G_SUPPLY, G_TICKER, O_AMOUNT,
+    };
+
+    #[test]
+    fn certified_issue() {
+        let issuer = issuer();
+        let globals = [
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 8u8),
+            StateData::new(G_SUPPLY, 1000u64),
+        ];
+        let genesis = fungible_ctx(&[], &[600, 400]).with_globals(globals.clone());
+        assert_eq!(issuer.validate_genesis(&genesis.as_context()), Ok(()));
+
+        // The value issue doesn't accept the certificate
+        let balance = StateValue::new(O_AMOUNT, 1000u64);
+        let mixed =
+            TestContext::with(&[], &[balance, certificate_allocation(7)]).with_globals(globals);
+        assert_eq!(
+            issuer.validate_genesis(&mixed.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_UNEXPECTED_OWNED_TYPE_OUT)))
+        );
+
+        let spec = [
+            StateData::new(G_CERTIFICATE_TICKER, 0u8),
+            StateData::new(G_CERTIFICATE_NAME, 0u8),
+            StateData::new(G_CERTIFICATE_FRACTIONS, 1u64),
+            StateData::new(G_CERTIFICATE, 7u64),
+        ];
+        let certify =
+            TestContext::with(&[], &[certificate_allocation(7)]).with_globals(spec.clone());
+        assert_eq!(
+            issuer.validate_op(VERIFIER_CERTIFY, &certify.as_context()),
+            Ok(())
+        );
+        let duplicated =
+            TestContext::with(&[], &[certificate_allocation(7), certificate_allocation(7)])
+                .with_globals(spec);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_CERTIFY, &duplicated.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_TOKEN_EXCESS_OUT)))
+        );
+    }
+
+    #[test]
+    fn certified_transfer() {
+        let issuer = issuer();
+        let transfer = fungible_ctx(&[1000], &[600, 400]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER, &transfer.as_context()),
+            Ok(())
+        );
+        let certificate = certificate_allocation(7);
+        let transfer = TestContext::with(&[certificate], &[certificate]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_CERTIFICATE_TRANSFER, &transfer.as_context()),
+            Ok(())
+        );
+
+        // The certificate can't be dropped, changed or moved together with the value
+        let dropped = TestContext::with(&[certificate], &[]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_CERTIFICATE_TRANSFER, &dropped.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_NO_OUTPUT)))
+        );
+        let changed = TestContext::with(&[certificate], &[certificate_allocation(8)]);
+        assert!(issuer
+            .validate_op(VERIFIER_CERTIFICATE_TRANSFER, &changed.as_context())
+            .is_err());
+        let balance = StateValue::new(O_AMOUNT, 1000u64);
+        let mixed = TestContext::with(&[balance, certificate], &[balance, certificate]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER, &mixed.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_UNEXPECTED_OWNED_TYPE_IN)))
+        );
+    }
+}
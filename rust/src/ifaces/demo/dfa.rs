@@ -0,0 +1,163 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Delegated Fungible Asset: an RGB20-compatible asset which holders may grant operator
+//! allowances (see [`FN_FUNGIBLE_APPROVE`]), spent by the operators with delegated transfers (see
+//! [`FN_FUNGIBLE_TRANSFER_FROM`]).
+//!
+//! An allowance is an escrow: the approved amount moves from the holder balances into the
+//! allowance, assigned to a seal of the operator, which thus gets the authority to spend it.
+//!
+//! The allowance state ([`O_ALLOWANCE`]) pairs an amount with a grantee commitment, which has no
+//! matching type in the common interface types; thus allowances are not exposed by the API and
+//! must be constructed by the wallets directly.
+
+use hypersonic::{Codex, Identity, Issuer, Semantics};
+use ifaces::CommonTypes;
+use zkaluvm::alu::CoreConfig;
+use zkaluvm::FIELD_ORDER_SECP;
+
+use crate::api_builder::IssuerFeatures;
+use crate::rgb20::fna;
+use crate::scripts::{
+    ScriptVersion, FN_FUNGIBLE_APPROVE, FN_FUNGIBLE_BLANK, FN_FUNGIBLE_ISSUE, FN_FUNGIBLE_TRANSFER,
+    FN_FUNGIBLE_TRANSFER_FROM,
+};
+use crate::{
+    scripts, ERRNO_ALLOWANCE_EXCEEDED, ERRNO_INVALID_ALLOWANCE, ERRNO_NO_ALLOWANCE, O_ALLOWANCE,
+    PANDORA,
+};
+
+pub const VERIFIER_GENESIS: u16 = fna::VERIFIER_GENESIS;
+pub const VERIFIER_TRANSFER: u16 = fna::VERIFIER_TRANSFER;
+pub const VERIFIER_BLANK: u16 = fna::VERIFIER_BLANK;
+pub const VERIFIER_APPROVE: u16 = 4;
+pub const VERIFIER_TRANSFER_FROM: u16 = 5;
+
+pub fn issuer() -> Issuer {
+    let types = CommonTypes::new();
+    let codex = codex();
+    let mut api = fna::api_v(ScriptVersion::LATEST, codex.codex_id());
+    api.verifiers
+        .insert(vname!("approve"), VERIFIER_APPROVE)
+        .expect("too many verifiers");
+    api.verifiers
+        .insert(vname!("transferFrom"), VERIFIER_TRANSFER_FROM)
+        .expect("too many verifiers");
+    api.errors
+        .insert(
+            ERRNO_ALLOWANCE_EXCEEDED,
+            tiny_s!("the remaining operator allowance exceeds the spent one"),
+        )
+        .expect("too many errors");
+    api.errors
+        .insert(
+            ERRNO_NO_ALLOWANCE,
+            tiny_s!("the delegated transfer must spend an operator allowance"),
+        )
+        .expect("too many errors");
+    api.errors
+        .insert(
+            ERRNO_INVALID_ALLOWANCE,
+            tiny_s!("an operator allowance must have an amount and a grantee, and a delegated transfer may spend and return only a single allowance of the same grantee"),
+        )
+        .expect("too many errors");
+
+    let semantics = Semantics {
+        version: 0,
+        default: api,
+        custom: none!(),
+        codex_libs: small_bset![
+            scripts::shared_lib().into_lib(),
+            scripts::fungible().into_lib(),
+        ],
+        api_libs: none!(),
+        types: types.type_system(),
+    };
+    Issuer::new(codex, semantics).expect("invalid issuer")
+}
+
+pub fn codex() -> Codex {
+    let lib = scripts::fungible();
+    Codex {
+        name: tiny_s!("Delegated Fungible Asset"),
+        developer: Identity::from(PANDORA),
+        version: default!(),
+        features: none!(),
+        timestamp: 1732529307,
+        field_order: FIELD_ORDER_SECP,
+        input_config: IssuerFeatures::NONE.input_config(),
+        verification_config: CoreConfig::default(),
+        verifiers: tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(FN_FUNGIBLE_ISSUE),
+            VERIFIER_TRANSFER => lib.routine(FN_FUNGIBLE_TRANSFER),
+            VERIFIER_BLANK => lib.routine(FN_FUNGIBLE_BLANK),
+            VERIFIER_APPROVE => lib.routine(FN_FUNGIBLE_APPROVE),
+            VERIFIER_TRANSFER_FROM => lib.routine(FN_FUNGIBLE_TRANSFER_FROM),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::StateValue;
+
+    use super::*;
+    use crate::api_builder::{IssuerError, IssuerValidate};
+    use crate::test_helpers::TestContext;
+    use crate::{ERRNO_SUM_MISMATCH, O_AMOUNT};
+
+    fn allowance(amount: u64) -> StateValue {
+        StateValue::Triple {
+            first: O_ALLOWANCE.into(),
+            second: amount.into(),
+            third: 1u64.into(),
+        }
+    }
+
+    #[test]
+    fn transfer_from() {
+        let issuer = issuer();
+        let balance = StateValue::new(O_AMOUNT, 500u64);
+
+        let approve = TestContext::with(&[balance], &[allowance(500)]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_APPROVE, &approve.as_context()),
+            Ok(())
+        );
+        let within = TestContext::with(&[allowance(500)], &[balance]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER_FROM, &within.as_context()),
+            Ok(())
+        );
+        let over = TestContext::with(&[allowance(400)], &[balance]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER_FROM, &over.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_SUM_MISMATCH)))
+        );
+        let grown = TestContext::with(&[balance, allowance(400)], &[allowance(900)]);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER_FROM, &grown.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_ALLOWANCE_EXCEEDED)))
+        );
+    }
+}
@@ -0,0 +1,121 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Lot-traded Fungible Asset: an RGB20-compatible asset which allocations must be multiples of the
+//! lot size declared in genesis (see [`FN_FUNGIBLE_TRANSFER_LOTS`]).
+
+use hypersonic::{Codex, Identity, Issuer, Semantics};
+use ifaces::CommonTypes;
+use zkaluvm::alu::CoreConfig;
+use zkaluvm::FIELD_ORDER_SECP;
+
+use crate::api_builder::{typed_global, IssuerFeatures};
+use crate::rgb20::fna;
+use crate::scripts::{
+    ScriptVersion, FN_FUNGIBLE_BLANK, FN_FUNGIBLE_ISSUE_LOTS, FN_FUNGIBLE_TRANSFER_LOTS,
+};
+use crate::{scripts, ERRNO_NON_LOT_OUTPUT, ERRNO_NO_LOT_SIZE, G_LOT_SIZE, PANDORA};
+
+pub const VERIFIER_GENESIS: u16 = fna::VERIFIER_GENESIS;
+pub const VERIFIER_TRANSFER: u16 = fna::VERIFIER_TRANSFER;
+pub const VERIFIER_BLANK: u16 = fna::VERIFIER_BLANK;
+
+pub fn issuer() -> Issuer {
+    let types = CommonTypes::new();
+    let codex = codex();
+    let mut api = fna::api_v(ScriptVersion::LATEST, codex.codex_id());
+    api.global
+        .insert(
+            vname!("lotSize"),
+            typed_global(types.get("RGBContract.Amount"), G_LOT_SIZE),
+        )
+        .expect("too many globals");
+    api.errors
+        .insert(
+            ERRNO_NON_LOT_OUTPUT,
+            tiny_s!("each allocation must be a multiple of the lot size"),
+        )
+        .expect("too many errors");
+    api.errors
+        .insert(
+            ERRNO_NO_LOT_SIZE,
+            tiny_s!("the lot size must be a non-zero amount declared after the issued supply and provided to each transfer"),
+        )
+        .expect("too many errors");
+
+    let semantics = Semantics {
+        version: 0,
+        default: api,
+        custom: none!(),
+        codex_libs: small_bset![
+            scripts::shared_lib().into_lib(),
+            scripts::fungible().into_lib(),
+        ],
+        api_libs: none!(),
+        types: types.type_system(),
+    };
+    Issuer::new(codex, semantics).expect("invalid issuer")
+}
+
+pub fn codex() -> Codex {
+    let lib = scripts::fungible();
+    Codex {
+        name: tiny_s!("Lot-traded Fungible Asset"),
+        developer: Identity::from(PANDORA),
+        version: default!(),
+        features: none!(),
+        timestamp: 1732529307,
+        field_order: FIELD_ORDER_SECP,
+        input_config: IssuerFeatures::NONE.input_config(),
+        verification_config: CoreConfig::default(),
+        verifiers: tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(FN_FUNGIBLE_ISSUE_LOTS),
+            VERIFIER_TRANSFER => lib.routine(FN_FUNGIBLE_TRANSFER_LOTS),
+            VERIFIER_BLANK => lib.routine(FN_FUNGIBLE_BLANK),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::StateValue;
+
+    use super::*;
+    use crate::api_builder::{IssuerError, IssuerValidate};
+    use crate::test_helpers::fungible_ctx;
+
+    #[test]
+    fn transfer_in_lots() {
+        let issuer = issuer();
+        let lot_size = [StateValue::new(G_LOT_SIZE, 100u64)];
+        let aligned = fungible_ctx(&[500], &[200, 300]).with_global_inputs(lot_size);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER, &aligned.as_context()),
+            Ok(())
+        );
+        let misaligned = fungible_ctx(&[500], &[250, 250]).with_global_inputs(lot_size);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_TRANSFER, &misaligned.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_NON_LOT_OUTPUT)))
+        );
+    }
+}
@@ -0,0 +1,188 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Retirable Fungible Asset: an RGB20-compatible asset which may be retired as a whole (see
+//! [`FN_FUNGIBLE_RETIRE`]), for instance when a regulated asset is closed by its issuer.
+//!
+//! Only the owner of the `retireRight` assigned in genesis (see [`FN_FUNGIBLE_ISSUE_RETIRABLE`])
+//! may retire the asset.
+//!
+//! The retirement publishes the `retired` global, which has no verifiable value and may keep the
+//! reason of the retirement in the raw (non-verifiable) state.
+
+use hypersonic::{
+    Codex, GlobalApi, Identity, Issuer, OwnedApi, RawBuilder, RawConvertor, Semantics, StateArithm,
+    StateBuilder, StateConvertor,
+};
+use ifaces::CommonTypes;
+use strict_types::SemId;
+use zkaluvm::alu::CoreConfig;
+use zkaluvm::FIELD_ORDER_SECP;
+
+use crate::api_builder::IssuerFeatures;
+use crate::rgb20::fna;
+use crate::scripts::{
+    ScriptVersion, FN_FUNGIBLE_BLANK, FN_FUNGIBLE_ISSUE_RETIRABLE, FN_FUNGIBLE_RETIRE,
+    FN_FUNGIBLE_TRANSFER,
+};
+use crate::{
+    scripts, ERRNO_INVALID_RETIRE_RIGHT, ERRNO_NO_RETIRED, ERRNO_NO_RETIRE_RIGHT,
+    ERRNO_RETIRE_HAS_OUTPUTS, G_RETIRED, O_RETIRE_RIGHT, PANDORA,
+};
+
+pub const VERIFIER_GENESIS: u16 = fna::VERIFIER_GENESIS;
+pub const VERIFIER_TRANSFER: u16 = fna::VERIFIER_TRANSFER;
+pub const VERIFIER_BLANK: u16 = fna::VERIFIER_BLANK;
+pub const VERIFIER_RETIRE: u16 = 4;
+
+pub fn issuer() -> Issuer {
+    let types = CommonTypes::new();
+    let codex = codex();
+    let mut api = fna::api_v(ScriptVersion::LATEST, codex.codex_id());
+    api.global
+        .insert(
+            vname!("retired"),
+            GlobalApi {
+                published: true,
+                sem_id: SemId::unit(),
+                convertor: StateConvertor::TypedEncoder(G_RETIRED),
+                builder: StateBuilder::TypedEncoder(G_RETIRED),
+                raw_convertor: RawConvertor::StrictDecode(types.get("RGBContract.Details")),
+                raw_builder: RawBuilder::StrictEncode(types.get("RGBContract.Details")),
+            },
+        )
+        .expect("too many globals");
+    api.owned
+        .insert(
+            vname!("retireRight"),
+            OwnedApi {
+                sem_id: SemId::unit(),
+                arithmetics: StateArithm::NonFungible,
+                convertor: StateConvertor::TypedEncoder(O_RETIRE_RIGHT),
+                builder: StateBuilder::TypedEncoder(O_RETIRE_RIGHT),
+                witness_sem_id: SemId::unit(),
+                witness_builder: StateBuilder::Unit,
+            },
+        )
+        .expect("too many owned states");
+    api.verifiers
+        .insert(vname!("retire"), VERIFIER_RETIRE)
+        .expect("too many verifiers");
+    api.errors
+        .insert(
+            ERRNO_RETIRE_HAS_OUTPUTS,
+            tiny_s!("the retirement must not define any owned state"),
+        )
+        .expect("too many errors");
+    api.errors
+        .insert(
+            ERRNO_NO_RETIRED,
+            tiny_s!("the retirement must publish the retirement marker as its only global state"),
+        )
+        .expect("too many errors");
+    api.errors
+        .insert(
+            ERRNO_NO_RETIRE_RIGHT,
+            tiny_s!("the issue must assign and the retirement must spend the issuer right to retire the asset"),
+        )
+        .expect("too many errors");
+    api.errors
+        .insert(
+            ERRNO_INVALID_RETIRE_RIGHT,
+            tiny_s!("the issuer right to retire the asset must be single and have no value"),
+        )
+        .expect("too many errors");
+
+    let semantics = Semantics {
+        version: 0,
+        default: api,
+        custom: none!(),
+        codex_libs: small_bset![
+            scripts::shared_lib().into_lib(),
+            scripts::fungible().into_lib(),
+        ],
+        api_libs: none!(),
+        types: types.type_system(),
+    };
+    Issuer::new(codex, semantics).expect("invalid issuer")
+}
+
+pub fn codex() -> Codex {
+    let lib = scripts::fungible();
+    Codex {
+        name: tiny_s!("Retirable Fungible Asset"),
+        developer: Identity::from(PANDORA),
+        version: default!(),
+        features: none!(),
+        timestamp: 1732529307,
+        field_order: FIELD_ORDER_SECP,
+        input_config: IssuerFeatures::NONE.input_config(),
+        verification_config: CoreConfig::default(),
+        verifiers: tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(FN_FUNGIBLE_ISSUE_RETIRABLE),
+            VERIFIER_TRANSFER => lib.routine(FN_FUNGIBLE_TRANSFER),
+            VERIFIER_BLANK => lib.routine(FN_FUNGIBLE_BLANK),
+            VERIFIER_RETIRE => lib.routine(FN_FUNGIBLE_RETIRE),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::{StateData, StateValue};
+
+    use super::*;
+    use crate::api_builder::{IssuerError, IssuerValidate};
+    use crate::test_helpers::{fungible_ctx, TestContext};
+    use crate::O_AMOUNT;
+
+    #[test]
+    fn retire() {
+        let issuer = issuer();
+        let marker = [StateData::new(G_RETIRED, 0u8)];
+        let right = StateValue::Single {
+            first: O_RETIRE_RIGHT.into(),
+        };
+        let balances = [
+            StateValue::new(O_AMOUNT, 100u64),
+            StateValue::new(O_AMOUNT, 900u64),
+        ];
+
+        let retire =
+            TestContext::with(&[balances[0], right, balances[1]], &[]).with_globals(marker.clone());
+        assert_eq!(
+            issuer.validate_op(VERIFIER_RETIRE, &retire.as_context()),
+            Ok(())
+        );
+        let unauthorized = fungible_ctx(&[100, 900], &[]).with_globals(marker.clone());
+        assert_eq!(
+            issuer.validate_op(VERIFIER_RETIRE, &unauthorized.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_NO_RETIRE_RIGHT)))
+        );
+        let leftover = TestContext::with(&[balances[0], right, balances[1]], &balances[1..])
+            .with_globals(marker);
+        assert_eq!(
+            issuer.validate_op(VERIFIER_RETIRE, &leftover.as_context()),
+            Err(IssuerError::Rejected(Some(ERRNO_RETIRE_HAS_OUTPUTS)))
+        );
+    }
+}
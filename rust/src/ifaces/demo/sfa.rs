@@ -0,0 +1,96 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Sealable Fungible Asset: an RGB20-compatible asset which allocations may be permanently sealed
+//! by assigning [`FN_FUNGIBLE_LOCK_UNSPENDABLE`] as their lock script.
+//!
+//! Sealing is irreversible: a sealed allocation can never be spent, so its amount is taken out of
+//! circulation forever.
+
+use hypersonic::{Codex, Identity, Issuer, Semantics};
+use ifaces::CommonTypes;
+use zkaluvm::alu::{CoreConfig, LibSite};
+use zkaluvm::FIELD_ORDER_SECP;
+
+use crate::api_builder::IssuerFeatures;
+use crate::rgb20::fna;
+use crate::scripts::{
+    ScriptVersion, FN_FUNGIBLE_BLANK, FN_FUNGIBLE_ISSUE, FN_FUNGIBLE_LOCK_UNSPENDABLE,
+    FN_FUNGIBLE_TRANSFER,
+};
+use crate::{scripts, ERRNO_SEALED_INPUT, PANDORA};
+
+pub const VERIFIER_GENESIS: u16 = fna::VERIFIER_GENESIS;
+pub const VERIFIER_TRANSFER: u16 = fna::VERIFIER_TRANSFER;
+pub const VERIFIER_BLANK: u16 = fna::VERIFIER_BLANK;
+
+pub fn issuer() -> Issuer {
+    let types = CommonTypes::new();
+    let codex = codex();
+    let mut api = fna::api_v(ScriptVersion::LATEST, codex.codex_id());
+    api.errors
+        .insert(
+            ERRNO_SEALED_INPUT,
+            tiny_s!("the allocation is permanently sealed and can never be spent"),
+        )
+        .expect("too many errors");
+
+    let semantics = Semantics {
+        version: 0,
+        default: api,
+        custom: none!(),
+        codex_libs: small_bset![
+            scripts::shared_lib().into_lib(),
+            scripts::fungible().into_lib(),
+        ],
+        api_libs: none!(),
+        types: types.type_system(),
+    };
+    Issuer::new(codex, semantics).expect("invalid issuer")
+}
+
+pub fn codex() -> Codex {
+    let lib = scripts::fungible();
+    Codex {
+        name: tiny_s!("Sealable Fungible Asset"),
+        developer: Identity::from(PANDORA),
+        version: default!(),
+        features: none!(),
+        timestamp: 1732529307,
+        field_order: FIELD_ORDER_SECP,
+        // Lock scripts must be able to run for the sealed allocations to fail
+        input_config: IssuerFeatures::LOCK_SCRIPTS.input_config(),
+        verification_config: CoreConfig::default(),
+        verifiers: tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(FN_FUNGIBLE_ISSUE),
+            VERIFIER_TRANSFER => lib.routine(FN_FUNGIBLE_TRANSFER),
+            VERIFIER_BLANK => lib.routine(FN_FUNGIBLE_BLANK),
+        },
+    }
+}
+
+/// Returns the lock script which must be assigned to an allocation to seal it.
+///
+/// The sealed allocation can never be spent afterwards (see [`FN_FUNGIBLE_LOCK_UNSPENDABLE`]).
+pub fn seal() -> LibSite {
+    scripts::fungible().routine(FN_FUNGIBLE_LOCK_UNSPENDABLE)
+}
@@ -20,6 +20,8 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
+pub mod api_builder;
+pub mod demo;
 pub mod rgb20;
 pub mod rgb21;
 pub mod rgb25;
@@ -20,150 +20,221 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
+use amplify::confinement::TinyOrdMap;
 use hypersonic::{
-    Aggregator, Api, CallState, Codex, CodexId, GlobalApi, Identity, Issuer, OwnedApi, RawBuilder,
-    RawConvertor, Semantics, StateArithm, StateBuilder, StateConvertor, SubAggregator,
+    Aggregator, Api, Codex, CodexId, GlobalApi, Identity, Issuer, OwnedApi, RawBuilder,
+    RawConvertor, StateArithm, StateBuilder, StateConvertor, SubAggregator,
 };
 use ifaces::CommonTypes;
 use strict_types::SemId;
 use zkaluvm::alu::CoreConfig;
 use zkaluvm::FIELD_ORDER_SECP;
 
-use crate::scripts::{FN_FUNGIBLE_ISSUE, FN_FUNGIBLE_TRANSFER};
+use crate::api_builder::{asset_spec_globals, reference_global};
+use crate::api_builder::{build_issuer, conforms, transfer_call, IssuerFeatures};
+use crate::scripts::{
+    v0, ScriptVersion, FN_FUNGIBLE_BLANK, FN_FUNGIBLE_ISSUE, FN_FUNGIBLE_TRANSFER,
+};
 use crate::{
-    scripts, ERRNO_INVALID_BALANCE_IN, ERRNO_INVALID_BALANCE_OUT, ERRNO_INVALID_PRECISION,
-    ERRNO_NO_ISSUED, ERRNO_NO_NAME, ERRNO_NO_PRECISION, ERRNO_NO_TICKER, ERRNO_PRECISION_OVERFLOW,
-    ERRNO_SUM_ISSUE_MISMATCH, ERRNO_SUM_MISMATCH, ERRNO_UNEXPECTED_GLOBAL,
-    ERRNO_UNEXPECTED_GLOBAL_IN, ERRNO_UNEXPECTED_GLOBAL_OUT, ERRNO_UNEXPECTED_OWNED_IN,
-    ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT, G_NAME, G_PRECISION, G_SUPPLY,
-    G_TICKER, O_AMOUNT, PANDORA,
+    scripts, ERRNO_EMPTY_NAME, ERRNO_EMPTY_STATE, ERRNO_EMPTY_TICKER, ERRNO_INVALID_BALANCE_IN,
+    ERRNO_INVALID_BALANCE_OUT, ERRNO_INVALID_DETAILS, ERRNO_INVALID_PRECISION,
+    ERRNO_INVALID_REFERENCE, ERRNO_MULTIPLE_PRECISION, ERRNO_MULTIPLE_SUPPLY, ERRNO_NO_ISSUED,
+    ERRNO_NO_NAME, ERRNO_NO_PRECISION, ERRNO_NO_TICKER, ERRNO_PRECISION_OVERFLOW,
+    ERRNO_SUM_ISSUE_MISMATCH, ERRNO_SUM_MISMATCH, ERRNO_SUM_OVERFLOW, ERRNO_SUPPLY_MISPLACED,
+    ERRNO_UNEXPECTED_GLOBAL, ERRNO_UNEXPECTED_GLOBAL_IN, ERRNO_UNEXPECTED_GLOBAL_OUT,
+    ERRNO_UNEXPECTED_OWNED_IN, ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT,
+    ERRNO_ZERO_SUPPLY, G_RGB20_DETAILS, O_AMOUNT, PANDORA,
 };
 
 pub const VERIFIER_GENESIS: u16 = 0;
 pub const VERIFIER_TRANSFER: u16 = 1;
+pub const VERIFIER_BLANK: u16 = 2;
+
+/// Version of the scripts used by the released FNA codex.
+pub const SCRIPT_VERSION: ScriptVersion = ScriptVersion::V0;
 
+/// The released FNA issuer (see [`codex`]).
 pub fn issuer() -> Issuer {
+    issuer_v(SCRIPT_VERSION)
+}
+
+/// FNA issuer using the scripts of a specific version (see [`codex_v`]).
+pub fn issuer_v(version: ScriptVersion) -> Issuer {
     let types = CommonTypes::new();
-    let codex = codex();
-    let api = api(codex.codex_id());
-
-    let semantics = Semantics {
-        version: 0,
-        default: api,
-        custom: none!(),
-        codex_libs: small_bset![
-            scripts::shared_lib().into_lib(),
-            scripts::fungible().into_lib(),
+    build_issuer(
+        codex_v(version),
+        |codex_id| api_v(version, codex_id),
+        small_bset![
+            scripts::shared_lib_v(version).into_lib(),
+            scripts::fungible_v(version).into_lib(),
         ],
-        api_libs: none!(),
-        types: types.type_system(),
-    };
-    Issuer::new(codex, semantics).expect("invalid issuer")
+        types.type_system(),
+    )
+    .expect("API doesn't match the codex")
 }
 
+/// Canonical definition of the released FNA codex (also known as NIA, non-inflatable asset).
+///
+/// All other FNA entry points ([`issuer`], the issuer registry and the `fna` binary) delegate to
+/// this function, such that there is a single FNA contract.
 pub fn codex() -> Codex {
-    let lib = scripts::fungible();
+    codex_v(SCRIPT_VERSION)
+}
+
+/// FNA codex using the scripts of a specific version.
+///
+/// Only the [`ScriptVersion::V0`] codex is released: the codices of other versions commit to
+/// different libraries and verifiers, and thus have different ids, defining contracts which are
+/// incompatible with the released FNA.
+pub fn codex_v(version: ScriptVersion) -> Codex {
+    let lib = scripts::fungible_v(version);
+    let verifiers = match version {
+        ScriptVersion::V0 => tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(v0::FN_FUNGIBLE_ISSUE),
+            VERIFIER_TRANSFER => lib.routine(v0::FN_FUNGIBLE_TRANSFER),
+        },
+        ScriptVersion::V1 => tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(FN_FUNGIBLE_ISSUE),
+            VERIFIER_TRANSFER => lib.routine(FN_FUNGIBLE_TRANSFER),
+            VERIFIER_BLANK => lib.routine(FN_FUNGIBLE_BLANK),
+        },
+    };
     Codex {
         name: tiny_s!("Fungible Non-inflatable Asset"),
         developer: Identity::from(PANDORA),
+        // The codex version is reserved and zero; the script version is committed to by the
+        // library ids of the verifiers
         version: default!(),
         features: none!(),
         timestamp: 1732529307,
         field_order: FIELD_ORDER_SECP,
-        input_config: CoreConfig {
-            halt: true,
-            complexity_lim: Some(0),
-        },
+        input_config: IssuerFeatures::NONE.input_config(),
         verification_config: CoreConfig::default(),
-        verifiers: tiny_bmap! {
-            VERIFIER_GENESIS => lib.routine(FN_FUNGIBLE_ISSUE),
-            VERIFIER_TRANSFER => lib.routine(FN_FUNGIBLE_TRANSFER),
-        },
+        verifiers,
     }
 }
 
+/// API of the released FNA codex.
 pub fn api(codex_id: CodexId) -> Api {
+    api_v(SCRIPT_VERSION, codex_id)
+}
+
+/// FNA API matching the codex of a specific script version (see [`codex_v`]).
+pub fn api_v(version: ScriptVersion, codex_id: CodexId) -> Api {
     let types = CommonTypes::new();
 
-    Api {
-        codex_id,
-        conforms: tiny_bset!(20),
-        default_call: Some(CallState::with("transfer", "balance")),
-        global: tiny_bmap! {
-            vname!("ticker") => GlobalApi {
-                published: true,
-                sem_id: types.get("RGBContract.Ticker"),
-                convertor: StateConvertor::TypedEncoder(G_TICKER),
-                builder: StateBuilder::TypedEncoder(G_TICKER),
-                raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
-                raw_builder: RawBuilder::StrictEncode(SemId::unit())
-            },
-            vname!("name") => GlobalApi {
-                published: true,
-                sem_id: types.get("RGBContract.AssetName"),
-                convertor: StateConvertor::TypedEncoder(G_NAME),
-                builder: StateBuilder::TypedEncoder(G_NAME),
-                raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
-                raw_builder: RawBuilder::StrictEncode(SemId::unit())
-            },
-            vname!("precision") => GlobalApi {
-                published: true,
-                sem_id: types.get("RGBContract.Precision"),
-                convertor: StateConvertor::TypedEncoder(G_PRECISION),
-                builder: StateBuilder::TypedEncoder(G_PRECISION),
-                raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
-                raw_builder: RawBuilder::StrictEncode(SemId::unit())
-            },
-            vname!("issued") => GlobalApi {
+    let mut global = asset_spec_globals(&types);
+    let mut verifiers = tiny_bmap! {
+        vname!("issue") => VERIFIER_GENESIS,
+        vname!("transfer") => VERIFIER_TRANSFER,
+        vname!("_") => VERIFIER_TRANSFER,
+    };
+    let mut errors = tiny_bmap! {
+        ERRNO_NO_TICKER => tiny_s!("no RGB20 ticker is set, or it is misplaced in the global state declaration (the ticker should be declared first)"),
+        ERRNO_NO_NAME => tiny_s!("no RGB20 asset name is set, or it is misplaced in the global state declaration (the name should be declared second)"),
+        ERRNO_NO_PRECISION => tiny_s!("no RGB20 precision is set, or it is misplaced in the global state declaration (the precision should be declared third)"),
+        ERRNO_INVALID_PRECISION => tiny_s!("invalid RGB20 ticket precision value"),
+        ERRNO_UNEXPECTED_OWNED_IN => tiny_s!("operation must have no inputs"),
+        ERRNO_UNEXPECTED_GLOBAL_IN => tiny_s!("operation must not use any global state"),
+        ERRNO_UNEXPECTED_GLOBAL_OUT => tiny_s!("operation must not declare any global state"),
+        ERRNO_INVALID_BALANCE_IN => tiny_s!("invalid value for an input balance"),
+        ERRNO_INVALID_BALANCE_OUT => tiny_s!("invalid value for an output balance"),
+        ERRNO_NO_ISSUED => tiny_s!("no information about the issued supply found"),
+        ERRNO_PRECISION_OVERFLOW => tiny_s!("the precision overflows the maximum value"),
+        ERRNO_SUM_ISSUE_MISMATCH => tiny_s!("the declared issued supply does not match the output balance"),
+        ERRNO_SUM_MISMATCH => tiny_s!("the sum of inputs is not equal to the sum of outputs"),
+        ERRNO_UNEXPECTED_GLOBAL => tiny_s!("unexpected global state"),
+        ERRNO_UNEXPECTED_OWNED_TYPE_IN => tiny_s!("unexpected operation input"),
+        ERRNO_UNEXPECTED_OWNED_TYPE_OUT => tiny_s!("unexpected operation output"),
+    };
+
+    if version >= ScriptVersion::V1 {
+        global.insert(
+            vname!("details"),
+            GlobalApi {
                 published: true,
-                sem_id: types.get("RGBContract.Amount"),
-                convertor: StateConvertor::TypedEncoder(G_SUPPLY),
-                builder: StateBuilder::TypedEncoder(G_SUPPLY),
-                raw_convertor: RawConvertor::StrictDecode(types.get("RGBContract.ProofOfReserves")),
-                raw_builder: RawBuilder::StrictEncode(types.get("RGBContract.ProofOfReserves"))
+                sem_id: SemId::unit(),
+                convertor: StateConvertor::TypedEncoder(G_RGB20_DETAILS),
+                builder: StateBuilder::TypedEncoder(G_RGB20_DETAILS),
+                raw_convertor: RawConvertor::StrictDecode(types.get("RGBContract.Details")),
+                raw_builder: RawBuilder::StrictEncode(types.get("RGBContract.Details")),
             },
-        },
-        owned: tiny_bmap! {
-            vname!("balance") => OwnedApi {
-                sem_id: types.get("RGBContract.Amount"),
-                arithmetics: StateArithm::Fungible,
-                convertor: StateConvertor::TypedEncoder(O_AMOUNT),
-                builder: StateBuilder::TypedEncoder(O_AMOUNT),
-                witness_sem_id: SemId::unit(),
-                witness_builder: StateBuilder::Unit
-            }
-        },
+        );
+        global.insert(vname!("reference"), reference_global(&types));
+
+        verifiers
+            .insert(vname!("_"), VERIFIER_BLANK)
+            .expect("too many verifiers");
+        for (errno, message) in [
+                (ERRNO_EMPTY_TICKER, tiny_s!("RGB20 ticker must not be empty")),
+                (ERRNO_EMPTY_NAME, tiny_s!("RGB20 asset name must not be empty")),
+                (ERRNO_MULTIPLE_PRECISION, tiny_s!("RGB20 ticker precision must be declared once")),
+                (ERRNO_SUM_OVERFLOW, tiny_s!("the sum of balances overflows 64 bits")),
+                (ERRNO_EMPTY_STATE, tiny_s!("operation input must have a state type")),
+                (ERRNO_ZERO_SUPPLY, tiny_s!("the issued supply must not be zero")),
+                (ERRNO_SUPPLY_MISPLACED, tiny_s!("the issued supply must be declared after the precision")),
+                (ERRNO_MULTIPLE_SUPPLY, tiny_s!("the issued supply must be declared only once")),
+                (ERRNO_INVALID_DETAILS, tiny_s!("invalid RGB20 details, or they are misplaced in the global state declaration (the details should be declared after the issued supply)")),
+                (ERRNO_INVALID_REFERENCE, tiny_s!("invalid issuer reference, or it is misplaced in the global state declaration (the reference should be declared last)")),
+        ] {
+            errors.insert(errno, message).expect("too many errors");
+        }
+    }
+
+    let owned = tiny_bmap! {
+        vname!("balance") => OwnedApi {
+            sem_id: types.get("RGBContract.Amount"),
+            arithmetics: StateArithm::Fungible,
+            convertor: StateConvertor::TypedEncoder(O_AMOUNT),
+            builder: StateBuilder::TypedEncoder(O_AMOUNT),
+            witness_sem_id: SemId::unit(),
+            witness_builder: StateBuilder::Unit
+        }
+    };
+
+    Api {
+        codex_id,
+        conforms: conforms(&[20]),
+        default_call: transfer_call(&owned),
+        global: TinyOrdMap::from_checked(global),
+        owned,
         aggregators: tiny_bmap! {
             vname!("name") => Aggregator::Take(SubAggregator::TheOnly(vname!("name"))),
             vname!("ticker") => Aggregator::Take(SubAggregator::TheOnly(vname!("ticker"))),
             vname!("precision") => Aggregator::Take(SubAggregator::TheOnly(vname!("precision"))),
             vname!("supply") => Aggregator::Take(SubAggregator::SumOrDefault(vname!("issued"))),
-            vname!("maxSupply") => Aggregator::Take(
-                SubAggregator::Copy(vname!("issuedSupply"))
-            ),
-        },
-        verifiers: tiny_bmap! {
-            vname!("issue") => VERIFIER_GENESIS,
-            vname!("transfer") => VERIFIER_TRANSFER,
-            vname!("_") => VERIFIER_TRANSFER,
-        },
-        errors: tiny_bmap! {
-            ERRNO_NO_TICKER => tiny_s!("no RGB20 ticker is set, or it is misplaced in the global state declaration (the ticker should be declared first)"),
-            ERRNO_NO_NAME => tiny_s!("no RGB20 asset name is set, or it is misplaced in the global state declaration (the name should be declared second)"),
-            ERRNO_NO_PRECISION => tiny_s!("no RGB20 precision is set, or it is misplaced in the global state declaration (the precision should be declared third)"),
-            ERRNO_INVALID_PRECISION => tiny_s!("invalid RGB20 ticket precision value"),
-            ERRNO_UNEXPECTED_OWNED_IN => tiny_s!("operation must have no inputs"),
-            ERRNO_UNEXPECTED_GLOBAL_IN => tiny_s!("operation must not use any global state"),
-            ERRNO_UNEXPECTED_GLOBAL_OUT => tiny_s!("operation must not declare any global state"),
-            ERRNO_INVALID_BALANCE_IN => tiny_s!("invalid value for an input balance"),
-            ERRNO_INVALID_BALANCE_OUT => tiny_s!("invalid value for an output balance"),
-            ERRNO_NO_ISSUED => tiny_s!("no information about the issued supply found"),
-            ERRNO_PRECISION_OVERFLOW => tiny_s!("the precision overflows the maximum value"),
-            ERRNO_SUM_ISSUE_MISMATCH => tiny_s!("the declared issued supply does not match the output balance"),
-            ERRNO_SUM_MISMATCH => tiny_s!("the sum of inputs is not equal to the sum of outputs"),
-            ERRNO_UNEXPECTED_GLOBAL => tiny_s!("unexpected global state"),
-            ERRNO_UNEXPECTED_OWNED_TYPE_IN => tiny_s!("unexpected operation input"),
-            ERRNO_UNEXPECTED_OWNED_TYPE_OUT => tiny_s!("unexpected operation output"),
+            vname!("maxSupply") => Aggregator::Take(SubAggregator::SumOrDefault(vname!("issued"))),
         },
+        verifiers,
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strict_types::{StrictVal, VariantName};
+
+    use super::*;
+
+    #[test]
+    fn supply_sums_issued() {
+        let api = api(codex().codex_id());
+        let types = CommonTypes::new().type_system();
+        let issued = [svnum!(1000u64), svnum!(500u64)];
+        let global = |name: &VariantName| -> Vec<&StrictVal> {
+            if name == &vname!("issued") {
+                issued.iter().collect()
+            } else {
+                vec![]
+            }
+        };
+        for name in [vname!("supply"), vname!("maxSupply")] {
+            let aggregator = api.aggregators.get(&name).expect("missing aggregator");
+            assert_eq!(
+                aggregator.aggregate(global, &none!(), &types),
+                Some(svnum!(1500u64)),
+                "{name}"
+            );
+        }
     }
 }
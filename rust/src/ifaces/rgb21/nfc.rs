@@ -20,36 +20,33 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
-use hypersonic::{Codex, Identity, Issuer, Semantics};
+use hypersonic::{Api, Codex, CodexId, Identity, Issuer, StateArithm};
 use ifaces::Rgb21Types;
 use zkaluvm::alu::CoreConfig;
 use zkaluvm::FIELD_ORDER_SECP;
 
-use super::{api, VERIFIER_GENESIS, VERIFIER_TRANSFER};
-use crate::{scripts, FN_DIVISIBLE_TRANSFER, FN_RGB21_ISSUE, PANDORA};
+use super::{VERIFIER_GENESIS, VERIFIER_TRANSFER};
+use crate::api_builder::{build_issuer, IssuerFeatures};
+use crate::{scripts, FN_FAC_TRANSFER, FN_RGB21_ISSUE, PANDORA};
 
 pub fn issuer() -> Issuer {
     let types = Rgb21Types::new();
-    let codex = codex();
-    let api = api(codex.codex_id());
-
-    let semantics = Semantics {
-        version: 0,
-        default: api,
-        custom: none!(),
-        codex_libs: small_bset![
+    build_issuer(
+        codex(),
+        api,
+        small_bset![
             scripts::shared_lib().into_lib(),
-            scripts::unique().into_lib(),
+            scripts::nft_lib().into_lib(),
+            scripts::divisible().into_lib(),
             scripts::collection().into_lib(),
         ],
-        api_libs: none!(),
-        types: types.type_system(),
-    };
-    Issuer::new(codex, semantics).expect("invalid issuer")
+        types.type_system(),
+    )
+    .expect("API doesn't match the codex")
 }
 
 fn codex() -> Codex {
-    let lib = scripts::unique();
+    let lib = scripts::collection();
     let codex = Codex {
         name: tiny_s!("Non-Fungible Asset Collection"),
         developer: Identity::from(PANDORA),
@@ -57,15 +54,17 @@ fn codex() -> Codex {
         features: none!(),
         timestamp: 1732529307,
         field_order: FIELD_ORDER_SECP,
-        input_config: CoreConfig {
-            halt: true,
-            complexity_lim: Some(0),
-        },
+        input_config: IssuerFeatures::NONE.input_config(),
         verification_config: CoreConfig::default(),
         verifiers: tiny_bmap! {
             VERIFIER_GENESIS => lib.routine(FN_RGB21_ISSUE),
-            VERIFIER_TRANSFER => lib.routine(FN_DIVISIBLE_TRANSFER),
+            VERIFIER_TRANSFER => lib.routine(FN_FAC_TRANSFER),
         },
     };
     codex
 }
+
+/// Each of the tokens is owned as a whole, thus the owned state uses non-fungible arithmetics.
+pub fn api(codex_id: CodexId) -> Api {
+    super::api(codex_id, StateArithm::NonFungible)
+}
@@ -20,35 +20,58 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
-use hypersonic::{Codex, Identity, Issuer, Semantics};
+use hypersonic::{Api, Codex, CodexId, Identity, Issuer, StateArithm};
 use ifaces::Rgb21Types;
 use zkaluvm::alu::CoreConfig;
 use zkaluvm::FIELD_ORDER_SECP;
 
-use super::{api, VERIFIER_GENESIS, VERIFIER_TRANSFER};
+use super::{VERIFIER_GENESIS, VERIFIER_TRANSFER};
+use crate::api_builder::{build_issuer, IssuerFeatures};
+use crate::scripts::{v0, ScriptVersion};
 use crate::{scripts, FN_RGB21_ISSUE, FN_UNIQUE_TRANSFER, PANDORA};
 
+/// Version of the scripts used by the released NFU codex.
+pub const SCRIPT_VERSION: ScriptVersion = ScriptVersion::V0;
+
+/// The released NFU issuer.
 pub fn issuer() -> Issuer {
-    let types = Rgb21Types::new();
-    let codex = codex();
-    let api = api(codex.codex_id());
+    issuer_v(SCRIPT_VERSION)
+}
 
-    let semantics = Semantics {
-        version: 0,
-        default: api,
-        custom: none!(),
-        codex_libs: small_bset![
-            scripts::shared_lib().into_lib(),
-            scripts::unique().into_lib(),
+/// NFU issuer using the scripts of a specific version.
+pub fn issuer_v(version: ScriptVersion) -> Issuer {
+    let types = Rgb21Types::new();
+    let libs = match version {
+        ScriptVersion::V0 => small_bset![
+            scripts::shared_lib_v(version).into_lib(),
+            scripts::unique_v(version).into_lib(),
+        ],
+        ScriptVersion::V1 => small_bset![
+            scripts::shared_lib_v(version).into_lib(),
+            scripts::nft_lib().into_lib(),
+            scripts::unique_v(version).into_lib(),
         ],
-        api_libs: none!(),
-        types: types.type_system(),
     };
-    Issuer::new(codex, semantics).expect("invalid issuer")
+    build_issuer(codex_v(version), api, libs, types.type_system())
+        .expect("API doesn't match the codex")
 }
 
-fn codex() -> Codex {
-    let lib = scripts::unique();
+/// NFU codex using the scripts of a specific version.
+///
+/// Only the [`ScriptVersion::V0`] codex is released; the codices of other versions have different
+/// ids.
+fn codex_v(version: ScriptVersion) -> Codex {
+    let lib = scripts::unique_v(version);
+    let verifiers = match version {
+        ScriptVersion::V0 => tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(v0::FN_RGB21_ISSUE),
+            VERIFIER_TRANSFER => lib.routine(v0::FN_UNIQUE_TRANSFER),
+        },
+        ScriptVersion::V1 => tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(FN_RGB21_ISSUE),
+            VERIFIER_TRANSFER => lib.routine(FN_UNIQUE_TRANSFER),
+        },
+    };
     let codex = Codex {
         name: tiny_s!("Non-Fungible Unique Asset"),
         developer: Identity::from(PANDORA),
@@ -56,15 +79,59 @@ fn codex() -> Codex {
         features: none!(),
         timestamp: 1732529307,
         field_order: FIELD_ORDER_SECP,
-        input_config: CoreConfig {
-            halt: true,
-            complexity_lim: Some(0),
-        },
+        input_config: IssuerFeatures::NONE.input_config(),
         verification_config: CoreConfig::default(),
-        verifiers: tiny_bmap! {
-            VERIFIER_GENESIS => lib.routine(FN_RGB21_ISSUE),
-            VERIFIER_TRANSFER => lib.routine(FN_UNIQUE_TRANSFER),
-        },
+        verifiers,
     };
     codex
 }
+
+/// Each of the tokens is owned as a whole, thus the owned state uses non-fungible arithmetics.
+pub fn api(codex_id: CodexId) -> Api {
+    super::api(codex_id, StateArithm::NonFungible)
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::StateData;
+
+    use super::*;
+    use crate::api_builder::{IssuerError, IssuerValidate};
+    use crate::test_helpers::TestContext;
+    use crate::{
+        nft_allocation, ERRNO_GLOBAL_FRACTIONS_NOT_ONE, G_DETAILS, G_NAME, G_NFT, G_PRECISION,
+    };
+
+    fn genesis(max_fractions: u64) -> Result<(), IssuerError> {
+        const TOKEN_ID: u64 = 1;
+        let ctx = TestContext::with(&[], &[nft_allocation(TOKEN_ID, 1)]).with_globals([
+            StateData::new(G_DETAILS, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, max_fractions),
+            StateData::new(G_NFT, TOKEN_ID),
+        ]);
+        issuer_v(ScriptVersion::V1).validate_genesis(&ctx.as_context())
+    }
+
+    #[test]
+    fn genesis_indivisible() {
+        assert_eq!(genesis(1), Ok(()));
+    }
+
+    #[test]
+    fn genesis_divisible_rejected() {
+        for max_fractions in [2, 100] {
+            assert_eq!(
+                genesis(max_fractions),
+                Err(IssuerError::Rejected(Some(ERRNO_GLOBAL_FRACTIONS_NOT_ONE)))
+            );
+        }
+    }
+
+    #[test]
+    fn owned_arithmetics() {
+        let api = api(codex_v(SCRIPT_VERSION).codex_id());
+        let owned = api.owned.get(&vname!("balance")).expect("no balance state");
+        assert!(matches!(owned.arithmetics, StateArithm::NonFungible));
+    }
+}
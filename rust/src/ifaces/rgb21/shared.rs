@@ -21,35 +21,122 @@
 // the License.
 
 use hypersonic::{
-    Aggregator, Api, CallState, CodexId, GlobalApi, OwnedApi, RawBuilder, RawConvertor,
-    StateArithm, StateBuilder, StateConvertor, SubAggregator,
+    Aggregator, Api, CodexId, GlobalApi, OwnedApi, RawBuilder, RawConvertor, StateArithm,
+    StateBuilder, StateConvertor, SubAggregator,
 };
-use ifaces::Rgb21Types;
-use strict_types::SemId;
+use ifaces::{rgb21_stl, rgb_contract_stl, AssetName, Attachment, MediaType, NftSpec, Rgb21Types};
+use strict_types::{SemId, StrictEncode, StrictVal, StrictWriter};
 
+use crate::api_builder::{conforms, transfer_call};
 use crate::{
-    ERRNO_FRACTIONALITY, ERRNO_INVALID_PRECISION, ERRNO_INVALID_TOKEN_ID, ERRNO_NO_INPUT,
+    ERRNO_ALLOCATION_MISMATCH, ERRNO_COLLECTION_SIZE_MISMATCH, ERRNO_EMPTY_COLLECTION,
+    ERRNO_EMPTY_NAME, ERRNO_EMPTY_STATE, ERRNO_FRACTIONALITY, ERRNO_FRACTIONS_MISMATCH,
+    ERRNO_GLOBAL_FRACTIONS_NOT_ONE, ERRNO_INVALID_PRECISION, ERRNO_INVALID_TOKEN_ID,
+    ERRNO_MISSING_ATTACHMENT, ERRNO_MULTIPLE_PRECISION, ERRNO_NO_COLLECTION_SIZE, ERRNO_NO_INPUT,
     ERRNO_NO_NAME, ERRNO_NO_OUTPUT, ERRNO_NO_PRECISION, ERRNO_NO_TICKER, ERRNO_NO_TOKEN_ID,
-    ERRNO_TOKEN_EXCESS, ERRNO_TOKEN_EXCESS_IN, ERRNO_TOKEN_EXCESS_OUT, ERRNO_UNEXPECTED_GLOBAL_IN,
-    ERRNO_UNEXPECTED_GLOBAL_OUT, ERRNO_UNEXPECTED_OWNED_IN, G_NAME, G_PRECISION, G_SUPPLY,
-    G_TICKER, O_AMOUNT,
+    ERRNO_OWNED_FRACTIONS_NOT_ONE, ERRNO_SPLIT_FORBIDDEN, ERRNO_SUM_OVERFLOW,
+    ERRNO_TOKENS_UNSORTED, ERRNO_TOKEN_EXCESS, ERRNO_TOKEN_EXCESS_IN, ERRNO_TOKEN_EXCESS_OUT,
+    ERRNO_UNDECLARED_TOKEN, ERRNO_UNEXPECTED_GLOBAL_IN, ERRNO_UNEXPECTED_GLOBAL_OUT,
+    ERRNO_UNEXPECTED_OWNED_IN, ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT,
+    ERRNO_UNKNOWN_OUTPUT_TOKEN, ERRNO_ZERO_FRACTION, G_NAME, G_PRECISION, G_SUPPLY, G_TICKER,
+    O_AMOUNT,
 };
 
 pub const VERIFIER_GENESIS: u16 = 0;
 pub const VERIFIER_TRANSFER: u16 = 1;
 
-pub fn api(codex_id: CodexId) -> Api {
+/// Constructs the state of a `token` global for an NFT with the given token id.
+///
+/// Returns a pair of the verifiable `RGB21.TokenNo` state and the raw `RGB21.NftSpec` value, which
+/// is accepted by the `token` global raw builder. The spec is produced from the typed `NftSpec`, so
+/// the caller doesn't need to know its strict type layout. If an attachment digest is provided,
+/// it is committed as an `application/octet-stream` external attachment.
+///
+/// # Panics
+///
+/// If the name is not a valid `RGBContract.AssetName`.
+pub fn nft_spec(token_id: u32, name: &str, attachment: Option<[u8; 32]>) -> (StrictVal, StrictVal) {
     let types = Rgb21Types::new();
+    let spec = NftSpec {
+        name: Some(name.parse::<AssetName>().expect("invalid NFT name")),
+        embedded: None,
+        external: attachment.map(|digest| Attachment {
+            ty: MediaType::with("application/octet-stream"),
+            digest: digest.into(),
+        }),
+        reserves: None,
+    };
+    let data = spec
+        .strict_encode(StrictWriter::in_memory::<{ u16::MAX as usize }>())
+        .expect("NFT spec exceeds the maximal size")
+        .unbox()
+        .unconfine();
+    let raw = types
+        .type_system()
+        .strict_deserialize_type(types.get("RGB21.NftSpec"), &data)
+        .expect("NFT spec is absent from the type system")
+        .unbox();
+    (svnum!(token_id), raw)
+}
+
+/// Strict type which is absent from the RGB21 type system, reported by [`checked_get`].
+#[derive(Clone, PartialEq, Eq, Debug, Display, Error)]
+#[display("type {0} is absent from the RGB21 type system")]
+pub struct MissingType(pub String);
+
+/// Resolves the semantic id of a type from the RGB21 type system by its fully qualified name.
+///
+/// Unlike [`Rgb21Types::get`], returns [`MissingType`] instead of panicking if the type is absent.
+/// The type is looked up in the RGB21 and RGBContract type libraries, from which the RGB21 type
+/// system is built, and is resolved only if it is present there.
+pub fn checked_get(types: &Rgb21Types, name: &'static str) -> Result<SemId, MissingType> {
+    let (lib_name, type_name) = name
+        .split_once('.')
+        .ok_or_else(|| MissingType(name.to_owned()))?;
+    let present = [rgb21_stl(), rgb_contract_stl()]
+        .into_iter()
+        .find(|lib| lib.name.to_string() == lib_name)
+        .is_some_and(|lib| lib.types.keys().any(|ty| ty.to_string() == type_name));
+    if !present {
+        return Err(MissingType(name.to_owned()));
+    }
+    Ok(types.get(name))
+}
+
+/// Constructs the RGB21 API.
+///
+/// # Panics
+///
+/// If one of the types used by the API is absent from the RGB21 type system (see [`try_api`]).
+pub fn api(codex_id: CodexId, arithmetics: StateArithm) -> Api {
+    try_api(codex_id, arithmetics).unwrap_or_else(|err| panic!("invalid RGB21 API: {err}"))
+}
 
-    Api {
+/// Constructs the RGB21 API, returning [`MissingType`] if one of the types used by the API is
+/// absent from the RGB21 type system.
+pub fn try_api(codex_id: CodexId, arithmetics: StateArithm) -> Result<Api, MissingType> {
+    let types = Rgb21Types::new();
+
+    let owned = tiny_bmap! {
+        vname!("balance") => OwnedApi {
+            sem_id: checked_get(&types, "RGB21.OwnedNft")?,
+            arithmetics,
+            convertor: StateConvertor::TypedFieldEncoder(O_AMOUNT),
+            builder: StateBuilder::TypedFieldEncoder(O_AMOUNT),
+            witness_sem_id: SemId::unit(),
+            witness_builder: StateBuilder::Unit
+        }
+    };
+
+    Ok(Api {
         codex_id,
-        conforms: tiny_bset!(21),
-        default_call: Some(CallState::with("transfer", "balance")),
+        conforms: conforms(&[21]),
+        default_call: transfer_call(&owned),
         global: tiny_bmap! {
             // NFT collection name
             vname!("name") => GlobalApi {
                 published: true,
-                sem_id: types.get("RGBContract.AssetName"),
+                sem_id: checked_get(&types, "RGBContract.AssetName")?,
                 convertor: StateConvertor::TypedEncoder(G_NAME),
                 builder: StateBuilder::TypedEncoder(G_NAME),
                 raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
@@ -57,7 +144,7 @@ pub fn api(codex_id: CodexId) -> Api {
             },
             vname!("ticker") => GlobalApi {
                 published: true,
-                sem_id: types.get("RGBContract.Ticker"),
+                sem_id: checked_get(&types, "RGBContract.Ticker")?,
                 convertor: StateConvertor::TypedEncoder(G_TICKER),
                 builder: StateBuilder::TypedEncoder(G_TICKER),
                 raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
@@ -65,7 +152,7 @@ pub fn api(codex_id: CodexId) -> Api {
             },
             vname!("maxFractions") => GlobalApi {
                 published: true,
-                sem_id: types.get("RGB21.TokenFractions"),
+                sem_id: checked_get(&types, "RGB21.TokenFractions")?,
                 convertor: StateConvertor::TypedEncoder(G_PRECISION),
                 builder: StateBuilder::TypedEncoder(G_PRECISION),
                 raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
@@ -73,23 +160,14 @@ pub fn api(codex_id: CodexId) -> Api {
             },
             vname!("token") => GlobalApi {
                 published: true,
-                sem_id: types.get("RGB21.TokenNo"),
+                sem_id: checked_get(&types, "RGB21.TokenNo")?,
                 convertor: StateConvertor::TypedFieldEncoder(G_SUPPLY),
                 builder: StateBuilder::TypedFieldEncoder(G_SUPPLY),
-                raw_convertor: RawConvertor::StrictDecode(types.get("RGB21.NftSpec")),
-                raw_builder: RawBuilder::StrictEncode(types.get("RGB21.NftSpec"))
+                raw_convertor: RawConvertor::StrictDecode(checked_get(&types, "RGB21.NftSpec")?),
+                raw_builder: RawBuilder::StrictEncode(checked_get(&types, "RGB21.NftSpec")?)
             },
         },
-        owned: tiny_bmap! {
-            vname!("balance") => OwnedApi {
-                sem_id: types.get("RGB21.OwnedNft"),
-                arithmetics: StateArithm::Fungible,
-                convertor: StateConvertor::TypedFieldEncoder(O_AMOUNT),
-                builder: StateBuilder::TypedFieldEncoder(O_AMOUNT),
-                witness_sem_id: SemId::unit(),
-                witness_builder: StateBuilder::Unit
-            }
-        },
+        owned,
         aggregators: tiny_bmap! {
             vname!("name") => Aggregator::Take(SubAggregator::TheOnly(vname!("name"))),
             vname!("ticker") => Aggregator::Take(SubAggregator::TheOnly(vname!("ticker"))),
@@ -104,19 +182,181 @@ pub fn api(codex_id: CodexId) -> Api {
         errors: tiny_bmap! {
             ERRNO_NO_TICKER => tiny_s!("no NFT ticker is set, or it is misplaced in the global state declaration (the ticker should be declared first)"),
             ERRNO_NO_NAME => tiny_s!("no NFT name is set, or it is misplaced in the global state declaration (the name should be declared second)"),
+            ERRNO_EMPTY_NAME => tiny_s!("NFT collection name must not be empty"),
             ERRNO_NO_PRECISION => tiny_s!("no NFT fractionality is set, or it is misplaced in the global state declaration (the fractionality should be declared third)"),
             ERRNO_INVALID_PRECISION => tiny_s!("invalid NFT ticker fractionality value"),
+            ERRNO_MULTIPLE_PRECISION => tiny_s!("NFT fractionality must be declared once"),
             ERRNO_UNEXPECTED_OWNED_IN => tiny_s!("operation must have no inputs"),
             ERRNO_UNEXPECTED_GLOBAL_IN => tiny_s!("operation must not use any global state"),
             ERRNO_UNEXPECTED_GLOBAL_OUT => tiny_s!("operation must not declare any global state"),
             ERRNO_FRACTIONALITY => tiny_s!("the NFT token issued under this codex must be non-fractional"),
+            ERRNO_GLOBAL_FRACTIONS_NOT_ONE => tiny_s!("the NFT fractionality declared in the global state must be exactly one"),
+            ERRNO_OWNED_FRACTIONS_NOT_ONE => tiny_s!("the NFT token allocation must hold exactly one fraction"),
+            ERRNO_ZERO_FRACTION => tiny_s!("the NFT token allocation must hold a non-zero number of fractions"),
             ERRNO_INVALID_TOKEN_ID => tiny_s!("invalid token ID data"),
+            ERRNO_MISSING_ATTACHMENT => tiny_s!("the NFT token must commit to an attachment"),
             ERRNO_NO_INPUT => tiny_s!("the transfer operation must have one input"),
             ERRNO_NO_OUTPUT => tiny_s!("the transfer operation must have one input"),
             ERRNO_NO_TOKEN_ID => tiny_s!("no token ID is set for the transfer"),
             ERRNO_TOKEN_EXCESS => tiny_s!("the number of issued NFT tokens must be one"),
             ERRNO_TOKEN_EXCESS_IN => tiny_s!("the number of transferred NFT token inputs must be one"),
             ERRNO_TOKEN_EXCESS_OUT => tiny_s!("the number of transferred NFT token outputs must be one"),
+            ERRNO_NO_COLLECTION_SIZE => tiny_s!("no NFT collection size is set, or it is misplaced in the global state declaration (the size should be declared after the fractionality)"),
+            ERRNO_COLLECTION_SIZE_MISMATCH => tiny_s!("the number of issued NFT tokens does not match the declared collection size"),
+            ERRNO_TOKENS_UNSORTED => tiny_s!("NFT token ids must be declared in a strictly ascending order"),
+            ERRNO_ALLOCATION_MISMATCH => tiny_s!("each NFT token must be allocated exactly once, in the order of the token declarations"),
+            ERRNO_EMPTY_COLLECTION => tiny_s!("NFT collection must contain at least one token"),
+            ERRNO_FRACTIONS_MISMATCH => tiny_s!("the sum of issued NFT token fractions does not match the declared maximum number of fractions"),
+            ERRNO_SUM_OVERFLOW => tiny_s!("the sum of NFT token fractions overflows 64 bits"),
+            ERRNO_EMPTY_STATE => tiny_s!("NFT token input must have a state type"),
+            ERRNO_UNKNOWN_OUTPUT_TOKEN => tiny_s!("the transfer must not allocate NFT tokens which are absent from its inputs"),
+            ERRNO_UNDECLARED_TOKEN => tiny_s!("the NFT token allocation refers to a token which is not declared in the global state"),
+            ERRNO_SPLIT_FORBIDDEN => tiny_s!("NFT fractions can't be split into more allocations than were spent"),
+            ERRNO_UNEXPECTED_OWNED_TYPE_IN => tiny_s!("NFT token inputs must not be mixed with other owned state"),
+            ERRNO_UNEXPECTED_OWNED_TYPE_OUT => tiny_s!("NFT token outputs must not be mixed with other owned state"),
         },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::StateValue;
+    use strict_types::StrictDumb;
+
+    use super::*;
+    use crate::nft_allocation;
+
+    #[test]
+    fn nft_spec_roundtrip() {
+        let types = Rgb21Types::new();
+        let sys = types.type_system();
+        let sem_id = types.get("RGB21.NftSpec");
+
+        let writer = StrictWriter::in_memory::<{ u16::MAX as usize }>();
+        let data = NftSpec::strict_dumb()
+            .strict_encode(writer)
+            .expect("unable to serialize NFT spec")
+            .unbox()
+            .unconfine();
+        let val = sys
+            .strict_deserialize_type(sem_id, &data)
+            .expect("NFT spec is absent from the type system")
+            .unbox();
+
+        let api = api(CodexId::strict_dumb(), StateArithm::NonFungible);
+        let token = api.global.get(&vname!("token")).expect("no token state");
+        let raw = token
+            .raw_builder
+            .build(val.clone(), &sys)
+            .expect("unable to build raw NFT spec");
+        let converted = token
+            .raw_convertor
+            .convert(&raw, &sys)
+            .expect("unable to convert raw NFT spec");
+        assert_eq!(converted, val);
+    }
+
+    #[test]
+    fn token_field_encoder() {
+        let types = Rgb21Types::new();
+        let sys = types.type_system();
+        let sem_id = types.get("RGB21.TokenNo");
+        let api = api(CodexId::strict_dumb(), StateArithm::NonFungible);
+        let token = api.global.get(&vname!("token")).expect("no token state");
+
+        let (val, _) = nft_spec(7, "Token", None);
+        let state = token
+            .builder
+            .build(sem_id, val.clone(), &sys)
+            .expect("unable to build token state");
+        // The token id must be the verifiable field element following the state type
+        assert_eq!(state, StateValue::new(G_SUPPLY, 7u64));
+        let converted = token
+            .convertor
+            .convert(sem_id, state, &sys)
+            .expect("unable to convert token state");
+        assert_eq!(converted, Some(val));
+    }
+
+    #[test]
+    fn owned_field_encoder() {
+        let types = Rgb21Types::new();
+        let sys = types.type_system();
+        let sem_id = types.get("RGB21.OwnedNft");
+        let api = api(CodexId::strict_dumb(), StateArithm::NonFungible);
+        let balance = api.owned.get(&vname!("balance")).expect("no balance state");
+
+        let convert = |state: StateValue| {
+            balance
+                .convertor
+                .convert(sem_id, state, &sys)
+                .expect("unable to convert owned state")
+        };
+        let direct = convert(nft_allocation(7, 3)).expect("allocation of other state type");
+        let swapped = convert(nft_allocation(3, 7)).expect("allocation of other state type");
+        // The token id and the fractions must not be interchangeable
+        assert_ne!(direct, swapped);
+
+        // The token id must land into the second field element, and the fractions into the third
+        for (token_id, fractions) in [(7, 3), (3, 7), (0, 1), (u32::MAX as u64, u64::MAX)] {
+            let state = nft_allocation(token_id, fractions);
+            let val = convert(state).expect("allocation of other state type");
+            let built = balance
+                .builder
+                .build(sem_id, val, &sys)
+                .expect("unable to build owned state");
+            assert_eq!(built, nft_allocation(token_id, fractions));
+        }
+
+        // Owned state of the other types is not the NFT allocation
+        let other = StateValue::Triple {
+            first: G_SUPPLY.into(),
+            second: 7u64.into(),
+            third: 3u64.into(),
+        };
+        assert_eq!(convert(other), None);
+    }
+
+    #[test]
+    fn checked_types() {
+        let types = Rgb21Types::new();
+        assert_eq!(
+            checked_get(&types, "RGB21.NftSpec"),
+            Ok(types.get("RGB21.NftSpec"))
+        );
+        assert_eq!(
+            checked_get(&types, "RGB21.NoSuchType"),
+            Err(MissingType("RGB21.NoSuchType".to_owned()))
+        );
+        assert!(try_api(CodexId::strict_dumb(), StateArithm::NonFungible).is_ok());
+    }
+
+    #[test]
+    fn nft_spec_builder() {
+        let sys = Rgb21Types::new().type_system();
+        let api = api(CodexId::strict_dumb(), StateArithm::NonFungible);
+        let token = api.global.get(&vname!("token")).expect("no token state");
+
+        for attachment in [None, Some([0xA5u8; 32])] {
+            let (state, spec) = nft_spec(7, "Token", attachment);
+            assert_eq!(state, svnum!(7u32));
+            let raw = token
+                .raw_builder
+                .build(spec.clone(), &sys)
+                .expect("unable to build raw NFT spec");
+            let converted = token
+                .raw_convertor
+                .convert(&raw, &sys)
+                .expect("unable to convert raw NFT spec");
+            assert_eq!(converted, spec);
+            // All the optional spec fields must be present in the decoded value
+            assert!(spec.unwrap_struct("name").unwrap_option().is_some());
+            assert!(spec.unwrap_struct("embedded").unwrap_option().is_none());
+            assert_eq!(
+                spec.unwrap_struct("external").unwrap_option().is_some(),
+                attachment.is_some()
+            );
+            assert!(spec.unwrap_struct("reserves").unwrap_option().is_none());
+        }
     }
 }
@@ -21,47 +21,72 @@
 // the License.
 
 use hypersonic::{
-    Api, CallState, Codex, CodexId, GlobalApi, Identity, Issuer, OwnedApi, RawBuilder,
-    RawConvertor, Semantics, StateArithm, StateBuilder, StateConvertor,
+    Api, Codex, CodexId, GlobalApi, Identity, Issuer, OwnedApi, RawBuilder, RawConvertor,
+    StateArithm, StateBuilder, StateConvertor,
 };
 use ifaces::{CommonTypes, Rgb21Types};
 use strict_types::SemId;
 use zkaluvm::alu::CoreConfig;
 use zkaluvm::FIELD_ORDER_SECP;
 
+use crate::api_builder::{build_issuer, conforms, transfer_call, IssuerFeatures};
+use crate::scripts::{v0, ScriptVersion};
 use crate::{
-    scripts, ERRNO_INVALID_BALANCE_IN, ERRNO_INVALID_BALANCE_OUT, ERRNO_INVALID_PRECISION,
-    ERRNO_NO_ISSUED, ERRNO_NO_NAME, ERRNO_NO_PRECISION, ERRNO_NO_TICKER, ERRNO_PRECISION_OVERFLOW,
-    ERRNO_SUM_ISSUE_MISMATCH, ERRNO_SUM_MISMATCH, ERRNO_UNEXPECTED_GLOBAL,
+    scripts, ERRNO_EMPTY_NAME, ERRNO_EMPTY_STATE, ERRNO_INVALID_BALANCE_IN,
+    ERRNO_INVALID_BALANCE_OUT, ERRNO_INVALID_PRECISION, ERRNO_MULTIPLE_PRECISION,
+    ERRNO_MULTIPLE_SUPPLY, ERRNO_NO_DETAILS, ERRNO_NO_ISSUED, ERRNO_NO_NAME, ERRNO_NO_PRECISION,
+    ERRNO_NO_TICKER, ERRNO_PRECISION_OVERFLOW, ERRNO_SUM_ISSUE_MISMATCH, ERRNO_SUM_MISMATCH,
+    ERRNO_SUM_OVERFLOW, ERRNO_SUPPLY_MISPLACED, ERRNO_UNEXPECTED_GLOBAL,
     ERRNO_UNEXPECTED_GLOBAL_IN, ERRNO_UNEXPECTED_GLOBAL_OUT, ERRNO_UNEXPECTED_OWNED_IN,
-    ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT, FN_FUNGIBLE_ISSUE,
-    FN_FUNGIBLE_TRANSFER, G_DETAILS, G_NAME, G_PRECISION, G_SUPPLY, O_AMOUNT, PANDORA,
+    ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT, ERRNO_ZERO_SUPPLY,
+    FN_FUNGIBLE_BLANK, FN_FUNGIBLE_ISSUE_RGB25, FN_FUNGIBLE_TRANSFER, G_DETAILS, G_NAME,
+    G_PRECISION, G_SUPPLY, O_AMOUNT, PANDORA,
 };
 
 pub const VERIFIER_GENESIS: u16 = 0;
 pub const VERIFIER_TRANSFER: u16 = 1;
+pub const VERIFIER_BLANK: u16 = 2;
 
+/// Version of the scripts used by the released UFA codex.
+pub const SCRIPT_VERSION: ScriptVersion = ScriptVersion::V0;
+
+/// The released UFA issuer.
 pub fn issuer() -> Issuer {
+    issuer_v(SCRIPT_VERSION)
+}
+
+/// UFA issuer using the scripts of a specific version.
+pub fn issuer_v(version: ScriptVersion) -> Issuer {
     let types = CommonTypes::new();
-    let codex = codex();
-    let api = api(codex.codex_id());
-
-    let semantics = Semantics {
-        version: 0,
-        default: api,
-        custom: none!(),
-        codex_libs: small_bset![
-            scripts::shared_lib().into_lib(),
-            scripts::fungible().into_lib(),
+    build_issuer(
+        codex_v(version),
+        |codex_id| api_v(version, codex_id),
+        small_bset![
+            scripts::shared_lib_v(version).into_lib(),
+            scripts::fungible_v(version).into_lib(),
         ],
-        api_libs: none!(),
-        types: types.type_system(),
-    };
-    Issuer::new(codex, semantics).expect("invalid issuer")
+        types.type_system(),
+    )
+    .expect("API doesn't match the codex")
 }
 
-fn codex() -> Codex {
-    let lib = scripts::fungible();
+/// UFA codex using the scripts of a specific version.
+///
+/// Only the [`ScriptVersion::V0`] codex is released; the codices of other versions have different
+/// ids.
+fn codex_v(version: ScriptVersion) -> Codex {
+    let lib = scripts::fungible_v(version);
+    let verifiers = match version {
+        ScriptVersion::V0 => tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(v0::FN_FUNGIBLE_ISSUE),
+            VERIFIER_TRANSFER => lib.routine(v0::FN_FUNGIBLE_TRANSFER),
+        },
+        ScriptVersion::V1 => tiny_bmap! {
+            VERIFIER_GENESIS => lib.routine(FN_FUNGIBLE_ISSUE_RGB25),
+            VERIFIER_TRANSFER => lib.routine(FN_FUNGIBLE_TRANSFER),
+            VERIFIER_BLANK => lib.routine(FN_FUNGIBLE_BLANK),
+        },
+    };
     let codex = Codex {
         name: tiny_s!("Uniquely Fungible Asset"),
         developer: Identity::from(PANDORA),
@@ -69,26 +94,79 @@ fn codex() -> Codex {
         features: none!(),
         timestamp: 1732529307,
         field_order: FIELD_ORDER_SECP,
-        input_config: CoreConfig {
-            halt: true,
-            complexity_lim: Some(0),
-        },
+        input_config: IssuerFeatures::NONE.input_config(),
         verification_config: CoreConfig::default(),
-        verifiers: tiny_bmap! {
-            VERIFIER_GENESIS => lib.routine(FN_FUNGIBLE_ISSUE),
-            VERIFIER_TRANSFER => lib.routine(FN_FUNGIBLE_TRANSFER),
-        },
+        verifiers,
     };
     codex
 }
 
+/// API of the released UFA codex.
 pub fn api(codex_id: CodexId) -> Api {
+    api_v(SCRIPT_VERSION, codex_id)
+}
+
+/// UFA API matching the codex of a specific script version.
+pub fn api_v(version: ScriptVersion, codex_id: CodexId) -> Api {
     let types = Rgb21Types::new();
 
+    let mut verifiers = tiny_bmap! {
+        vname!("issue") => VERIFIER_GENESIS,
+        vname!("transfer") => VERIFIER_TRANSFER,
+        vname!("_") => VERIFIER_TRANSFER,
+    };
+    let mut errors = tiny_bmap! {
+        ERRNO_NO_TICKER => tiny_s!("no RGB20 ticker is set, or it is misplaced in the global state declaration (the ticker should be declared first)"),
+        ERRNO_NO_NAME => tiny_s!("no RGB20 asset name is set, or it is misplaced in the global state declaration (the name should be declared second)"),
+        ERRNO_NO_PRECISION => tiny_s!("no RGB20 precision is set, or it is misplaced in the global state declaration (the precision should be declared third)"),
+        ERRNO_INVALID_PRECISION => tiny_s!("invalid RGB20 ticket precision value"),
+        ERRNO_UNEXPECTED_OWNED_IN => tiny_s!("operation must have no inputs"),
+        ERRNO_UNEXPECTED_GLOBAL_IN => tiny_s!("operation must not use any global state"),
+        ERRNO_UNEXPECTED_GLOBAL_OUT => tiny_s!("operation must not declare any global state"),
+        ERRNO_INVALID_BALANCE_IN => tiny_s!("invalid value for an input balance"),
+        ERRNO_INVALID_BALANCE_OUT => tiny_s!("invalid value for an output balance"),
+        ERRNO_NO_ISSUED => tiny_s!("no information about the issued supply found"),
+        ERRNO_PRECISION_OVERFLOW => tiny_s!("the precision overflows the maximum value"),
+        ERRNO_SUM_ISSUE_MISMATCH => tiny_s!("the declared issued supply does not match the output balance"),
+        ERRNO_SUM_MISMATCH => tiny_s!("the sum of inputs is not equal to the sum of outputs"),
+        ERRNO_UNEXPECTED_GLOBAL => tiny_s!("unexpected global state"),
+        ERRNO_UNEXPECTED_OWNED_TYPE_IN => tiny_s!("unexpected operation input"),
+        ERRNO_UNEXPECTED_OWNED_TYPE_OUT => tiny_s!("unexpected operation output"),
+    };
+
+    if version >= ScriptVersion::V1 {
+        verifiers
+            .insert(vname!("_"), VERIFIER_BLANK)
+            .expect("too many verifiers");
+        for (errno, message) in [
+            (ERRNO_NO_DETAILS, tiny_s!("no RGB25 asset details are set, they are misplaced in the global state declaration (the details should be declared first), or they don't keep their data in the raw state")),
+            (ERRNO_EMPTY_NAME, tiny_s!("RGB25 asset name must not be empty")),
+            (ERRNO_MULTIPLE_PRECISION, tiny_s!("RGB25 asset precision must be declared once")),
+            (ERRNO_SUM_OVERFLOW, tiny_s!("the sum of balances overflows 64 bits")),
+            (ERRNO_EMPTY_STATE, tiny_s!("operation input must have a state type")),
+            (ERRNO_ZERO_SUPPLY, tiny_s!("the issued supply must not be zero")),
+            (ERRNO_SUPPLY_MISPLACED, tiny_s!("the issued supply must be declared after the precision")),
+            (ERRNO_MULTIPLE_SUPPLY, tiny_s!("the issued supply must be declared only once")),
+        ] {
+            errors.insert(errno, message).expect("too many errors");
+        }
+    }
+
+    let owned = tiny_bmap! {
+        vname!("balance") => OwnedApi {
+            sem_id: types.get("RGBContract.Amount"),
+            arithmetics: StateArithm::Fungible,
+            convertor: StateConvertor::TypedEncoder(O_AMOUNT),
+            builder: StateBuilder::TypedEncoder(O_AMOUNT),
+            witness_sem_id: SemId::unit(),
+            witness_builder: StateBuilder::Unit
+        }
+    };
+
     Api {
         codex_id,
-        conforms: tiny_bset!(25),
-        default_call: Some(CallState::with("transfer", "balance")),
+        conforms: conforms(&[25]),
+        default_call: transfer_call(&owned),
         global: tiny_bmap! {
             vname!("name") => GlobalApi {
                 published: true,
@@ -103,7 +181,7 @@ pub fn api(codex_id: CodexId) -> Api {
                 sem_id: SemId::unit(),
                 convertor: StateConvertor::TypedEncoder(G_DETAILS),
                 builder: StateBuilder::TypedEncoder(G_DETAILS),
-                raw_convertor: RawConvertor::StrictDecode(SemId::unit()),
+                raw_convertor: RawConvertor::StrictDecode(types.get("RGBContract.Details")),
                 raw_builder: RawBuilder::StrictEncode(types.get("RGBContract.Details"))
             },
             vname!("precision") => GlobalApi {
@@ -123,39 +201,57 @@ pub fn api(codex_id: CodexId) -> Api {
                 raw_builder: RawBuilder::StrictEncode(SemId::unit())
             },
         },
-        owned: tiny_bmap! {
-            vname!("balance") => OwnedApi {
-                sem_id: types.get("RGBContract.Amount"),
-                arithmetics: StateArithm::Fungible,
-                convertor: StateConvertor::TypedEncoder(O_AMOUNT),
-                builder: StateBuilder::TypedEncoder(O_AMOUNT),
-                witness_sem_id: SemId::unit(),
-                witness_builder: StateBuilder::Unit
-            }
-        },
+        owned,
         aggregators: empty!(),
-        verifiers: tiny_bmap! {
-            vname!("issue") => VERIFIER_GENESIS,
-            vname!("transfer") => VERIFIER_TRANSFER,
-            vname!("_") => VERIFIER_TRANSFER,
-        },
-        errors: tiny_bmap! {
-            ERRNO_NO_TICKER => tiny_s!("no RGB20 ticker is set, or it is misplaced in the global state declaration (the ticker should be declared first)"),
-            ERRNO_NO_NAME => tiny_s!("no RGB20 asset name is set, or it is misplaced in the global state declaration (the name should be declared second)"),
-            ERRNO_NO_PRECISION => tiny_s!("no RGB20 precision is set, or it is misplaced in the global state declaration (the precision should be declared third)"),
-            ERRNO_INVALID_PRECISION => tiny_s!("invalid RGB20 ticket precision value"),
-            ERRNO_UNEXPECTED_OWNED_IN => tiny_s!("operation must have no inputs"),
-            ERRNO_UNEXPECTED_GLOBAL_IN => tiny_s!("operation must not use any global state"),
-            ERRNO_UNEXPECTED_GLOBAL_OUT => tiny_s!("operation must not declare any global state"),
-            ERRNO_INVALID_BALANCE_IN => tiny_s!("invalid value for an input balance"),
-            ERRNO_INVALID_BALANCE_OUT => tiny_s!("invalid value for an output balance"),
-            ERRNO_NO_ISSUED => tiny_s!("no information about the issued supply found"),
-            ERRNO_PRECISION_OVERFLOW => tiny_s!("the precision overflows the maximum value"),
-            ERRNO_SUM_ISSUE_MISMATCH => tiny_s!("the declared issued supply does not match the output balance"),
-            ERRNO_SUM_MISMATCH => tiny_s!("the sum of inputs is not equal to the sum of outputs"),
-            ERRNO_UNEXPECTED_GLOBAL => tiny_s!("unexpected global state"),
-            ERRNO_UNEXPECTED_OWNED_TYPE_IN => tiny_s!("unexpected operation input"),
-            ERRNO_UNEXPECTED_OWNED_TYPE_OUT => tiny_s!("unexpected operation output"),
-        },
+        verifiers,
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::{StateData, StateValue};
+
+    use super::*;
+    use crate::api_builder::{IssuerError, IssuerValidate};
+    use crate::test_helpers::fungible_ctx;
+
+    fn genesis(details: Option<StateData>) -> Result<(), IssuerError> {
+        let globals = details.into_iter().chain([
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18u8),
+            StateData::new(G_SUPPLY, 1000u64),
+        ]);
+        let ctx = fungible_ctx(&[], &[1000]).with_globals(globals);
+        issuer_v(ScriptVersion::V1).validate_genesis(&ctx.as_context())
+    }
+
+    #[test]
+    fn genesis_details() {
+        assert_eq!(genesis(Some(StateData::new(G_DETAILS, 0u8))), Ok(()));
+    }
+
+    #[test]
+    fn genesis_requires_details() {
+        assert_eq!(
+            genesis(None),
+            Err(IssuerError::Rejected(Some(ERRNO_NO_DETAILS)))
+        );
+    }
+
+    #[test]
+    fn genesis_typed_details() {
+        let details = StateData {
+            value: StateValue::Triple {
+                first: G_DETAILS.into(),
+                second: 0u64.into(),
+                third: 1u64.into(),
+            },
+            raw: None,
+        };
+        assert_eq!(
+            genesis(Some(details)),
+            Err(IssuerError::Rejected(Some(ERRNO_NO_DETAILS)))
+        );
     }
 }
@@ -29,9 +29,13 @@ extern crate strict_types;
 compile_error!("feature std must be used");
 
 mod ifaces;
+mod registry;
 mod scripts;
+#[cfg(any(test, feature = "test-helpers"))]
+pub mod test_helpers;
 
 pub use ifaces::*;
+pub use registry::*;
 pub use scripts::*;
 
 pub const PANDORA: &str = "dns:pandoraprime.ch";
@@ -44,6 +48,131 @@ pub const G_PRECISION: u256 = u256::from_inner([2, 0, 0, 0]);
 pub const G_SUPPLY: u256 = u256::from_inner([3, 0, 0, 0]);
 pub const G_NFT: u256 = G_SUPPLY;
 pub const G_DETAILS: u256 = G_TICKER;
+pub const G_SUPPLY_B: u256 = u256::from_inner([4, 0, 0, 0]);
+pub const G_COLLECTION_SIZE: u256 = u256::from_inner([5, 0, 0, 0]);
+pub const G_MAX_PER_OUTPUT: u256 = u256::from_inner([6, 0, 0, 0]);
+pub const G_RGB20_DETAILS: u256 = u256::from_inner([7, 0, 0, 0]);
+pub const G_REFERENCE: u256 = u256::from_inner([8, 0, 0, 0]);
+pub const G_RECIPIENT_CLASSES: u256 = u256::from_inner([9, 0, 0, 0]);
+pub const G_LOT_SIZE: u256 = u256::from_inner([10, 0, 0, 0]);
+pub const G_MIN_OUTPUT: u256 = u256::from_inner([11, 0, 0, 0]);
+/// Marker of a retired contract, published by [`FN_FUNGIBLE_RETIRE`].
+pub const G_RETIRED: u256 = u256::from_inner([12, 0, 0, 0]);
+/// Token declaration of the certificate of issuance, keeping the certificate number in the second
+/// field element (see [`demo::cva`]).
+pub const G_CERTIFICATE: u256 = u256::from_inner([13, 0, 0, 0]);
+/// Ticker of the certificate of issuance, taking the place of [`G_TICKER`] in the certificate
+/// specification.
+pub const G_CERTIFICATE_TICKER: u256 = u256::from_inner([14, 0, 0, 0]);
+/// Name of the certificate of issuance, taking the place of [`G_NAME`] in the certificate
+/// specification.
+pub const G_CERTIFICATE_NAME: u256 = u256::from_inner([15, 0, 0, 0]);
+/// Fractions of the certificate of issuance, taking the place of [`G_PRECISION`] in the certificate
+/// specification.
+pub const G_CERTIFICATE_FRACTIONS: u256 = u256::from_inner([16, 0, 0, 0]);
+/// The maximal precision of a fungible asset ([`G_PRECISION`]) allowed by the issuers, matching
+/// the range of the `RGBContract.Precision` type.
+pub const MAX_PRECISION: u8 = 18;
+
 pub const O_AMOUNT: u256 = u256::ZERO;
+pub const O_AMOUNT_A: u256 = O_AMOUNT;
+pub const O_AMOUNT_B: u256 = u256::ONE;
+/// Operator allowance, spent by [`FN_FUNGIBLE_TRANSFER_FROM`].
+///
+/// The allowance is an escrow assigned to a seal of the operator: it keeps the approved amount in
+/// the second field element and a commitment to the grantee (the operator) in the third one.
+pub const O_ALLOWANCE: u256 = u256::from_inner([2, 0, 0, 0]);
+/// Allocation of the certificate of issuance, keeping the certificate number in the second field
+/// element and the number of its fractions in the third one, like the NFT allocations (see
+/// [`demo::cva`]).
+pub const O_CERTIFICATE: u256 = u256::from_inner([3, 0, 0, 0]);
+/// Issuer right to retire the contract, assigned in genesis by [`FN_FUNGIBLE_ISSUE_RETIRABLE`] and
+/// spent by [`FN_FUNGIBLE_RETIRE`]. The right has no value.
+pub const O_RETIRE_RIGHT: u256 = u256::from_inner([4, 0, 0, 0]);
+
+// Some of the state type constants deliberately share the same field element:
+//
+// | Constant     | Aliases    | Reason                                                           |
+// |--------------|------------|------------------------------------------------------------------|
+// | `G_DETAILS`  | `G_TICKER` | RGB21 and RGB25 keep the asset details in place of the ticker    |
+// | `G_NFT`      | `G_SUPPLY` | RGB21 declares NFT tokens in place of the issued supply          |
+// | `O_AMOUNT_A` | `O_AMOUNT` | the first asset of a pair uses the default allocation type       |
+// | `O_AMOUNT`   | `G_NAME`   | global and owned state types share the zero field element        |
+//
+// Any other collision between the constants of the same kind is an error, which is caught by the
+// tests; the aliases themselves are checked at compile time below, such that re-indexing one of the
+// constants without the other fails the build.
+
+const fn same_field(a: u256, b: u256) -> bool {
+    let (a, b) = (a.as_inner(), b.as_inner());
+    a[0] == b[0] && a[1] == b[1] && a[2] == b[2] && a[3] == b[3]
+}
+
+const _: () = assert!(same_field(G_DETAILS, G_TICKER));
+const _: () = assert!(same_field(G_NFT, G_SUPPLY));
+const _: () = assert!(same_field(O_AMOUNT_A, O_AMOUNT));
+const _: () = assert!(same_field(O_AMOUNT, G_NAME));
 
 // TODO: Export codex constructors.
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    const GLOBALS: [(&str, u256); 19] = [
+        ("G_NAME", G_NAME),
+        ("G_TICKER", G_TICKER),
+        ("G_PRECISION", G_PRECISION),
+        ("G_SUPPLY", G_SUPPLY),
+        ("G_NFT", G_NFT),
+        ("G_DETAILS", G_DETAILS),
+        ("G_SUPPLY_B", G_SUPPLY_B),
+        ("G_COLLECTION_SIZE", G_COLLECTION_SIZE),
+        ("G_MAX_PER_OUTPUT", G_MAX_PER_OUTPUT),
+        ("G_RGB20_DETAILS", G_RGB20_DETAILS),
+        ("G_REFERENCE", G_REFERENCE),
+        ("G_RECIPIENT_CLASSES", G_RECIPIENT_CLASSES),
+        ("G_LOT_SIZE", G_LOT_SIZE),
+        ("G_MIN_OUTPUT", G_MIN_OUTPUT),
+        ("G_RETIRED", G_RETIRED),
+        ("G_CERTIFICATE", G_CERTIFICATE),
+        ("G_CERTIFICATE_TICKER", G_CERTIFICATE_TICKER),
+        ("G_CERTIFICATE_NAME", G_CERTIFICATE_NAME),
+        ("G_CERTIFICATE_FRACTIONS", G_CERTIFICATE_FRACTIONS),
+    ];
+    const OWNED: [(&str, u256); 6] = [
+        ("O_AMOUNT", O_AMOUNT),
+        ("O_AMOUNT_A", O_AMOUNT_A),
+        ("O_AMOUNT_B", O_AMOUNT_B),
+        ("O_ALLOWANCE", O_ALLOWANCE),
+        ("O_CERTIFICATE", O_CERTIFICATE),
+        ("O_RETIRE_RIGHT", O_RETIRE_RIGHT),
+    ];
+
+    fn aliases(consts: &[(&'static str, u256)]) -> BTreeSet<(&'static str, &'static str)> {
+        let mut aliases = bset![];
+        for (no, (name1, val1)) in consts.iter().enumerate() {
+            for (name2, val2) in &consts[no + 1..] {
+                if val1 == val2 {
+                    aliases.insert((*name1.min(name2), *name1.max(name2)));
+                }
+            }
+        }
+        aliases
+    }
+
+    #[test]
+    fn global_aliases() {
+        assert_eq!(
+            aliases(&GLOBALS),
+            bset![("G_DETAILS", "G_TICKER"), ("G_NFT", "G_SUPPLY")]
+        );
+    }
+
+    #[test]
+    fn owned_aliases() {
+        assert_eq!(aliases(&OWNED), bset![("O_AMOUNT", "O_AMOUNT_A")]);
+    }
+}
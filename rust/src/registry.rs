@@ -0,0 +1,449 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::{fs, io};
+
+use hypersonic::{CodexId, Issuer};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use strict_types::StrictSerialize;
+use zkaluvm::alu::{CompiledLib, LibId};
+
+use crate::{
+    collection, divisible, fungible, nft_lib, rgb20, rgb21, rgb25, shared_lib, unique, v0,
+};
+
+/// Name of the index file written by [`export_libs`].
+pub const LIBS_INDEX_FILE: &str = "index.txt";
+
+/// Instantiates all issuers shipped with the crate, each accompanied by its abbreviation (see
+/// README for the issuer classification).
+///
+/// Demo issuers are not included.
+pub fn all_issuers() -> Vec<(&'static str, Issuer)> {
+    vec![
+        ("FNA", rgb20::fna::issuer()),
+        ("NFU", rgb21::nfu::issuer()),
+        ("NFC", rgb21::nfc::issuer()),
+        ("NDC", rgb21::ndc::issuer()),
+        ("UFA", rgb25::ufa::issuer()),
+    ]
+}
+
+/// Instantiates all issuers shipped with the crate, keyed by their codex id.
+///
+/// Provides a single discovery point for wallets and nodes to load all supported contract types.
+pub fn standard_issuers() -> BTreeMap<CodexId, Issuer> {
+    all_issuers()
+        .into_iter()
+        .map(|(_, issuer)| (issuer.codex().codex_id(), issuer))
+        .collect()
+}
+
+/// Summary information about an issuer, used to build catalogs of supported contract types.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct IssuerMetadata {
+    pub abbr: String,
+    pub name: String,
+    pub codex_id: CodexId,
+    pub conforms: Vec<u16>,
+    pub verifiers: Vec<u16>,
+    pub lib_ids: Vec<LibId>,
+}
+
+/// Extracts [`IssuerMetadata`] from an issuer.
+pub fn issuer_metadata(abbr: &str, issuer: &Issuer) -> IssuerMetadata {
+    let codex = issuer.codex();
+    IssuerMetadata {
+        abbr: abbr.to_owned(),
+        name: codex.name.to_string(),
+        codex_id: codex.codex_id(),
+        conforms: issuer.default_api().conforms.iter().copied().collect(),
+        verifiers: codex.verifiers.keys().copied().collect(),
+        lib_ids: issuer.lib_ids(),
+    }
+}
+
+/// Machine-readable description of an issuer API, which doesn't require the consumers to depend on
+/// the `hypersonic` types.
+///
+/// All the identifiers (codex id, semantic type ids and error codes) are provided as strings.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct IssuerDescriptor {
+    pub name: String,
+    pub codex_id: String,
+    pub conforms: Vec<u16>,
+    /// Semantic type ids of the global state, keyed by the state name.
+    pub global: BTreeMap<String, String>,
+    /// Semantic type ids of the owned state, keyed by the state name.
+    pub owned: BTreeMap<String, String>,
+    /// Verifier ids, keyed by the method name.
+    pub verifiers: BTreeMap<String, u16>,
+    /// Error messages, keyed by the decimal error code.
+    pub errors: BTreeMap<String, String>,
+}
+
+/// Extension trait producing [`IssuerDescriptor`] from an issuer.
+pub trait IssuerDescribe {
+    /// Describes the default API of the issuer.
+    fn descriptor(&self) -> IssuerDescriptor;
+}
+
+impl IssuerDescribe for Issuer {
+    fn descriptor(&self) -> IssuerDescriptor {
+        let api = self.default_api();
+        IssuerDescriptor {
+            name: self.codex().name.to_string(),
+            codex_id: self.codex().codex_id().to_string(),
+            conforms: api.conforms.iter().copied().collect(),
+            global: api
+                .global
+                .iter()
+                .map(|(name, state)| (name.to_string(), state.sem_id.to_string()))
+                .collect(),
+            owned: api
+                .owned
+                .iter()
+                .map(|(name, state)| (name.to_string(), state.sem_id.to_string()))
+                .collect(),
+            verifiers: api
+                .verifiers
+                .iter()
+                .map(|(method, verifier)| (method.to_string(), *verifier))
+                .collect(),
+            errors: api
+                .errors
+                .iter()
+                .map(|(errno, message)| (errno.to_string(), message.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Produces metadata for all issuers shipped with the crate.
+pub fn manifest() -> Vec<IssuerMetadata> {
+    all_issuers()
+        .iter()
+        .map(|(abbr, issuer)| issuer_metadata(abbr, issuer))
+        .collect()
+}
+
+/// Compiles all script libraries used by the issuers shipped with the crate, each accompanied by
+/// its name.
+///
+/// The frozen libraries of the released codices (see [`crate::v0`]) go first.
+pub fn standard_libs() -> Vec<(&'static str, CompiledLib)> {
+    vec![
+        ("shared_v0", v0::shared_lib()),
+        ("fungible_v0", v0::fungible()),
+        ("unique_v0", v0::unique()),
+        ("shared", shared_lib()),
+        ("fungible", fungible()),
+        ("nft", nft_lib()),
+        ("unique", unique()),
+        ("divisible", divisible()),
+        ("collection", collection()),
+    ]
+}
+
+/// Maps the ids of the [`standard_libs`] to their names.
+pub fn lib_names() -> BTreeMap<LibId, &'static str> {
+    standard_libs()
+        .into_iter()
+        .map(|(name, lib)| (lib.into_lib().lib_id(), name))
+        .collect()
+}
+
+/// Extension trait enumerating the script libraries an issuer codex depends on.
+pub trait IssuerLibs {
+    /// Lists the ids of the codex libraries in the order of [`standard_libs`], i.e. each library
+    /// follows its dependencies. Libraries which are not a part of [`standard_libs`] follow the
+    /// standard ones, ordered by their id.
+    ///
+    /// The names of the standard libraries can be resolved with [`lib_names`].
+    fn lib_ids(&self) -> Vec<LibId>;
+}
+
+impl IssuerLibs for Issuer {
+    fn lib_ids(&self) -> Vec<LibId> {
+        let mut ids = self
+            .semantics()
+            .codex_libs
+            .iter()
+            .map(|lib| lib.lib_id())
+            .collect::<Vec<_>>();
+        let order = standard_libs()
+            .into_iter()
+            .map(|(_, lib)| lib.into_lib().lib_id())
+            .collect::<Vec<_>>();
+        ids.sort_by_key(|id| {
+            (
+                order
+                    .iter()
+                    .position(|known| known == id)
+                    .unwrap_or(order.len()),
+                *id,
+            )
+        });
+        ids
+    }
+}
+
+/// Writes each of the [`standard_libs`] into the `dir` as a strict-serialized `<lib_id>.alu` file,
+/// such that the libraries can be used by external verifiers and tooling.
+///
+/// Additionally writes [`LIBS_INDEX_FILE`], listing the library names and ids, one library per
+/// line separated by a space.
+pub fn export_libs(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let mut index = String::new();
+    for (name, lib) in standard_libs() {
+        let lib = lib.into_lib();
+        let lib_id = lib.lib_id();
+        lib.strict_serialize_to_file::<{ usize::MAX }>(dir.join(format!("{lib_id}.alu")))
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        index.push_str(&format!("{name} {lib_id}\n"));
+    }
+    fs::write(dir.join(LIBS_INDEX_FILE), index)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use hypersonic::{RawBuilder, RawConvertor};
+    use strict_types::{SemId, StrictDeserialize};
+    use zkaluvm::alu::Lib;
+
+    use super::*;
+    use crate::api_builder::IssuerValidate;
+    use crate::scripts::ScriptVersion;
+    use crate::test_helpers::sample_genesis;
+
+    #[test]
+    fn manifest_entries() {
+        let manifest = manifest();
+        let abbrs = manifest
+            .iter()
+            .map(|meta| meta.abbr.as_str())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(abbrs, bset!["FNA", "NFU", "NFC", "NDC", "UFA"]);
+
+        let ids = manifest
+            .iter()
+            .map(|meta| meta.codex_id)
+            .collect::<BTreeSet<_>>();
+        assert_eq!(ids.len(), manifest.len());
+
+        for meta in &manifest {
+            assert!(!meta.verifiers.is_empty());
+            assert!(!meta.lib_ids.is_empty());
+        }
+    }
+
+    #[test]
+    fn fna_entry_points() {
+        let codex_id = rgb20::fna::codex().codex_id();
+        assert_eq!(rgb20::fna::issuer().codex().codex_id(), codex_id);
+
+        let (_, issuer) = all_issuers()
+            .into_iter()
+            .find(|(abbr, _)| *abbr == "FNA")
+            .expect("no FNA issuer");
+        assert_eq!(issuer.codex().codex_id(), codex_id);
+        assert!(standard_issuers().contains_key(&codex_id));
+
+        let meta = manifest()
+            .into_iter()
+            .find(|meta| meta.abbr == "FNA")
+            .expect("no FNA in the manifest");
+        assert_eq!(meta.codex_id, codex_id);
+        assert_eq!(issuer.descriptor().codex_id, codex_id.to_string());
+    }
+
+    #[test]
+    fn fna_descriptor() {
+        let issuer = rgb20::fna::issuer();
+        let descriptor = issuer.descriptor();
+        assert_eq!(descriptor.name, "Fungible Non-inflatable Asset");
+        assert_eq!(descriptor.codex_id, issuer.codex().codex_id().to_string());
+        assert_eq!(descriptor.conforms, vec![20]);
+        assert!(descriptor.global.contains_key("ticker"));
+        assert!(descriptor.owned.contains_key("balance"));
+        assert_eq!(
+            descriptor.verifiers.get("transfer"),
+            Some(&rgb20::fna::VERIFIER_TRANSFER)
+        );
+        assert!(descriptor
+            .errors
+            .contains_key(&crate::ERRNO_SUM_MISMATCH.to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn fna_descriptor_json() {
+        let descriptor = rgb20::fna::issuer().descriptor();
+        let json = serde_json::to_value(&descriptor).expect("unable to serialize the descriptor");
+        for key in [
+            "name",
+            "codexId",
+            "conforms",
+            "global",
+            "owned",
+            "verifiers",
+            "errors",
+        ] {
+            assert!(json.get(key).is_some(), "{key}");
+        }
+        assert_eq!(json["owned"]["balance"], descriptor.owned["balance"]);
+        let decoded: IssuerDescriptor =
+            serde_json::from_value(json).expect("unable to deserialize the descriptor");
+        assert_eq!(decoded, descriptor);
+    }
+
+    #[test]
+    fn standard_issuers_unique() {
+        let issuers = standard_issuers();
+        assert_eq!(issuers.len(), all_issuers().len());
+        for (codex_id, issuer) in issuers {
+            assert_eq!(issuer.codex().codex_id(), codex_id);
+        }
+    }
+
+    #[test]
+    fn standard_issuers_lint() {
+        for (codex_id, issuer) in standard_issuers() {
+            assert_eq!(crate::api_builder::lint(&issuer), vec![], "{codex_id}");
+        }
+    }
+
+    #[test]
+    fn standard_issuers_sample_genesis() {
+        for (abbr, issuer) in all_issuers() {
+            let genesis = sample_genesis(&issuer).expect("non-standard issuer");
+            assert_eq!(
+                issuer.validate_genesis(&genesis.as_context()),
+                Ok(()),
+                "{abbr}"
+            );
+        }
+        assert!(sample_genesis(&crate::demo::pfa::issuer()).is_none());
+    }
+
+    #[test]
+    fn details_wiring() {
+        // Details are unverifiable raw data: the typed state is unit, and the details type is
+        // used by both the raw convertor and the raw builder
+        let details = ifaces::CommonTypes::new().get("RGBContract.Details");
+        for (abbr, issuer) in all_issuers() {
+            let Some(global) = issuer.default_api().global.get(&vname!("details")) else {
+                continue;
+            };
+            assert_eq!(global.sem_id, SemId::unit(), "{abbr}");
+            assert_eq!(
+                global.raw_convertor,
+                RawConvertor::StrictDecode(details),
+                "{abbr}"
+            );
+            assert_eq!(
+                global.raw_builder,
+                RawBuilder::StrictEncode(details),
+                "{abbr}"
+            );
+        }
+
+        // RGB21 declares no details global, thus it is skipped by the checks above
+        for issuer in [
+            rgb21::nfu::issuer(),
+            rgb21::nfc::issuer(),
+            rgb21::ndc::issuer(),
+        ] {
+            let api = issuer.default_api();
+            assert!(api.global.get(&vname!("details")).is_none());
+        }
+    }
+
+    #[test]
+    fn nfu_lib_ids() {
+        let names = lib_names();
+        let libs = rgb21::nfu::issuer()
+            .lib_ids()
+            .into_iter()
+            .map(|id| names.get(&id).copied())
+            .collect::<Vec<_>>();
+        assert_eq!(libs, vec![Some("shared_v0"), Some("unique_v0")]);
+
+        let libs = rgb21::nfu::issuer_v(ScriptVersion::V1)
+            .lib_ids()
+            .into_iter()
+            .map(|id| names.get(&id).copied())
+            .collect::<Vec<_>>();
+        assert_eq!(libs, vec![Some("shared"), Some("nft"), Some("unique")]);
+
+        let libs = rgb20::fna::issuer().lib_ids();
+        assert_eq!(
+            libs,
+            vec![
+                v0::shared_lib().into_lib().lib_id(),
+                v0::fungible().into_lib().lib_id()
+            ]
+        );
+    }
+
+    #[test]
+    fn export_libs_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("issuers-libs-{}", std::process::id()));
+        export_libs(&dir).expect("unable to export libraries");
+
+        let index = fs::read_to_string(dir.join(LIBS_INDEX_FILE)).expect("no index");
+        let exported = index
+            .lines()
+            .map(|line| line.split_once(' ').expect("invalid index line"))
+            .collect::<Vec<_>>();
+        let libs = standard_libs();
+        assert_eq!(exported.len(), libs.len());
+        for ((name, id), (expected_name, lib)) in exported.into_iter().zip(libs) {
+            let expected = lib.into_lib().lib_id();
+            assert_eq!(name, expected_name);
+            assert_eq!(id, expected.to_string());
+            let read = Lib::strict_deserialize_from_file::<{ usize::MAX }>(
+                dir.join(format!("{expected}.alu")),
+            )
+            .expect("unable to read library");
+            assert_eq!(read.lib_id(), expected, "{name}");
+        }
+
+        fs::remove_dir_all(dir).expect("unable to clean up");
+    }
+}
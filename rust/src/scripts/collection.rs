@@ -20,130 +20,240 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
+use amplify::num::u256;
 use hypersonic::uasm;
 use zkaluvm::alu::CompiledLib;
 
-use super::{shared_lib, unique, FN_ASSET_SPEC, FN_GLOBAL_VERIFY_TOKEN};
-use crate::{divisible, O_AMOUNT};
+use super::{nft_lib, shared_lib, validate_dependencies, FN_ASSET_SPEC, FN_GLOBAL_VERIFY_TOKEN};
+use crate::{
+    divisible, ERRNO_FRACTIONALITY, ERRNO_INVALID_TOKEN_ID, ERRNO_OWNED_FRACTIONS_NOT_ONE,
+    ERRNO_TOKEN_EXCESS_IN, ERRNO_TOKEN_EXCESS_OUT, ERRNO_UNEXPECTED_GLOBAL_IN, FN_NFT_SUM_INPUTS,
+    FN_NFT_SUM_OUTPUTS, FN_RGB21_ISSUE, G_COLLECTION_SIZE, O_AMOUNT,
+};
 
-pub const FN_FAC_TRANSFER: u16 = 6;
-pub const FN_UNIQUE: u16 = 3;
+/// Transfer of indivisible collection tokens.
+///
+/// The tokens are collected from the input destructible state, such that the transfer doesn't
+/// require the token globals to be provided as operation inputs. Each of the spent tokens must be
+/// spent from a single input and assigned to a single output, both allocating the whole token. An
+/// output allocating a token which is not spent fails with [`ERRNO_TOKEN_EXCESS_OUT`].
+pub const FN_FAC_TRANSFER: u16 = 3;
 
+pub const ERRNO_NO_COLLECTION_SIZE: u256 = u256::from_inner([1, 3, 0, 0]);
+pub const ERRNO_COLLECTION_SIZE_MISMATCH: u256 = u256::from_inner([2, 3, 0, 0]);
+pub const ERRNO_TOKENS_UNSORTED: u256 = u256::from_inner([3, 3, 0, 0]);
+pub const ERRNO_ALLOCATION_MISMATCH: u256 = u256::from_inner([4, 3, 0, 0]);
+pub const ERRNO_EMPTY_COLLECTION: u256 = u256::from_inner([5, 3, 0, 0]);
+
+/// Collection genesis requires token ids in the global state to be declared in a strictly
+/// ascending order, and the owned state to allocate each of the tokens exactly once, in the same
+/// order. This makes the verification linear and detects duplicated tokens without extra scans.
+///
+/// The token metadata (`RGB21.NftSpec`) is kept in the raw state of the token globals, which is not
+/// accessible to the scripts, and doesn't repeat the token number: the token id is the verifiable
+/// part of the same global. Thus, the consistency between the token declarations and their
+/// allocations is enforced by matching the token ids only, failing with
+/// [`ERRNO_ALLOCATION_MISMATCH`].
 pub fn collection() -> CompiledLib {
     let shared = shared_lib().into_lib().lib_id();
-    let uniq = unique().into_lib().lib_id();
+    let nft = nft_lib().into_lib().lib_id();
+    let frac = divisible().into_lib().lib_id();
 
     const CHECK_TOKENS: u16 = 1;
     const VERIFY_AMOUNT: u16 = 2;
-    const NEXT_OUTPUT: u16 = 4;
-    const NEXT_GLOBAL: u16 = 5;
+    const LOOP_TRANSFER: u16 = 4;
+    const SKIP_TRANSFER: u16 = 5;
+    const NEXT_TRANSFER: u16 = 6;
+    const END_TRANSFER: u16 = 7;
+    const COUNT_OUTPUTS: u16 = 8;
+    const OUTPUTS_COUNTED: u16 = 9;
 
     let mut code = uasm! {
       proc FN_RGB21_ISSUE:
+        // Genesis must not use global state; we do not rely on `FN_ASSET_SPEC` checking this
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        rsti    immutable;
+        cknxi   immutable;
+        not     CO;
+        chk     CO;
+
         call    shared, FN_ASSET_SPEC; // Check asset specification
 
         // Check there is no fractionality
+        put     E1, ERRNO_FRACTIONALITY; // Set error code for the case of failure
         put     EH, 1;
         eq      E4, EH;         // `E4` contains fractions from asset spec
         chk     CO;
         clr     EH;
 
+        // Read the declared collection size
+        put     E1, ERRNO_NO_COLLECTION_SIZE; // Set error code for the case of failure
+        ldo     immutable;      // Read the fourth global state - collection size
+        chk     CO;             // - it must exist
+        put     EH, G_COLLECTION_SIZE;
+        eq      EA, EH;         // - it must have the correct state type
+        chk     CO;             // - or fail otherwise
+        test    EB;             // - it must have a value
+        chk     CO;             // - or fail otherwise
+        mov     E5, EB;         // Save the declared collection size
+        test    EC;             // ensure other field elements are empty
+        not     CO;
+        chk     CO;
+        test    ED;             // ensure other field elements are empty
+        not     CO;
+        chk     CO;
+
+        put     E4, 0;          // Start counter for tokens
+        put     E6, 0;          // The minimal token id allowed for the next token
+        put     E8, 1;          // E8 will hold 1 as a constant for increment operations
+        clr     EG;             // EG is set once we have seen a token
+        rsto    destructible;   // Start iteration over owned tokens
         call    CHECK_TOKENS;
-        call    FN_UNIQUE;
+        put     E1, ERRNO_EMPTY_COLLECTION; // Set error code for the case of failure
+        test    EG;             // The collection must contain at least one token
+        chk     CO;
+        put     E1, ERRNO_COLLECTION_SIZE_MISMATCH; // Set error code for the case of failure
+        eq      E4, E5;         // The number of tokens must match the declared collection size
+        chk     CO;
+
+        // Check we do not allocate tokens not listed in the global state
+        put     E1, ERRNO_ALLOCATION_MISMATCH; // Set error code for the case of failure
+        ldo     destructible;   // There must be no more owned tokens
+        not     CO;
+        chk     CO;
+
+        clr     E1;             // Clear the error code
         ret;
 
       routine CHECK_TOKENS:
         ldo     immutable;      // Read token information
-        chk     CO;
-        jif     CO, +3;         // Return if no more state is count
+        not     CO;
+        jif     CO, +3;         // Return if no more state is left
         ret;
 
-        call    uniq, FN_GLOBAL_VERIFY_TOKEN; // Verify token spec
-        rsto    destructible;   // Start iteration over owned tokens
-        put     E2, 0;          // Initialize token counter
-        call    VERIFY_AMOUNT;  // Verify token amount
-        put     E7, 1;          // Check token fraction is exactly 1
-        eq      EB, E7;
+        call    nft, FN_GLOBAL_VERIFY_TOKEN; // Verify token spec, token id is returned in `E3`
+        put     E1, ERRNO_INVALID_TOKEN_ID; // Set error code for the case of failure
+        fits    E3, 64.bits;    // Token id must fit in u64
         chk     CO;
-        jmp     CHECK_TOKENS;   // Loop next token
 
-        ret;
+        // Check the token id is greater than the previous one
+        put     E1, ERRNO_TOKENS_UNSORTED; // Set error code for the case of failure
+        neg     EF, E6;         // EF = -min
+        add     EF, E3;         // EF = token id - min, which wraps around the field order (and does
+        fits    EF, 64.bits;    // not fit u64) if the token id is less than the allowed minimum
+        chk     CO;
+        mov     E6, E3;         // The next token id must be greater than this one
+        add     E6, E8;
 
+        add     E4, E8;         // Increment token counter
+        put     EG, 1;          // Mark that we have seen a token
+        call    VERIFY_AMOUNT;  // Verify token allocation
+        jmp     CHECK_TOKENS;   // Loop next token
+
+      // Check the next owned token allocates the token `E3`
       proc VERIFY_AMOUNT:
-        ldo     destructible;
+        put     E1, ERRNO_ALLOCATION_MISMATCH; // Set error code for the case of failure
+        ldo     destructible;   // Each token must have an allocation
         chk     CO;
-        jif     CO, +3;
-        ret;
 
+        put     E1, ERRNO_INVALID_TOKEN_ID; // Set error code for the case of failure
         put     E7, O_AMOUNT;   // Check that the state type is correct
         eq      EA, E7;
         chk     CO;
 
-        eq      EC, EE;         // Filter by token Id
+        put     E1, ERRNO_ALLOCATION_MISMATCH; // Set error code for the case of failure
+        eq      EB, E3;         // Allocations must follow the order of the tokens
         chk     CO;
-        jif     CO, +3;
-        ret;
 
-        put     E7, 1;          // Check the amount is correct
-        eq      EB, E7;
+        put     E1, ERRNO_FRACTIONALITY; // Set error code for the case of failure
+        put     E7, 1;          // Check the token fraction is exactly 1
+        eq      EC, E7;
         chk     CO;
 
-        add     E2, E7;         // Increase token counter
-
         test    ED;             // The last field element must be empty
+        not     CO;
         chk     CO;
-
-        jmp     VERIFY_AMOUNT; // Process to the next token
-
-      // Check we do not use tokens not listed in the global state
-      // TODO: Ensure all token ids are unique
-      proc FN_UNIQUE:
-        rsto    destructible;  // Reset output owned state iterator
-        put     E2, 1;          // We need this for the first cycle to succeed
-
-      label NEXT_OUTPUT:
-        put     E7, 1;          // Check there is a token
-        eq      E2, E7;
-        chk     CO;
-
-        ldo     destructible;  // Load next token data
-        jif     CO, +3;         // Return if no more tokens left
         ret;
 
-        put     E2, 0;          // Initialize token counter for the global state
-        mov     EE, EB;         // Save the token id
-        rsto    immutable;     // Start iteration over global state
-
-      label NEXT_GLOBAL:
-        ldo     immutable;
-        jif     CO, NEXT_OUTPUT;// No more tokens in global state, processing to the next output
-
-        eq      EC, EE;         // Filter by token id
-        jif     CO, NEXT_GLOBAL;
+      proc FN_FAC_TRANSFER:
+        cknxo   immutable;      // No new global state must be defined
+        not     CO;
+        chk     CO;
+        put     E7, 1;          // E7 will hold 1 as a constant for comparisons
+        put     E6, 0;          // E6 counts the inputs whose tokens were already checked
 
-        add     E2, E7;         // Increment token counter
-        jmp     NEXT_GLOBAL;
+        // Each of the spent tokens must be spent and allocated exactly once, as a whole
+      label LOOP_TRANSFER:
+        rsti    destructible;   // Restore position of the input iterator
+        put     EG, 0;          // EG counts the skipped inputs
+      label SKIP_TRANSFER:
+        eq      EG, E6;         // Have we skipped all the already checked inputs?
+        jif     CO, NEXT_TRANSFER;
+        ldi     destructible;   // Skip the input
+        add     EG, E7;
+        jmp     SKIP_TRANSFER;
+      label NEXT_TRANSFER:
+        ldi     destructible;   // Read the next input
+        not     CO;
+        jif     CO, END_TRANSFER; // Finish if no more inputs
+        add     E6, E7;         // Count the input
+        mov     EE, EB;         // Select the token to sum the fractions for
+        call    frac, FN_NFT_SUM_INPUTS; // Sum and count inputs
+        call    frac, FN_NFT_SUM_OUTPUTS; // Sum and count outputs
+        put     E1, ERRNO_TOKEN_EXCESS_IN; // Set error code for the case of failure
+        eq      E4, E7;         // There must be a single input
+        chk     CO;
+        put     E1, ERRNO_TOKEN_EXCESS_OUT; // Set error code for the case of failure
+        eq      E5, E7;         // There must be a single output
+        chk     CO;
+        put     E1, ERRNO_OWNED_FRACTIONS_NOT_ONE; // Set error code for the case of failure
+        eq      E2, E7;         // The input must allocate the whole token
+        chk     CO;
+        eq      E3, E7;         // The output must allocate the whole token
+        chk     CO;
+        jmp     LOOP_TRANSFER;  // Process to the next token
 
-      proc FN_UAC_TRANSFER:
-        cknxo   immutable;     // No new global state must be defined
+        // Each of the spent tokens has a single output, thus any other output allocates a token
+        // which is not spent
+      label END_TRANSFER:
+        put     E1, ERRNO_TOKEN_EXCESS_OUT; // Set error code for the case of failure
+        rsto    destructible;   // Start iteration over outputs
+        put     EG, 0;          // EG counts the outputs
+      label COUNT_OUTPUTS:
+        ldo     destructible;   // Read the next output
         not     CO;
+        jif     CO, OUTPUTS_COUNTED; // Finish if no more outputs
+        add     EG, E7;         // Count the output
+        jmp     COUNT_OUTPUTS;
+      label OUTPUTS_COUNTED:
+        eq      EG, E6;         // There must be as many outputs as the inputs
         chk     CO;
 
-        // TODO: Complete implementation
+        clr     E1;             // Clear the error code
         ret;
     };
 
-    CompiledLib::compile(&mut code, &[&shared_lib(), &unique(), &divisible()])
-        .unwrap_or_else(|err| panic!("Invalid script: {err}"))
+    let deps = [&shared_lib(), &nft_lib(), &divisible()];
+    let lib = CompiledLib::compile(&mut code, &deps)
+        .unwrap_or_else(|err| panic!("Invalid script: {err}"));
+    debug_assert_eq!(validate_dependencies(&lib, &deps), Ok(()));
+    debug_assert_eq!(
+        lib.routine(FN_RGB21_ISSUE).offset,
+        0,
+        "RGB21 issue must be the first routine"
+    );
+    lib
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::scripts::divisible;
-    use hypersonic::Instr;
-    use zkaluvm::alu::{CoreConfig, Lib, LibId, Vm};
-    use zkaluvm::{GfaConfig, FIELD_ORDER_SECP};
+    use crate::{FN_RGB21_ISSUE, G_DETAILS, G_NAME, G_NFT, G_PRECISION};
+    use hypersonic::{AuthToken, Instr, StateCell, StateData, StateValue, VmContext};
+    use strict_types::StrictDumb;
+    use zkaluvm::alu::{CoreConfig, CoreExt, Lib, LibId, Supercore, Vm};
+    use zkaluvm::{GfaConfig, GfaCore, RegE, FIELD_ORDER_SECP};
 
     const CONFIG: CoreConfig = CoreConfig {
         halt: true,
@@ -159,7 +269,7 @@ mod tests {
         );
         fn resolver(id: LibId) -> Option<Lib> {
             let lib = collection();
-            let unique = unique();
+            let nft = nft_lib();
             let fractionable = divisible();
             let shared = shared_lib();
             if lib.as_lib().lib_id() == id {
@@ -168,8 +278,8 @@ mod tests {
             if fractionable.as_lib().lib_id() == id {
                 return Some(fractionable.into_lib());
             }
-            if unique.as_lib().lib_id() == id {
-                return Some(unique.into_lib());
+            if nft.as_lib().lib_id() == id {
+                return Some(nft.into_lib());
             }
             if shared.as_lib().lib_id() == id {
                 return Some(shared.into_lib());
@@ -178,4 +288,224 @@ mod tests {
         }
         (collection(), vm, resolver)
     }
+
+    fn token_out(token_id: u64) -> StateCell {
+        StateCell {
+            data: StateValue::Triple {
+                first: O_AMOUNT.into(),
+                second: token_id.into(),
+                third: 1u64.into(),
+            },
+            auth: AuthToken::strict_dumb(),
+            lock: None,
+        }
+    }
+
+    fn genesis(size: u64, tokens: &[u64]) -> (bool, u256) {
+        genesis_with(size, tokens, tokens)
+    }
+
+    fn genesis_with(size: u64, tokens: &[u64], owned: &[u64]) -> (bool, u256) {
+        genesis_routine(FN_RGB21_ISSUE, size, tokens, owned)
+    }
+
+    fn genesis_routine(routine: u16, size: u64, tokens: &[u64], owned: &[u64]) -> (bool, u256) {
+        let mut globals = vec![
+            StateData::new(G_DETAILS, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 1u8),
+            StateData::new(G_COLLECTION_SIZE, size),
+        ];
+        globals.extend(tokens.iter().map(|id| StateData::new(G_NFT, *id)));
+        let owned = owned.iter().copied().map(token_out).collect::<Vec<_>>();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: owned.as_slice(),
+            immutable_output: globals.as_slice(),
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm.exec(lib.routine(routine), &context, resolver).is_ok();
+        let gfa: GfaCore = vm.core.cx.subcore();
+        let errno = gfa.get(RegE::E1).map(|fe| fe.to_u256()).unwrap_or_default();
+        (res, errno)
+    }
+
+    #[test]
+    fn genesis_size_match() {
+        let (res, _) = genesis(3, &[1, 2, 3]);
+        assert!(res);
+    }
+
+    #[test]
+    fn genesis_empty_collection() {
+        let (res, errno) = genesis(0, &[]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_EMPTY_COLLECTION);
+    }
+
+    #[test]
+    fn genesis_single_token() {
+        let (res, _) = genesis(1, &[1]);
+        assert!(res);
+    }
+
+    #[test]
+    fn genesis_size_short() {
+        let (res, errno) = genesis(4, &[1, 2, 3]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_COLLECTION_SIZE_MISMATCH);
+    }
+
+    #[test]
+    fn genesis_size_over() {
+        let (res, errno) = genesis(2, &[1, 2, 3]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_COLLECTION_SIZE_MISMATCH);
+    }
+
+    #[test]
+    fn genesis_sorted() {
+        assert!(genesis(3, &[0, 1, 2]).0);
+        assert!(genesis(3, &[1, 5, 100]).0);
+        assert!(genesis(2, &[u64::MAX - 1, u64::MAX]).0);
+    }
+
+    #[test]
+    fn genesis_unsorted() {
+        let (res, errno) = genesis(3, &[2, 1, 3]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_TOKENS_UNSORTED);
+    }
+
+    #[test]
+    fn genesis_duplicate() {
+        let (res, errno) = genesis(3, &[1, 2, 2]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_TOKENS_UNSORTED);
+    }
+
+    #[test]
+    fn genesis_allocation_order() {
+        let (res, errno) = genesis_with(3, &[1, 2, 3], &[1, 3, 2]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_ALLOCATION_MISMATCH);
+
+        let (res, errno) = genesis_with(3, &[1, 2, 3], &[1, 2]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_ALLOCATION_MISMATCH);
+
+        let (res, errno) = genesis_with(3, &[1, 2, 3], &[1, 2, 3, 4]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_ALLOCATION_MISMATCH);
+    }
+
+    #[test]
+    fn genesis_allocation_ids() {
+        assert!(genesis_with(3, &[1, 2, 3], &[1, 2, 3]).0);
+
+        let (res, errno) = genesis_with(3, &[1, 2, 3], &[1, 2, 4]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_ALLOCATION_MISMATCH);
+
+        let (res, errno) = genesis_with(1, &[7], &[8]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_ALLOCATION_MISMATCH);
+    }
+
+    #[test]
+    fn genesis_no_size() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[token_out(1)],
+            immutable_output: &[
+                StateData::new(G_DETAILS, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, 1u8),
+                StateData::new(G_NFT, 1u64),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_RGB21_ISSUE), &context, resolver)
+            .is_ok();
+        let gfa: GfaCore = vm.core.cx.subcore();
+        assert!(!res);
+        assert_eq!(
+            gfa.get(RegE::E1).unwrap().to_u256(),
+            ERRNO_NO_COLLECTION_SIZE
+        );
+    }
+
+    fn transfer(inputs: &[(u64, u64)], outputs: &[(u64, u64)]) -> (bool, u256) {
+        let context = crate::test_helpers::nft_ctx(inputs, outputs);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FAC_TRANSFER),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        let gfa: GfaCore = vm.core.cx.subcore();
+        let errno = gfa.get(RegE::E1).map(|fe| fe.to_u256()).unwrap_or_default();
+        (res, errno)
+    }
+
+    #[test]
+    fn transfer_tokens() {
+        assert!(transfer(&[(1, 1)], &[(1, 1)]).0);
+        assert!(transfer(&[(1, 1), (2, 1)], &[(2, 1), (1, 1)]).0);
+        assert!(transfer(&[], &[]).0);
+    }
+
+    #[test]
+    fn transfer_mint() {
+        assert_eq!(transfer(&[], &[(1, 1)]), (false, ERRNO_TOKEN_EXCESS_OUT));
+        assert_eq!(
+            transfer(&[(1, 1)], &[(1, 1), (2, 1)]),
+            (false, ERRNO_TOKEN_EXCESS_OUT)
+        );
+        assert_eq!(
+            transfer(&[(1, 1)], &[(1, 1), (1, 1)]),
+            (false, ERRNO_TOKEN_EXCESS_OUT)
+        );
+        assert_eq!(
+            transfer(&[(1, 1)], &[(2, 1)]),
+            (false, ERRNO_TOKEN_EXCESS_OUT)
+        );
+    }
+
+    #[test]
+    fn transfer_burn() {
+        assert_eq!(transfer(&[(1, 1)], &[]), (false, ERRNO_TOKEN_EXCESS_OUT));
+        assert_eq!(
+            transfer(&[(1, 1), (1, 1)], &[(1, 1)]),
+            (false, ERRNO_TOKEN_EXCESS_IN)
+        );
+    }
+
+    #[test]
+    fn transfer_fractions() {
+        assert_eq!(
+            transfer(&[(1, 2)], &[(1, 2)]),
+            (false, ERRNO_OWNED_FRACTIONS_NOT_ONE)
+        );
+    }
+
+    #[test]
+    fn issue_is_first() {
+        let lib = collection();
+        assert_eq!(lib.routine(FN_RGB21_ISSUE).offset, 0);
+        // The first routine verifies genesis, which is rejected by the transfer
+        assert!(genesis_routine(FN_RGB21_ISSUE, 2, &[1, 2], &[1, 2]).0);
+        assert_eq!(
+            genesis_routine(FN_RGB21_ISSUE, 3, &[1, 2], &[1, 2]),
+            (false, ERRNO_COLLECTION_SIZE_MISMATCH)
+        );
+        assert!(!genesis_routine(FN_FAC_TRANSFER, 2, &[1, 2], &[1, 2]).0);
+    }
 }
@@ -0,0 +1,379 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Complexity budgets for the verification routines.
+//!
+//! Each budget is an upper bound on the complexity consumed by a routine on a representative
+//! worst-case context (see tests in this module). A script change which makes a routine exceed its
+//! budget must be reviewed for accidental loop nesting before the budget gets increased.
+
+// TODO: Set each budget from the complexity measured on its context, and check that the budgets
+//       remain tight (i.e. a script change reducing the complexity lowers the budget).
+
+use hypersonic::Instr;
+use zkaluvm::alu::{LibId, Vm};
+
+/// Complexity budget for [`FN_FUNGIBLE_ISSUE`](crate::FN_FUNGIBLE_ISSUE) with 100 outputs.
+pub const BUDGET_FUNGIBLE_ISSUE: u64 = 500_000_000;
+/// Complexity budget for [`FN_FUNGIBLE_TRANSFER`](crate::FN_FUNGIBLE_TRANSFER) with 100 inputs
+/// and 100 outputs.
+pub const BUDGET_FUNGIBLE_TRANSFER: u64 = 1_000_000_000;
+/// Complexity budget for [`FN_RGB21_ISSUE`](crate::FN_RGB21_ISSUE) of the unique token library.
+pub const BUDGET_UNIQUE_ISSUE: u64 = 580_000_000;
+/// Complexity budget for [`FN_UNIQUE_TRANSFER`](crate::FN_UNIQUE_TRANSFER).
+pub const BUDGET_UNIQUE_TRANSFER: u64 = 580_000_000;
+/// Complexity budget for [`FN_RGB21_ISSUE`](crate::FN_RGB21_ISSUE) of the divisible token library
+/// with 10 tokens, each having 10 allocations.
+pub const BUDGET_DIVISIBLE_ISSUE: u64 = 5_000_000_000;
+/// Complexity budget for [`FN_DIVISIBLE_BATCH_ISSUE`](crate::FN_DIVISIBLE_BATCH_ISSUE) with 1000
+/// tokens, each having 2 allocations. The batch issue is linear, while the same context makes
+/// [`FN_RGB21_ISSUE`](crate::FN_RGB21_ISSUE) of the divisible token library scan all 2000
+/// allocations for each of the tokens, exceeding this budget.
+pub const BUDGET_DIVISIBLE_BATCH_ISSUE: u64 = 100_000_000_000;
+/// Complexity budget for [`FN_RGB21_ISSUE`](crate::FN_RGB21_ISSUE) of the collection library with
+/// 10 tokens.
+pub const BUDGET_COLLECTION_ISSUE: u64 = 5_000_000_000;
+
+/// Returns the complexity consumed by the VM since its creation.
+pub fn consumed_complexity(vm: &Vm<Instr<LibId>>) -> u64 {
+    vm.core.ca()
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::{AuthToken, Input, StateCell, StateData, StateValue, VmContext};
+    use strict_types::StrictDumb;
+    use zkaluvm::alu::{CompiledLib, CoreConfig, Lib, LibSite};
+    use zkaluvm::{GfaConfig, FIELD_ORDER_SECP};
+
+    use super::*;
+    use crate::test_helpers::{assert_terminates, fungible_ctx, nft_ctx, TestContext};
+    use crate::{
+        all_issuers, collection, demo, divisible, fungible, multi_fungible, nft_allocation,
+        nft_lib, shared_lib, unique, unique_with, v0, FN_DIVISIBLE_BATCH_ISSUE, FN_FUNGIBLE_ISSUE,
+        FN_FUNGIBLE_TRANSFER, FN_RGB21_ISSUE, FN_UNIQUE_TRANSFER, G_COLLECTION_SIZE, G_DETAILS,
+        G_NAME, G_NFT, G_PRECISION, G_SUPPLY, G_TICKER, O_AMOUNT,
+    };
+
+    fn resolver(id: LibId) -> Option<Lib> {
+        [
+            shared_lib(),
+            fungible(),
+            unique(),
+            nft_lib(),
+            divisible(),
+            collection(),
+        ]
+        .into_iter()
+        .map(|lib| lib.into_lib())
+        .find(|lib| lib.lib_id() == id)
+    }
+
+    fn measure(site: LibSite, context: &VmContext, budget: u64) -> u64 {
+        let mut vm = Vm::<Instr<LibId>>::with(
+            CoreConfig {
+                halt: true,
+                complexity_lim: Some(budget),
+            },
+            GfaConfig {
+                field_order: FIELD_ORDER_SECP,
+            },
+        );
+        let res = vm.exec(site, context, resolver).is_ok();
+        let consumed = consumed_complexity(&vm);
+        assert!(
+            res,
+            "routine failed or exceeded the budget {budget} (consumed {consumed})"
+        );
+        assert!(
+            consumed <= budget,
+            "consumed {consumed} exceeding the budget {budget}"
+        );
+        consumed
+    }
+
+    fn amount(val: u64) -> StateCell {
+        StateCell {
+            data: StateValue::new(O_AMOUNT, val),
+            auth: AuthToken::strict_dumb(),
+            lock: None,
+        }
+    }
+
+    fn token(token_id: u64, fractions: u64) -> StateCell {
+        StateCell {
+            data: nft_allocation(token_id, fractions),
+            auth: AuthToken::strict_dumb(),
+            lock: None,
+        }
+    }
+
+    #[test]
+    fn fungible_issue() {
+        let output = [10u64; 100].map(amount);
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &output,
+            immutable_output: &[
+                StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, 18_u8),
+                StateData::new(G_SUPPLY, 1000_u64),
+            ],
+        };
+        measure(
+            fungible().routine(FN_FUNGIBLE_ISSUE),
+            &context,
+            BUDGET_FUNGIBLE_ISSUE,
+        );
+    }
+
+    #[test]
+    fn fungible_transfer() {
+        let input = [10u64; 100].map(|val| (Input::strict_dumb(), amount(val)));
+        let output = [10u64; 100].map(amount);
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &input,
+            immutable_input: &[],
+            destructible_output: &output,
+            immutable_output: &[],
+        };
+        measure(
+            fungible().routine(FN_FUNGIBLE_TRANSFER),
+            &context,
+            BUDGET_FUNGIBLE_TRANSFER,
+        );
+    }
+
+    #[test]
+    fn unique_issue() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[token(1, 1)],
+            immutable_output: &[
+                StateData::new(G_DETAILS, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, 1u8),
+                StateData::new(G_NFT, 1u64),
+            ],
+        };
+        measure(
+            unique().routine(FN_RGB21_ISSUE),
+            &context,
+            BUDGET_UNIQUE_ISSUE,
+        );
+    }
+
+    #[test]
+    fn unique_transfer() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[(Input::strict_dumb(), token(1, 1))],
+            immutable_input: &[],
+            destructible_output: &[token(1, 1)],
+            immutable_output: &[],
+        };
+        measure(
+            unique().routine(FN_UNIQUE_TRANSFER),
+            &context,
+            BUDGET_UNIQUE_TRANSFER,
+        );
+    }
+
+    #[test]
+    fn divisible_issue() {
+        let mut globals = vec![
+            StateData::new(G_DETAILS, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 100u64),
+        ];
+        globals.extend((1..=10u64).map(|id| StateData::new(G_NFT, id)));
+        let output = (1..=10u64)
+            .flat_map(|id| [10u64; 10].map(|fractions| token(id, fractions)))
+            .collect::<Vec<_>>();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &output,
+            immutable_output: &globals,
+        };
+        measure(
+            divisible().routine(FN_RGB21_ISSUE),
+            &context,
+            BUDGET_DIVISIBLE_ISSUE,
+        );
+    }
+
+    fn batch_context_data() -> (Vec<StateData>, Vec<StateCell>) {
+        let mut globals = vec![
+            StateData::new(G_DETAILS, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 100u64),
+        ];
+        globals.extend((1..=1000u64).map(|id| StateData::new(G_NFT, id)));
+        let output = (1..=1000u64)
+            .flat_map(|id| [40u64, 60].map(|fractions| token(id, fractions)))
+            .collect::<Vec<_>>();
+        (globals, output)
+    }
+
+    #[test]
+    fn divisible_batch_issue() {
+        let (globals, output) = batch_context_data();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &output,
+            immutable_output: &globals,
+        };
+        measure(
+            divisible().routine(FN_DIVISIBLE_BATCH_ISSUE),
+            &context,
+            BUDGET_DIVISIBLE_BATCH_ISSUE,
+        );
+
+        // The quadratic issue can't validate the same batch within the budget
+        let mut vm = Vm::<Instr<LibId>>::with(
+            CoreConfig {
+                halt: true,
+                complexity_lim: Some(BUDGET_DIVISIBLE_BATCH_ISSUE),
+            },
+            GfaConfig {
+                field_order: FIELD_ORDER_SECP,
+            },
+        );
+        let res = vm
+            .exec(divisible().routine(FN_RGB21_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(!res);
+    }
+
+    #[test]
+    fn collection_issue() {
+        let mut globals = vec![
+            StateData::new(G_DETAILS, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 1u8),
+            StateData::new(G_COLLECTION_SIZE, 10u64),
+        ];
+        globals.extend((1..=10u64).map(|id| StateData::new(G_NFT, id)));
+        let output = (1..=10u64).map(|id| token(id, 1)).collect::<Vec<_>>();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &output,
+            immutable_output: &globals,
+        };
+        measure(
+            collection().routine(FN_RGB21_ISSUE),
+            &context,
+            BUDGET_COLLECTION_ISSUE,
+        );
+    }
+
+    /// Complexity limit for a codex verifier: the budget of the routine it runs, or, for the
+    /// routines having no budget of their own, the transfer budget of the fungible and unique
+    /// libraries and the (non-batch) issue budget of the divisible and collection libraries.
+    fn verifier_budget(site: LibSite) -> u64 {
+        let runs = |lib: CompiledLib, routine: u16| lib.routine(routine) == site;
+        let within = |lib: CompiledLib| lib.into_lib().lib_id() == site.prog_id;
+        let certificate = || unique_with(demo::cva::certificate_layout());
+
+        if runs(fungible(), FN_FUNGIBLE_ISSUE) || runs(v0::fungible(), v0::FN_FUNGIBLE_ISSUE) {
+            BUDGET_FUNGIBLE_ISSUE
+        } else if within(fungible()) || within(v0::fungible()) || within(multi_fungible()) {
+            BUDGET_FUNGIBLE_TRANSFER
+        } else if runs(divisible(), FN_DIVISIBLE_BATCH_ISSUE) {
+            BUDGET_DIVISIBLE_BATCH_ISSUE
+        } else if within(divisible()) {
+            BUDGET_DIVISIBLE_ISSUE
+        } else if within(collection()) {
+            BUDGET_COLLECTION_ISSUE
+        } else if runs(unique(), FN_RGB21_ISSUE)
+            || runs(v0::unique(), v0::FN_RGB21_ISSUE)
+            || runs(certificate(), FN_RGB21_ISSUE)
+        {
+            BUDGET_UNIQUE_ISSUE
+        } else if within(unique()) || within(v0::unique()) || within(certificate()) {
+            BUDGET_UNIQUE_TRANSFER
+        } else {
+            panic!("verifier {site} runs a library without complexity budget")
+        }
+    }
+
+    #[test]
+    fn verifiers_terminate() {
+        let mut globals = vec![
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 1u8),
+            StateData::new(G_SUPPLY, 1600u64),
+        ];
+        globals.extend((1..=16u64).map(|id| StateData::new(G_NFT, id)));
+        let tokens = (1..=16u64).map(|id| (id, 1)).collect::<Vec<_>>();
+        let contexts = [
+            TestContext::default(),
+            fungible_ctx(&[100; 16], &[100; 16]),
+            fungible_ctx(&[], &[100; 16]).with_globals(globals.clone()),
+            nft_ctx(&tokens, &tokens),
+            nft_ctx(&[], &tokens).with_globals(globals),
+        ];
+
+        let mut issuers = all_issuers()
+            .into_iter()
+            .map(|(_, issuer)| issuer)
+            .collect::<Vec<_>>();
+        issuers.extend([
+            demo::afa::issuer(),
+            demo::cfa::issuer(),
+            demo::cva::issuer(),
+            demo::dfa::issuer(),
+            demo::lfa::issuer(),
+            demo::mdc::issuer(),
+            demo::pfa::issuer(),
+            demo::rfa::issuer(),
+            demo::sfa::issuer(),
+        ]);
+        for issuer in issuers {
+            let libs = issuer.semantics().codex_libs.clone();
+            let resolver = |id: LibId| libs.iter().find(|lib| lib.lib_id() == id).cloned();
+            for site in issuer.codex().verifiers.values() {
+                for context in &contexts {
+                    assert_terminates(
+                        *site,
+                        &context.as_context(),
+                        resolver,
+                        verifier_budget(*site),
+                    );
+                }
+            }
+        }
+    }
+}
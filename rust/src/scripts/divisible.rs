@@ -20,11 +20,19 @@
 // or implied. See the License for the specific language governing permissions and limitations under
 // the License.
 
+use amplify::num::u256;
 use hypersonic::uasm;
 use zkaluvm::alu::CompiledLib;
 
-use super::{shared_lib, unique, FN_ASSET_SPEC, FN_GLOBAL_VERIFY_TOKEN};
-use crate::{G_NFT, O_AMOUNT};
+use super::{
+    nft_lib, shared_lib, validate_dependencies, ERRNO_EMPTY_STATE, FN_ADD_CHECKED,
+    FN_ASSERT_PAIR_VALUE, FN_ASSET_SPEC, FN_GLOBAL_VERIFY_TOKEN,
+};
+use crate::{
+    ERRNO_INVALID_TOKEN_ID, ERRNO_TOKENS_UNSORTED, ERRNO_UNEXPECTED_GLOBAL_IN,
+    ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT, ERRNO_ZERO_FRACTION,
+    FN_RGB21_ISSUE, G_NFT, O_AMOUNT,
+};
 
 /// Sum input owned state for a specific token id.
 ///
@@ -35,16 +43,30 @@ use crate::{G_NFT, O_AMOUNT};
 ///
 /// # Output
 ///
-/// `E2` contains the sum of inputs.
+/// `E2` contains the sum of input fractions (the third field element of the owned state).
+/// `E4` contains the number of inputs allocating the token.
 ///
 /// # Reset registers
 ///
-/// `EA`-`ED`.
+/// `EA`-`ED`, `E8`.
 ///
 /// # Side effects
 ///
 /// Extinguishes the input destructible state iterator
-pub const FN_NFT_SUM_INPUTS: u16 = 8;
+///
+/// # Errors
+///
+/// Fails with [`ERRNO_UNEXPECTED_OWNED_TYPE_IN`] if any of the inputs has an owned state type
+/// other than [`O_AMOUNT`], regardless of its token id; the token id filter only skips allocations
+/// of other tokens. An input with no state type (i.e.
+/// [`StateValue::None`](hypersonic::StateValue::None)) fails with
+/// [`ERRNO_EMPTY_STATE`].
+///
+/// Fails with [`ERRNO_ZERO_FRACTION`] if an input of the token holds zero fractions.
+///
+/// Fails with [`ERRNO_SUM_OVERFLOW`](crate::ERRNO_SUM_OVERFLOW) if the sum of the fractions doesn't
+/// fit into 64 bits, as checked by [`FN_ADD_CHECKED`].
+pub const FN_NFT_SUM_INPUTS: u16 = 12;
 
 /// Sum output owned state for a specific token id.
 ///
@@ -55,7 +77,8 @@ pub const FN_NFT_SUM_INPUTS: u16 = 8;
 ///
 /// # Output
 ///
-/// `E3` contains the sum of outputs.
+/// `E3` contains the sum of output fractions (the third field element of the owned state).
+/// `E5` contains the number of outputs allocating the token.
 ///
 /// # Reset registers
 ///
@@ -64,104 +87,187 @@ pub const FN_NFT_SUM_INPUTS: u16 = 8;
 /// # Side effects
 ///
 /// Extinguishes the output destructible state iterator
-pub const FN_NFT_SUM_OUTPUTS: u16 = 10;
+///
+/// # Errors
+///
+/// Fails with [`ERRNO_UNEXPECTED_OWNED_TYPE_OUT`] if any of the outputs has an owned state type
+/// other than [`O_AMOUNT`], regardless of its token id; the token id filter only skips allocations
+/// of other tokens.
+///
+/// Fails with [`ERRNO_ZERO_FRACTION`] if an output of the token holds zero fractions.
+///
+/// Fails with [`ERRNO_SUM_OVERFLOW`](crate::ERRNO_SUM_OVERFLOW) if the sum of the fractions doesn't
+/// fit into 64 bits, as checked by [`FN_ADD_CHECKED`].
+pub const FN_NFT_SUM_OUTPUTS: u16 = 14;
 
+/// Transfer conserving the fractions of each of the tokens present in the inputs or outputs.
+///
+/// The token ids are collected from the input and output destructible state, such that the
+/// transfer doesn't require the token globals to be provided as operation inputs. Since the token
+/// declarations are not available, the inputs serve as the reference set of the tokens: an output
+/// allocating a token which is absent from the inputs fails with [`ERRNO_UNKNOWN_OUTPUT_TOKEN`].
 pub const FN_DIVISIBLE_TRANSFER: u16 = 6;
 
+/// Transfer which allows to merge token fractions from multiple inputs, but forbids splitting
+/// them: for each of the tokens the number of outputs must not exceed the number of inputs, failing
+/// with [`ERRNO_SPLIT_FORBIDDEN`] otherwise.
+///
+/// Works like [`FN_DIVISIBLE_TRANSFER`], collecting the token ids from the input and output
+/// destructible state, such that the operation author can't skip any of the tokens.
+pub const FN_NFT_MERGE_ONLY_TRANSFER: u16 = 16;
+
+/// Batch issue of divisible tokens, linear in the number of tokens and allocations.
+///
+/// Works like [`FN_RGB21_ISSUE`], but requires the token ids in the global state to be declared in
+/// a strictly ascending order, and the owned state to group the allocations of each of the tokens
+/// together, following the order of the tokens. This allows to verify the issue with a single
+/// synchronized walk over the global and owned state, while [`FN_RGB21_ISSUE`] scans all outputs
+/// for each of the tokens.
+///
+/// An allocation which appears after the group of its token fails the verification with
+/// [`ERRNO_OUTPUTS_NOT_GROUPED`]; an allocation of a token following the last declared one fails
+/// with [`ERRNO_UNDECLARED_TOKEN`].
+pub const FN_DIVISIBLE_BATCH_ISSUE: u16 = 25;
+
+pub const ERRNO_FRACTIONS_MISMATCH: u256 = u256::from_inner([1, 4, 0, 0]);
+pub const ERRNO_UNDECLARED_TOKEN: u256 = u256::from_inner([2, 4, 0, 0]);
+pub const ERRNO_SPLIT_FORBIDDEN: u256 = u256::from_inner([3, 4, 0, 0]);
+pub const ERRNO_OUTPUTS_NOT_GROUPED: u256 = u256::from_inner([4, 4, 0, 0]);
+pub const ERRNO_UNKNOWN_OUTPUT_TOKEN: u256 = u256::from_inner([5, 4, 0, 0]);
+
 pub fn divisible() -> CompiledLib {
     let shared = shared_lib().into_lib().lib_id();
-    let uda = unique().into_lib().lib_id();
+    let nft = nft_lib().into_lib().lib_id();
 
     const NEXT_TOKEN: u16 = 1;
     const END_TOKENS: u16 = 2;
     const NEXT_OWNED: u16 = 3;
     const NEXT_GLOBAL: u16 = 4;
-    const END_TOKEN: u16 = 5;
-    const LOOP_TOKEN: u16 = 7;
-    const LOOP_INPUTS: u16 = 9;
-    const LOOP_OUTPUTS: u16 = 11;
+    const END_OWNED: u16 = 5;
+    const TRANSFER_TOKENS: u16 = 7;
+    const LOOP_TOKEN: u16 = 8;
+    const OUTPUT_TOKENS: u16 = 9;
+    const LOOP_OUT_TOKEN: u16 = 10;
+    const END_TRANSFER: u16 = 11;
+    const LOOP_INPUTS: u16 = 13;
+    const LOOP_OUTPUTS: u16 = 15;
+    const SKIP_INPUTS: u16 = 17;
+    const LOOP_SKIP_INPUTS: u16 = 18;
+    const END_SKIP_INPUTS: u16 = 19;
+    const SKIP_OUTPUTS: u16 = 20;
+    const LOOP_SKIP_OUTPUTS: u16 = 21;
+    const END_SKIP_OUTPUTS: u16 = 22;
+    const CONSERVE_TOKEN: u16 = 23;
+    const TOKEN_CONSERVED: u16 = 24;
+    const BATCH_TOKEN: u16 = 26;
+    const BATCH_GROUP: u16 = 27;
+    const BATCH_GROUP_OTHER: u16 = 28;
+    const BATCH_GROUP_END: u16 = 29;
+    const BATCH_END: u16 = 30;
+    const BATCH_DONE: u16 = 31;
+    const BATCH_NEXT_OUT: u16 = 32;
 
     // TODO: Check the correctness and completeness of the implementation
     let mut code = uasm! {
      proc FN_RGB21_ISSUE:
+        // Genesis must not use global state; we do not rely on `FN_ASSET_SPEC` checking this
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN ;// Set error code for the case of failure
+        rsti    immutable       ;// Start iteration over global inputs
+        cknxi   immutable       ;// Try to iterate over global inputs
+        not     CO              ;// Invert result (we need NO state as a Success)
+        chk     CO              ;// Fail if there is a global input
+
         call    shared, FN_ASSET_SPEC   ;// Call asset check
         fits    E4, 64.bits     ;// The precision must fit into u64
         chk     CO              ;// - or fail otherwise
-        mov     E2, E4          ;// Save the precision value to match it against issued amounts
-
-        // Validate global tokens and issued amounts
-        put     E4, 0           ;// Start counter for tokens
+        mov     E2, E4          ;// Save `maxFractions` to match it against the issued fractions
 
+        // Validate global tokens and issued fractions
      label NEXT_TOKEN:
-        ldo     immutable      ;// Read fourth global state - token information
+        ldo     immutable      ;// Read next global state - token information
+        not     CO;
         jif     CO, END_TOKENS ;// Complete token validation if no more tokens left
 
         // Verify token spec
-        call    uda, FN_GLOBAL_VERIFY_TOKEN   ;// Verify token spec
+        call    nft, FN_GLOBAL_VERIFY_TOKEN   ;// Verify token spec, token id is returned in `E3`
         // TODO: Ensure all token ids are unique
 
-        // Check issued supply
+        // Check issued fractions
+        mov     EE, E3          ;// Select the token to sum the fractions for
         call    FN_NFT_SUM_OUTPUTS    ;// Sum outputs
-        eq      E2, E3          ;// check that 'fractions' supply equals to the sum of outputs
+        put     E1, ERRNO_FRACTIONS_MISMATCH ;// Set error code for the case of failure
+        eq      E2, E3          ;// check that `maxFractions` equals to the sum of the token outputs
         chk     CO              ;// fail if not
-        put     E8, 1           ;// E8 will hold 1 as a constant for counter-increment operation
-        add     E4, E8          ;// Increment token counter
         jmp     NEXT_TOKEN     ;// Process to the next token
 
         // Validate that owned tokens match the list of issued tokens
       label END_TOKENS:
         rsto    destructible   ;// Reset state iterator
+        put     E7, G_NFT       ;// Set E7 to field element representing token data
       label NEXT_OWNED:
-        rsto    immutable      ;// Reset state iterator
         ldo     destructible   ;// Iterate over tokens
-        // Finish if no more elements are present
         not     CO;
-        jif     CO, +3;
-        ret;
-        mov     E6, EC          ;// Save token id
-        put     E5, 0           ;// Start counter
-        put     E7, G_NFT   ;// Set E7 to field element representing token data
+        jif     CO, END_OWNED  ;// Finish if no more elements are present
+        mov     E6, EB          ;// Save token id
+        rsto    immutable      ;// Reset state iterator
+        put     E1, ERRNO_UNDECLARED_TOKEN ;// Set error code for the case of failure
       label NEXT_GLOBAL:
         ldo     immutable      ;// Load global state
-        jif     CO, END_TOKEN  ;// We've done
+        chk     CO              ;// Fail if the token was not found in the global state
         eq      EA, E7          ;// It must has correct state type
+        not     CO;
         jif     CO, NEXT_GLOBAL;// If not, goto next global state
         eq      EB, E6          ;// Check if the token id match
+        not     CO;
         jif     CO, NEXT_GLOBAL;// Skip otherwise
-        put     E8, 1           ;// E8 will hold 1 as a constant for counter increment operation
-        add     E5, E8          ;// Increase counter
-      label END_TOKEN:
-        put     E8, 0           ;// E8 will hold 0 as a constant for `eq` operation
-        eq      E5, E8          ;// Check that the token has allocations
-        not     CO              ;// We need to invert CO so if no allocations we fail
-        chk     CO              ;// Fail otherwise
-        jmp     NEXT_OWNED     ;// Go to the next owned
+        jmp     NEXT_OWNED     ;// The token is declared; go to the next owned
+      label END_OWNED:
+        clr     E1              ;// Clear the error code
+        ret;
 
       proc FN_DIVISIBLE_TRANSFER:
+        clr     EG              ;// The fractions may be split into more outputs
+     label TRANSFER_TOKENS:
         // Verify that no global state is defined
         cknxo   immutable      ;// Try to iterate over global state
         not     CO              ;// Invert result (we need NO state as a Success)
         chk     CO              ;// Fail if there is a global state
 
-        put     EE, O_AMOUNT;// Set EE to the field element representing owned value
+        put     E1, ERRNO_FRACTIONS_MISMATCH ;// Set error code for the case of failure
+        put     EF, 1           ;// EF will hold 1 as a constant for the counter increment
+        put     E6, 0           ;// E6 counts the inputs whose tokens were already checked
 
-        // For each token verify the sum of inputs equal sum of outputs
+        // For each token found in the inputs verify the sum of inputs equal sum of outputs
       label LOOP_TOKEN:
-        ldi     immutable      ;// Iterate over tokens
+        call    SKIP_INPUTS     ;// Restore position of the input iterator
+        ldi     destructible    ;// Read next input
         not     CO;
-        jif     CO, +3;
-        ret                     ;// Finish if no more tokens
-        mov     EE, EB          ;// Save token id for FN_SUM_OUTPUTS
-        call    FN_NFT_SUM_INPUTS     ;// Compute sum of inputs
-        call    FN_NFT_SUM_OUTPUTS    ;// Compute sum of outputs
-        eq      E2, E3          ;// check that the sum of inputs equals sum of outputs
-        chk     CO              ;// fail if not
-        jmp     LOOP_TOKEN     ;// Process to the next token
+        jif     CO, OUTPUT_TOKENS ;// Continue with the output tokens if no more inputs
+        add     E6, EF          ;// Count the input
+        mov     EE, EB          ;// Save token id for FN_SUM_INPUTS and FN_SUM_OUTPUTS
+        call    CONSERVE_TOKEN  ;// Check that the token fractions are conserved
+        jmp     LOOP_TOKEN      ;// Process to the next token
 
-        // TODO: Check that no tokens not listed in global state are defined
+        // Repeat the same for each token found in the outputs, such that the tokens which are
+        // absent in the inputs are detected
+     label OUTPUT_TOKENS:
+        put     E6, 0           ;// E6 counts the outputs whose tokens were already checked
+     label LOOP_OUT_TOKEN:
+        call    SKIP_OUTPUTS    ;// Restore position of the output iterator
+        ldo     destructible    ;// Read next output
+        not     CO;
+        jif     CO, END_TRANSFER;// Finish if no more outputs
+        add     E6, EF          ;// Count the output
+        mov     EE, EB          ;// Save token id for FN_SUM_INPUTS and FN_SUM_OUTPUTS
+        call    CONSERVE_TOKEN  ;// Check that the token fractions are conserved
+        jmp     LOOP_OUT_TOKEN  ;// Process to the next token
+     label END_TRANSFER:
+        clr     E1              ;// Clear the error code
+        ret;
 
-     proc FN_SUM_INPUTS:
+     proc FN_NFT_SUM_INPUTS:
         put     E2, 0           ;// Set initial sum to zero
+        put     E4, 0           ;// Set initial input counter to zero
         put     EH, O_AMOUNT    ;// Set EH to the field element representing the owned value
         rsti    destructible    ;// Start iteration over inputs
 
@@ -173,27 +279,40 @@ pub fn divisible() -> CompiledLib {
         jif     CO, +3;
         ret;
 
-        eq      EA, EH          ;// do we have a correct state type?
+        put     E1, ERRNO_EMPTY_STATE ;// Set error code for the case of failure
+        test    EA              ;// a genuine owned state must carry a type tag
         chk     CO              ;// fail if not
 
-        eq      EC, EE          ;// ensure EC value equals to EE
-        jif     CO, LOOP_INPUTS ;// - read next input otherwise
-        jmp     +4              ;// process to normal flow
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_IN ;// Set error code for the case of failure
+        eq      EA, EH          ;// do we have a correct state type?
+        chk     CO              ;// fail if not, even if the token doesn't match the filter
 
-        test    ED              ;// ensure ED is not set
+        eq      EB, EE          ;// filter by the token id
         not     CO;
-        chk     CO              ;// fail if not
+        jif     CO, LOOP_INPUTS ;// - read next input otherwise
 
-        fits    EB, 64.bits     ;// ensure the value fits in u64
+        put     E1, ERRNO_FRACTIONS_MISMATCH ;// Set error code for the case of failure
+        call    shared, FN_ASSERT_PAIR_VALUE ;// ensure there are no extra field elements
+
+        fits    EC, 64.bits     ;// ensure the fractions fit in u64
         chk     CO              ;// fail if not
-        add     E2, EB          ;// add input to input accumulator
-        fits    E2, 64.bits     ;// ensure we do not overflow
+        put     E1, ERRNO_ZERO_FRACTION ;// Set error code for the case of failure
+        put     E8, 0;
+        eq      EC, E8          ;// ensure the allocation holds some fractions
+        not     CO;
         chk     CO              ;// fail if not
+        mov     EB, EC          ;// add input to input accumulator
+        mov     E8, E2;
+        call    shared, FN_ADD_CHECKED ;// - failing on overflow
+        mov     E2, E8;
+        put     E8, 1;
+        add     E4, E8          ;// count the input
 
         jmp     LOOP_INPUTS     ;// loop
 
-     proc FN_SUM_OUTPUTS:
+     proc FN_NFT_SUM_OUTPUTS:
         put     E3, 0           ;// Set initial sum to zero
+        put     E5, 0           ;// Set initial output counter to zero
         put     EH, O_AMOUNT    ;// Set EH to the field element representing the owned value
         rsto    destructible    ;// Start iteration over outputs
 
@@ -205,38 +324,221 @@ pub fn divisible() -> CompiledLib {
         jif     CO, +3;
         ret;
 
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_OUT ;// Set error code for the case of failure
         eq      EA, EH          ;// do we have a correct state type?
-        chk     CO              ;// fail if not
+        chk     CO              ;// fail if not, even if the token doesn't match the filter
+
+        eq      EB, EE          ;// filter by the token id
+        not     CO;
+        jif     CO, LOOP_OUTPUTS;// - read next output otherwise
 
-        eq      EC, EE          ;// ensure EC value equals to EE
-        jif     CO, LOOP_OUTPUTS;// - read next input otherwise
-        jmp     +6              ;// process to normal flow
+        put     E1, ERRNO_FRACTIONS_MISMATCH ;// Set error code for the case of failure
+        call    shared, FN_ASSERT_PAIR_VALUE ;// ensure there are no extra field elements
 
-        test    ED              ;// ensure ED is not set
+        fits    EC, 64.bits     ;// ensure the fractions fit in u64
+        chk     CO              ;// fail if not
+        put     E1, ERRNO_ZERO_FRACTION ;// Set error code for the case of failure
+        put     E8, 0;
+        eq      EC, E8          ;// ensure the allocation holds some fractions
         not     CO;
         chk     CO              ;// fail if not
+        mov     EB, EC          ;// add output to output accumulator
+        mov     E8, E3;
+        call    shared, FN_ADD_CHECKED ;// - failing on overflow
+        mov     E3, E8;
+        put     E8, 1;
+        add     E5, E8          ;// count the output
+
+        jmp     LOOP_OUTPUTS    ;// loop
+
+     proc FN_NFT_MERGE_ONLY_TRANSFER:
+        put     EG, 1           ;// The fractions must not be split into more outputs
+        jmp     TRANSFER_TOKENS ;// Verify the tokens as in FN_DIVISIBLE_TRANSFER
+
+     routine SKIP_INPUTS:
+        rsti    destructible    ;// Start iteration over inputs
+        put     E7, 0           ;// Set skipped inputs counter to zero
+     label LOOP_SKIP_INPUTS:
+        eq      E7, E6          ;// Have we skipped all the already checked inputs?
+        jif     CO, END_SKIP_INPUTS;
+        ldi     destructible    ;// Skip the input
+        add     E7, EF          ;// Count the skipped input
+        jmp     LOOP_SKIP_INPUTS;
+     label END_SKIP_INPUTS:
+        ret;
+
+     routine SKIP_OUTPUTS:
+        rsto    destructible    ;// Start iteration over outputs
+        put     E7, 0           ;// Set skipped outputs counter to zero
+     label LOOP_SKIP_OUTPUTS:
+        eq      E7, E6          ;// Have we skipped all the already checked outputs?
+        jif     CO, END_SKIP_OUTPUTS;
+        ldo     destructible    ;// Skip the output
+        add     E7, EF          ;// Count the skipped output
+        jmp     LOOP_SKIP_OUTPUTS;
+     label END_SKIP_OUTPUTS:
+        ret;
 
-        fits    EB, 64.bits     ;// ensure the value fits in u64
+     routine CONSERVE_TOKEN:
+        call    FN_NFT_SUM_INPUTS     ;// Compute sum and number of inputs
+        put     E1, ERRNO_UNKNOWN_OUTPUT_TOKEN ;// Set error code for the case of failure
+        put     E8, 0;
+        eq      E4, E8          ;// the token must be present in the inputs, which may be violated
+        not     CO              ;// only by the tokens found in the outputs
         chk     CO              ;// fail if not
-        add     E3, EB          ;// add input to input accumulator
-        fits    E3, 64.bits     ;// ensure we do not overflow
+        call    FN_NFT_SUM_OUTPUTS    ;// Compute sum of outputs
+        put     E1, ERRNO_FRACTIONS_MISMATCH ;// Set error code for the case of failure
+        eq      E2, E3          ;// check that the sum of inputs equals sum of outputs
         chk     CO              ;// fail if not
+        test    EG              ;// Is splitting forbidden?
+        not     CO;
+        jif     CO, TOKEN_CONSERVED ;// - we are done otherwise
+        put     E1, ERRNO_SPLIT_FORBIDDEN ;// Set error code for the case of failure
+        neg     E7, E5          ;// E7 = -outputs
+        add     E7, E4          ;// E7 = inputs - outputs, which wraps around the field order (and
+        fits    E7, 64.bits     ;// does not fit u64) if there are more outputs than inputs
+        chk     CO              ;// fail if not
+     label TOKEN_CONSERVED:
+        ret;
 
-        jmp     LOOP_OUTPUTS    ;// loop
+     proc FN_DIVISIBLE_BATCH_ISSUE:
+        // Genesis must not use global state; we do not rely on `FN_ASSET_SPEC` checking this
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN ;// Set error code for the case of failure
+        rsti    immutable       ;// Start iteration over global inputs
+        cknxi   immutable       ;// Try to iterate over global inputs
+        not     CO              ;// Invert result (we need NO state as a Success)
+        chk     CO              ;// Fail if there is a global input
+
+        call    shared, FN_ASSET_SPEC   ;// Call asset check
+        fits    E4, 64.bits     ;// The precision must fit into u64
+        chk     CO              ;// - or fail otherwise
+        mov     E2, E4          ;// Save `maxFractions` to match it against the issued fractions
+
+        put     E6, 0           ;// The minimal token id allowed for the next token
+        rsto    destructible    ;// Start iteration over the allocations
+        call    BATCH_NEXT_OUT  ;// Read the first allocation
+
+        // Walk the tokens, consuming the group of allocations of each of them
+     label BATCH_TOKEN:
+        ldo     immutable       ;// Read next global state - token information
+        not     CO;
+        jif     CO, BATCH_END   ;// Complete validation if no more tokens left
+
+        call    nft, FN_GLOBAL_VERIFY_TOKEN   ;// Verify token spec, token id is returned in `E3`
+        put     E1, ERRNO_INVALID_TOKEN_ID ;// Set error code for the case of failure
+        fits    E3, 64.bits     ;// Token id must fit in u64
+        chk     CO;
+
+        // Check the token id is greater than the previous one
+        put     E1, ERRNO_TOKENS_UNSORTED ;// Set error code for the case of failure
+        neg     EF, E6          ;// EF = -min
+        add     EF, E3          ;// EF = token id - min, which doesn't fit u64 if the token id is less
+        fits    EF, 64.bits     ;// than the allowed minimum
+        chk     CO;
+        mov     E6, E3          ;// The next token id must be greater than this one
+        put     E8, 1;
+        add     E6, E8;
+
+        put     E5, 0           ;// E5 will contain the sum of the token fractions
+     label BATCH_GROUP:
+        test    EG              ;// Is there an allocation left?
+        not     CO;
+        jif     CO, BATCH_GROUP_END ;// - finish the group otherwise
+        eq      E7, E3          ;// Does the allocation belong to the token?
+        not     CO;
+        jif     CO, BATCH_GROUP_OTHER ;// - finish the group otherwise
+        mov     EB, EE          ;// Add the allocated fractions
+        mov     E8, E5;
+        call    shared, FN_ADD_CHECKED ;// - failing on overflow
+        mov     E5, E8;
+        call    BATCH_NEXT_OUT  ;// Read the next allocation
+        jmp     BATCH_GROUP     ;// Loop the group
+
+        // The allocation must belong to one of the next tokens
+     label BATCH_GROUP_OTHER:
+        put     E1, ERRNO_OUTPUTS_NOT_GROUPED ;// Set error code for the case of failure
+        neg     EF, E7          ;// EF = -allocated token id
+        add     EF, E3          ;// EF = token id - allocated token id, which fits u64 if the
+        fits    EF, 64.bits     ;// allocated token precedes the current one
+        not     CO;
+        chk     CO;
+
+     label BATCH_GROUP_END:
+        put     E1, ERRNO_FRACTIONS_MISMATCH ;// Set error code for the case of failure
+        eq      E5, E2          ;// check that `maxFractions` equals to the sum of the token group
+        chk     CO              ;// fail if not
+        jmp     BATCH_TOKEN     ;// Process to the next token
+
+        // There must be no allocations left
+     label BATCH_END:
+        test    EG              ;// Is there an allocation left?
+        not     CO;
+        jif     CO, BATCH_DONE  ;// Succeed if there is none
+        put     E1, ERRNO_OUTPUTS_NOT_GROUPED ;// Set error code for the case of failure
+        neg     EF, E6          ;// EF = allocated token id - min, which doesn't fit u64 if the token
+        add     EF, E7          ;// precedes the last declared token
+        fits    EF, 64.bits;
+        chk     CO              ;// fail if the allocation is out of its group
+        put     E1, ERRNO_UNDECLARED_TOKEN ;// Otherwise the token is not declared
+        not     CO              ;// CO is set here, so this always fails
+        chk     CO;
+     label BATCH_DONE:
+        clr     E1              ;// Clear the error code
+        ret;
+
+        // Read the next allocation: token id into `E7`, fractions into `EE`; `EG` is set if present
+     routine BATCH_NEXT_OUT:
+        clr     EG              ;// Mark that there is no allocation
+        ldo     destructible    ;// Read next allocation
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+
+        put     E1, ERRNO_INVALID_TOKEN_ID ;// Set error code for the case of failure
+        put     EH, O_AMOUNT    ;// Check that the state type is correct
+        eq      EA, EH;
+        chk     CO;
+        fits    EB, 64.bits     ;// Token id must fit in u64
+        chk     CO;
+        test    ED              ;// The last field element must be empty
+        not     CO;
+        chk     CO;
+        put     E1, ERRNO_FRACTIONS_MISMATCH ;// Set error code for the case of failure
+        test    EC              ;// The fractions must be set
+        chk     CO;
+        fits    EC, 64.bits     ;// The fractions must fit in u64
+        chk     CO;
+
+        mov     E7, EB          ;// Save token id
+        mov     EE, EC          ;// Save fractions
+        put     EG, 1           ;// Mark that we have an allocation
+        ret;
     };
 
-    CompiledLib::compile(&mut code, &[&shared_lib(), &unique()])
-        .unwrap_or_else(|err| panic!("Invalid script: {err}"))
+    let deps = [&shared_lib(), &nft_lib()];
+    let lib = CompiledLib::compile(&mut code, &deps)
+        .unwrap_or_else(|err| panic!("Invalid script: {err}"));
+    debug_assert_eq!(validate_dependencies(&lib, &deps), Ok(()));
+    debug_assert_eq!(
+        lib.routine(FN_RGB21_ISSUE).offset,
+        0,
+        "RGB21 issue must be the first routine"
+    );
+    lib
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{FN_RGB21_ISSUE, G_DETAILS, G_NAME, G_PRECISION, G_SUPPLY};
+    use crate::{
+        nft_allocation, FN_RGB21_ISSUE, G_DETAILS, G_NAME, G_PRECISION, G_SUPPLY, O_AMOUNT_B,
+    };
     use hypersonic::{AuthToken, Instr, StateCell, StateData, StateValue, VmContext};
     use strict_types::StrictDumb;
-    use zkaluvm::alu::{CoreConfig, Lib, LibId, Vm};
-    use zkaluvm::{GfaConfig, FIELD_ORDER_SECP};
+    use zkaluvm::alu::{CoreConfig, CoreExt, Lib, LibId, Supercore, Vm};
+    use zkaluvm::{GfaConfig, GfaCore, RegE, FIELD_ORDER_SECP};
 
     const CONFIG: CoreConfig = CoreConfig {
         halt: true,
@@ -252,13 +554,13 @@ mod tests {
         );
         fn resolver(id: LibId) -> Option<Lib> {
             let lib = divisible();
-            let unique = unique();
+            let nft = nft_lib();
             let shared = shared_lib();
             if lib.as_lib().lib_id() == id {
                 return Some(lib.into_lib());
             }
-            if unique.as_lib().lib_id() == id {
-                return Some(unique.into_lib());
+            if nft.as_lib().lib_id() == id {
+                return Some(nft.into_lib());
             }
             if shared.as_lib().lib_id() == id {
                 return Some(shared.into_lib());
@@ -268,6 +570,42 @@ mod tests {
         (divisible(), vm, resolver)
     }
 
+    fn errno(vm: &Vm<Instr<LibId>>) -> u256 {
+        let gfa: GfaCore = vm.core.cx.subcore();
+        gfa.get(RegE::E1).map(|fe| fe.to_u256()).unwrap_or_default()
+    }
+
+    fn genesis_fractions(max: u64, fractions: &[u64]) -> (bool, u256) {
+        genesis_routine(FN_RGB21_ISSUE, max, fractions)
+    }
+
+    fn genesis_routine(routine: u16, max: u64, fractions: &[u64]) -> (bool, u256) {
+        const TOKEN_ID: u64 = 1;
+        let output = fractions
+            .iter()
+            .map(|val| StateCell {
+                data: nft_allocation(TOKEN_ID, *val),
+                auth: AuthToken::strict_dumb(),
+                lock: None,
+            })
+            .collect::<Vec<_>>();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: output.as_slice(),
+            immutable_output: &[
+                StateData::new(G_DETAILS, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, max),
+                StateData::new(G_NFT, TOKEN_ID),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm.exec(lib.routine(routine), &context, resolver).is_ok();
+        (res, errno(&vm))
+    }
+
     #[test]
     fn genesis_empty() {
         let context = VmContext {
@@ -378,7 +716,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
     fn genesis_correct() {
         const TOKEN_ID: u64 = 0;
         const SUPPLY: u64 = 1000_u64;
@@ -387,11 +724,7 @@ mod tests {
             destructible_input: &[],
             immutable_input: &[],
             destructible_output: &[StateCell {
-                data: StateValue::Triple {
-                    first: O_AMOUNT.into(),
-                    third: TOKEN_ID.into(),
-                    second: SUPPLY.into(),
-                },
+                data: nft_allocation(TOKEN_ID, SUPPLY),
                 auth: AuthToken::strict_dumb(),
                 lock: None,
             }],
@@ -399,7 +732,64 @@ mod tests {
                 StateData::new(G_DETAILS, 0u8),
                 StateData::new(G_NAME, 0u8),
                 StateData::new(G_PRECISION, SUPPLY),
-                StateData::new(G_SUPPLY, TOKEN_ID),
+                StateData::new(G_NFT, TOKEN_ID),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_RGB21_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(res);
+    }
+
+    #[test]
+    fn triple_layout() {
+        const MAX_FRACTIONS: u64 = 10;
+        let alloc = nft_allocation(3, 4);
+        assert_eq!(
+            alloc,
+            StateValue::Triple {
+                first: O_AMOUNT.into(),
+                second: 3u64.into(),
+                third: 4u64.into(),
+            }
+        );
+
+        // Token ids and fractions are chosen such that swapping the fields breaks the genesis
+        let output = [(3, 10), (7, 4), (7, 6)].map(|(token_id, fractions)| StateCell {
+            data: nft_allocation(token_id, fractions),
+            auth: AuthToken::strict_dumb(),
+            lock: None,
+        });
+        let swapped = output.clone().map(|cell| {
+            let StateValue::Triple {
+                first,
+                second,
+                third,
+            } = cell.data
+            else {
+                unreachable!()
+            };
+            StateCell {
+                data: StateValue::Triple {
+                    first,
+                    second: third,
+                    third: second,
+                },
+                ..cell
+            }
+        });
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &output,
+            immutable_output: &[
+                StateData::new(G_DETAILS, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, MAX_FRACTIONS),
+                StateData::new(G_NFT, 3u64),
+                StateData::new(G_NFT, 7u64),
             ],
         };
         let (lib, mut vm, resolver) = harness();
@@ -407,5 +797,360 @@ mod tests {
             .exec(lib.routine(FN_RGB21_ISSUE), &context, resolver)
             .is_ok();
         assert!(res);
+
+        let context = VmContext {
+            destructible_output: &swapped,
+            ..context
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_RGB21_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(!res);
+    }
+
+    #[test]
+    fn genesis_fractions_max() {
+        assert!(genesis_fractions(1000, &[1000]).0);
+        assert!(genesis_fractions(1000, &[100, 400, 500]).0);
+    }
+
+    #[test]
+    fn genesis_fractions_under() {
+        let (res, errno) = genesis_fractions(1000, &[100, 400, 499]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_FRACTIONS_MISMATCH);
+    }
+
+    #[test]
+    fn genesis_fractions_over() {
+        let (res, errno) = genesis_fractions(1000, &[100, 400, 501]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_FRACTIONS_MISMATCH);
+    }
+
+    fn merge_only(inputs: &[(u64, u64)], outputs: &[(u64, u64)]) -> (bool, u256) {
+        let ctx = crate::test_helpers::nft_ctx(inputs, outputs);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_NFT_MERGE_ONLY_TRANSFER),
+                &ctx.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn merge_only_merge() {
+        let (res, _) = merge_only(&[(1, 10), (1, 20), (1, 30)], &[(1, 60)]);
+        assert!(res);
+    }
+
+    #[test]
+    fn merge_only_pass_through() {
+        let (res, _) = merge_only(&[(1, 60)], &[(1, 60)]);
+        assert!(res);
+    }
+
+    #[test]
+    fn merge_only_split() {
+        let (res, errno) = merge_only(&[(1, 60)], &[(1, 30), (1, 30)]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_SPLIT_FORBIDDEN);
+    }
+
+    #[test]
+    fn merge_only_mint() {
+        let (res, errno) = merge_only(&[], &[(1, 60)]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_UNKNOWN_OUTPUT_TOKEN);
+
+        let (res, errno) = merge_only(&[(1, 60)], &[(1, 60), (2, 60)]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_UNKNOWN_OUTPUT_TOKEN);
+    }
+
+    #[test]
+    fn merge_only_tokens() {
+        let (res, _) = merge_only(&[(1, 10), (2, 20), (1, 30)], &[(2, 20), (1, 40)]);
+        assert!(res);
+
+        let (res, errno) = merge_only(&[(1, 10), (2, 20)], &[(1, 10), (2, 10), (2, 10)]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_SPLIT_FORBIDDEN);
+    }
+
+    #[test]
+    fn merge_only_mismatch() {
+        let (res, errno) = merge_only(&[(1, 10), (1, 20)], &[(1, 20)]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_FRACTIONS_MISMATCH);
+    }
+
+    fn transfer(inputs: &[(u64, u64)], outputs: &[(u64, u64)]) -> (bool, u256) {
+        let ctx = crate::test_helpers::nft_ctx(inputs, outputs);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_DIVISIBLE_TRANSFER),
+                &ctx.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn transfer_unexpected_owned_type() {
+        let balance = StateValue::new(O_AMOUNT_B, 100u64);
+        let token = |fractions: u64| nft_allocation(1, fractions);
+        let cases = [
+            (
+                vec![token(10), balance],
+                vec![token(10)],
+                ERRNO_UNEXPECTED_OWNED_TYPE_IN,
+            ),
+            (
+                vec![balance, token(10)],
+                vec![token(10)],
+                ERRNO_UNEXPECTED_OWNED_TYPE_IN,
+            ),
+            (
+                vec![token(10)],
+                vec![token(10), balance],
+                ERRNO_UNEXPECTED_OWNED_TYPE_OUT,
+            ),
+        ];
+        for (inputs, outputs, expected) in cases {
+            let ctx = crate::test_helpers::TestContext::with(&inputs, &outputs);
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(
+                    lib.routine(FN_DIVISIBLE_TRANSFER),
+                    &ctx.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            assert!(!res);
+            assert_eq!(errno(&vm), expected);
+        }
+    }
+
+    #[test]
+    fn sum_unexpected_owned_type() {
+        let balance = StateValue::new(O_AMOUNT_B, 100u64);
+        for (routine, inputs, outputs, expected) in [
+            (
+                FN_NFT_SUM_INPUTS,
+                vec![balance],
+                vec![],
+                ERRNO_UNEXPECTED_OWNED_TYPE_IN,
+            ),
+            (
+                FN_NFT_SUM_OUTPUTS,
+                vec![],
+                vec![balance],
+                ERRNO_UNEXPECTED_OWNED_TYPE_OUT,
+            ),
+        ] {
+            let ctx = crate::test_helpers::TestContext::with(&inputs, &outputs);
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(lib.routine(routine), &ctx.as_context(), resolver)
+                .is_ok();
+            assert!(!res);
+            assert_eq!(errno(&vm), expected);
+        }
+    }
+
+    #[test]
+    fn transfer_fractions_mismatch_errno() {
+        assert_eq!(
+            transfer(&[(1, 10)], &[(1, 11)]),
+            (false, ERRNO_FRACTIONS_MISMATCH)
+        );
+    }
+
+    #[test]
+    fn transfer_extra_field_element() {
+        let token = nft_allocation(1, 10);
+        let extra = StateValue::Quadruple {
+            first: O_AMOUNT.into(),
+            second: 1u64.into(),
+            third: 10u64.into(),
+            fourth: 1u64.into(),
+        };
+        for (inputs, outputs) in [(vec![extra], vec![token]), (vec![token], vec![extra])] {
+            let ctx = crate::test_helpers::TestContext::with(&inputs, &outputs);
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(
+                    lib.routine(FN_DIVISIBLE_TRANSFER),
+                    &ctx.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            assert!(!res);
+            assert_eq!(errno(&vm), ERRNO_FRACTIONS_MISMATCH);
+        }
+    }
+
+    #[test]
+    fn transfer_two_tokens() {
+        let (res, errno) = transfer(
+            &[(1, 10), (2, 5), (1, 20)],
+            &[(2, 2), (1, 25), (2, 3), (1, 5)],
+        );
+        assert!(res);
+        assert_eq!(errno, u256::ZERO);
+    }
+
+    #[test]
+    fn transfer_two_tokens_unbalanced() {
+        let (res, errno) = transfer(&[(1, 10), (2, 5)], &[(1, 10), (2, 6)]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_FRACTIONS_MISMATCH);
+    }
+
+    #[test]
+    fn transfer_zero_fraction() {
+        assert_eq!(
+            transfer(&[(1, 10), (2, 5)], &[(1, 10), (2, 5), (1, 0)]),
+            (false, ERRNO_ZERO_FRACTION)
+        );
+        assert_eq!(
+            transfer(&[(1, 10), (2, 0), (2, 5)], &[(1, 10), (2, 5)]),
+            (false, ERRNO_ZERO_FRACTION)
+        );
+    }
+
+    #[test]
+    fn transfer_token_not_in_inputs() {
+        let (res, errno) = transfer(&[(1, 10)], &[(1, 10), (2, 1)]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_UNKNOWN_OUTPUT_TOKEN);
+
+        let (res, errno) = transfer(&[(1, 10)], &[(2, 10)]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_UNKNOWN_OUTPUT_TOKEN);
+    }
+
+    #[test]
+    fn transfer_token_in_inputs() {
+        assert_eq!(
+            transfer(&[(1, 10), (2, 5)], &[(2, 5), (1, 4), (1, 6)]),
+            (true, u256::ZERO)
+        );
+        // A token which is present in the inputs, but not in the outputs, is a mismatch instead
+        assert_eq!(
+            transfer(&[(1, 10), (2, 5)], &[(1, 10)]),
+            (false, ERRNO_FRACTIONS_MISMATCH)
+        );
+    }
+
+    fn batch_issue(tokens: &[u64], outputs: &[(u64, u64)]) -> (bool, u256) {
+        let mut globals = vec![
+            StateData::new(G_DETAILS, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 100u64),
+        ];
+        globals.extend(tokens.iter().map(|id| StateData::new(G_NFT, *id)));
+        let output = outputs
+            .iter()
+            .map(|(id, fractions)| StateCell {
+                data: nft_allocation(*id, *fractions),
+                auth: AuthToken::strict_dumb(),
+                lock: None,
+            })
+            .collect::<Vec<_>>();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: output.as_slice(),
+            immutable_output: globals.as_slice(),
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_DIVISIBLE_BATCH_ISSUE), &context, resolver)
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn batch_issue_grouped() {
+        assert_eq!(
+            batch_issue(&[1, 2, 5], &[(1, 100), (2, 30), (2, 70), (5, 50), (5, 50)]),
+            (true, u256::ZERO)
+        );
+        assert_eq!(batch_issue(&[], &[]), (true, u256::ZERO));
+    }
+
+    #[test]
+    fn batch_issue_not_grouped() {
+        assert_eq!(
+            batch_issue(&[1, 2], &[(1, 100), (2, 100), (1, 10)]),
+            (false, ERRNO_OUTPUTS_NOT_GROUPED)
+        );
+        assert_eq!(
+            batch_issue(&[1, 2, 3], &[(1, 100), (2, 100), (1, 10), (3, 100)]),
+            (false, ERRNO_OUTPUTS_NOT_GROUPED)
+        );
+        // A group split by another token fails on the fractions of its first part
+        assert_eq!(
+            batch_issue(&[1, 2], &[(1, 50), (2, 100), (1, 50)]),
+            (false, ERRNO_FRACTIONS_MISMATCH)
+        );
+    }
+
+    #[test]
+    fn batch_issue_out_of_order() {
+        assert_eq!(
+            batch_issue(&[1, 2], &[(2, 100), (1, 100)]),
+            (false, ERRNO_FRACTIONS_MISMATCH)
+        );
+        assert_eq!(
+            batch_issue(&[2, 1], &[(2, 100), (1, 100)]),
+            (false, ERRNO_TOKENS_UNSORTED)
+        );
+        assert_eq!(
+            batch_issue(&[1, 1], &[(1, 100), (1, 100)]),
+            (false, ERRNO_TOKENS_UNSORTED)
+        );
+    }
+
+    #[test]
+    fn batch_issue_undeclared() {
+        assert_eq!(
+            batch_issue(&[1, 2], &[(1, 100), (2, 100), (3, 100)]),
+            (false, ERRNO_UNDECLARED_TOKEN)
+        );
+    }
+
+    #[test]
+    fn batch_issue_fractions_mismatch() {
+        assert_eq!(
+            batch_issue(&[1, 2], &[(1, 100), (2, 30), (2, 60)]),
+            (false, ERRNO_FRACTIONS_MISMATCH)
+        );
+        assert_eq!(
+            batch_issue(&[1, 2], &[(1, 100), (2, 30), (2, 80)]),
+            (false, ERRNO_FRACTIONS_MISMATCH)
+        );
+    }
+
+    #[test]
+    fn issue_is_first() {
+        let lib = divisible();
+        assert_eq!(lib.routine(FN_RGB21_ISSUE).offset, 0);
+        // The first routine verifies genesis, which is rejected by the transfer
+        assert!(genesis_routine(FN_RGB21_ISSUE, 1000, &[400, 600]).0);
+        assert_eq!(
+            genesis_routine(FN_RGB21_ISSUE, 1000, &[400, 599]),
+            (false, ERRNO_FRACTIONS_MISMATCH)
+        );
+        assert!(!genesis_routine(FN_DIVISIBLE_TRANSFER, 1000, &[400, 600]).0);
     }
 }
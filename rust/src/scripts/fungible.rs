@@ -21,17 +21,91 @@
 // the License.
 
 use amplify::num::u256;
-use hypersonic::uasm;
-use zkaluvm::alu::CompiledLib;
+use hypersonic::{uasm, Instr, StateCell, StateValue};
+use zkaluvm::alu::{CompiledLib, LibId};
 
-use super::{shared_lib, FN_ASSET_SPEC, FN_GLOBAL_ABSENT};
-use crate::{G_SUPPLY, O_AMOUNT};
+use super::{
+    shared_lib, validate_dependencies, ERRNO_EMPTY_NAME, ERRNO_EMPTY_STATE,
+    ERRNO_UNEXPECTED_GLOBAL_IN, FN_ADD_CHECKED, FN_ASSERT_PAIR_VALUE, FN_ASSERT_SIMPLE_VALUE,
+    FN_ASSET_DETAILS, FN_ASSET_SPEC, FN_GLOBAL_ABSENT,
+};
+use crate::{
+    G_DETAILS, G_LOT_SIZE, G_MAX_PER_OUTPUT, G_MIN_OUTPUT, G_NAME, G_PRECISION,
+    G_RECIPIENT_CLASSES, G_RETIRED, G_SUPPLY, G_TICKER, MAX_PRECISION, O_ALLOWANCE, O_AMOUNT,
+    O_RETIRE_RIGHT,
+};
 
+/// Issue verification.
+///
+/// The precision ([`G_PRECISION`]) must not exceed [`MAX_PRECISION`], failing with
+/// [`ERRNO_PRECISION_OVERFLOW`] otherwise. The precision must be declared only once, failing with
+/// [`ERRNO_MULTIPLE_PRECISION`](super::ERRNO_MULTIPLE_PRECISION) otherwise.
+///
+/// The issued supply ([`G_SUPPLY`]) must be declared right after the asset specification, i.e. as
+/// the fourth global state, optionally followed by the details and the reference. The failures
+/// related to the supply declaration are reported with:
+/// - [`ERRNO_NO_ISSUED`] if there is no supply declared as the fourth global state;
+/// - [`ERRNO_SUPPLY_MISPLACED`] if the supply is declared before the precision;
+/// - [`ERRNO_MULTIPLE_SUPPLY`] if the supply is declared more than once;
+/// - [`ERRNO_UNEXPECTED_GLOBAL`] if there is an extra global state after the supply, details and
+///   reference.
+///
+/// The ticker ([`G_TICKER`]) must carry a value, failing with [`ERRNO_EMPTY_TICKER`] otherwise.
 pub const FN_FUNGIBLE_ISSUE: u16 = 0;
-pub const FN_FUNGIBLE_TRANSFER: u16 = 1;
+
+/// Issue verification without the ticker value check, shared by [`FN_FUNGIBLE_ISSUE`] and
+/// [`FN_FUNGIBLE_ISSUE_RGB25`], whose first global state holds the details instead of the ticker.
+///
+/// Verifies the specification and the supply with [`FN_FUNGIBLE_ISSUE_SPEC`], checks that the
+/// supply is allocated to the outputs, and that only the optional details and reference follow it.
+///
+/// # Reset registers
+///
+/// `E2`-`E4`, `E7`, `E8`, `EA`-`ED`.
+pub const FN_FUNGIBLE_ISSUE_ALLOCATION: u16 = 1;
+
+/// Checks that the ticker ([`G_TICKER`]), which is the first global state, carries a value, failing
+/// with [`ERRNO_EMPTY_TICKER`] otherwise. Called at the end of all the RGB20 issue verifiers, after
+/// the type of the ticker is checked by [`FN_ASSET_SPEC`].
+///
+/// # Reset registers
+///
+/// `EA`-`ED`.
+///
+/// # Side effects
+///
+/// Leaves the output global state iterator after the ticker.
+pub const FN_FUNGIBLE_TICKER: u16 = 2;
+
+/// Common part of all the fungible issue verifiers: the asset specification and the issued supply.
+///
+/// Checks that genesis uses no global state, validates the asset specification with
+/// [`FN_ASSET_SPEC`] and the precision, and then reads the issued supply ([`G_SUPPLY`]), which
+/// must be declared right after the specification and must not be zero, failing with
+/// [`ERRNO_ZERO_SUPPLY`] otherwise. All the issue verifiers ([`FN_FUNGIBLE_ISSUE`] and its
+/// variants) call this procedure first, such that the supply checks can't diverge between them.
+///
+/// # Output
+///
+/// `E2` contains the issued supply, and `E4` the precision.
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `E7`, `E8`.
+///
+/// # Side effects
+///
+/// Leaves the output global state iterator right after the supply declaration.
+pub const FN_FUNGIBLE_ISSUE_SPEC: u16 = 3;
+pub const FN_FUNGIBLE_TRANSFER: u16 = 4;
 
 /// Sum input owned state
 ///
+/// All owned values are treated as unsigned 64-bit integers: a value must fit into 64 bits, and any
+/// field element `>= 2^64` fails the verification with [`ERRNO_INVALID_BALANCE_IN`]. Since field
+/// arithmetic is modular, this also rejects values which may be seen as negative numbers in some
+/// interpretation (like `-1`, which is the field order minus one).
+///
 /// # Input
 ///
 /// None
@@ -42,15 +116,34 @@ pub const FN_FUNGIBLE_TRANSFER: u16 = 1;
 ///
 /// # Reset registers
 ///
-/// `EA`-`ED`.
+/// `EA`-`ED`, `E5`, `E7`, `E8`.
 ///
 /// # Side effects
 ///
 /// Extinguishes the input destructible state iterator
-pub const FN_FUNGIBLE_SUM_INPUTS: u16 = 2;
+///
+/// # Errors
+///
+/// Fails with [`ERRNO_TOO_MANY_IO`] if the number of inputs exceeds the limit provided to
+/// [`fungible_with_max_io`]; with no limit, the inputs are not counted.
+///
+/// Fails with [`ERRNO_EMPTY_STATE`] if an input has no state type (i.e. it is
+/// [`StateValue::None`](hypersonic::StateValue::None)).
+///
+/// Fails with [`ERRNO_INVALID_BALANCE_IN`] if a value has extra field elements, as checked by
+/// [`FN_ASSERT_SIMPLE_VALUE`].
+///
+/// Fails with [`ERRNO_SUM_OVERFLOW`](crate::ERRNO_SUM_OVERFLOW) if the sum doesn't fit into 64
+/// bits, as checked by [`FN_ADD_CHECKED`].
+pub const FN_FUNGIBLE_SUM_INPUTS: u16 = 5;
 
 /// Sum output owned state
 ///
+/// All owned values are treated as unsigned 64-bit integers: a value must fit into 64 bits, and any
+/// field element `>= 2^64` fails the verification with [`ERRNO_INVALID_BALANCE_OUT`]. Since field
+/// arithmetic is modular, this also rejects values which may be seen as negative numbers in some
+/// interpretation (like `-1`, which is the field order minus one).
+///
 /// # Input
 ///
 /// None
@@ -61,446 +154,3593 @@ pub const FN_FUNGIBLE_SUM_INPUTS: u16 = 2;
 ///
 /// # Reset registers
 ///
-/// `EA`-`ED`, `E8`.
+/// `EA`-`ED`, `E5`, `E7`, `E8`.
 ///
 /// # Side effects
 ///
 /// Extinguishes the output destructible state iterator
-pub const FN_FUNGIBLE_SUM_OUTPUTS: u16 = 4;
-
-pub const ERRNO_PRECISION_OVERFLOW: u256 = u256::from_inner([1, 1, 0, 0]);
-pub const ERRNO_NO_ISSUED: u256 = u256::from_inner([2, 1, 0, 0]);
-pub const ERRNO_SUM_ISSUE_MISMATCH: u256 = u256::from_inner([3, 1, 0, 0]);
-pub const ERRNO_UNEXPECTED_GLOBAL: u256 = u256::from_inner([4, 1, 0, 0]);
-pub const ERRNO_SUM_MISMATCH: u256 = u256::from_inner([5, 1, 0, 0]);
-pub const ERRNO_UNEXPECTED_OWNED_TYPE_IN: u256 = u256::from_inner([6, 1, 0, 0]);
-pub const ERRNO_INVALID_BALANCE_IN: u256 = u256::from_inner([7, 1, 0, 0]);
-pub const ERRNO_UNEXPECTED_OWNED_TYPE_OUT: u256 = u256::from_inner([8, 1, 0, 0]);
-pub const ERRNO_INVALID_BALANCE_OUT: u256 = u256::from_inner([9, 1, 0, 0]);
-
-pub fn fungible() -> CompiledLib {
-    const LOOP_INPUTS: u16 = 3;
-    const LOOP_OUTPUTS: u16 = 5;
+///
+/// # Errors
+///
+/// Fails with [`ERRNO_TOO_MANY_IO`] if the number of outputs exceeds the limit provided to
+/// [`fungible_with_max_io`]; with no limit, the outputs are not counted.
+///
+/// Fails with [`ERRNO_INVALID_BALANCE_OUT`] if a value has extra field elements, as checked by
+/// [`FN_ASSERT_SIMPLE_VALUE`].
+///
+/// Fails with [`ERRNO_SUM_OVERFLOW`](crate::ERRNO_SUM_OVERFLOW) if the sum doesn't fit into 64
+/// bits, as checked by [`FN_ADD_CHECKED`].
+pub const FN_FUNGIBLE_SUM_OUTPUTS: u16 = 7;
 
-    let shared = shared_lib().into_lib().lib_id();
+/// Issue verification with a per-output cap.
+///
+/// Works like [`FN_FUNGIBLE_ISSUE`], but additionally requires the [`G_MAX_PER_OUTPUT`] global to
+/// follow the supply declaration (optionally followed by the details and the reference), and
+/// ensures that no single output exceeds it.
+pub const FN_FUNGIBLE_ISSUE_CAPPED: u16 = 9;
 
-    let mut code = uasm! {
-     routine FN_FUNGIBLE_ISSUE:
-        call    shared, FN_ASSET_SPEC;// Call asset check
+/// Sum output owned state, checking that each of the outputs doesn't exceed a cap.
+///
+/// # Input
+///
+/// `E5` contains the maximum value allowed for a single output.
+///
+/// # Output
+///
+/// `E3` contains the sum of outputs.
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `E6`, `E8`.
+///
+/// # Side effects
+///
+/// Extinguishes the output destructible state iterator
+pub const FN_FUNGIBLE_SUM_OUTPUTS_CAPPED: u16 = 10;
 
-        put     E1, ERRNO_PRECISION_OVERFLOW; // Set error code for the case of failure
-        fits    E4, 8.bits;     // The precision must fit into a byte
-        chk     CO;             // - or fail otherwise
+/// Lock script for permanently sealed allocations, which always fails with [`ERRNO_SEALED_INPUT`].
+///
+/// Codex verifiers have no access to the locks of the spent state, thus sealing is not performed by
+/// the transfer verifier. Instead, an issuer seals an allocation by assigning this routine as the
+/// `lock` of its [`StateCell`](hypersonic::StateCell), either in genesis or in a later operation.
+/// Since a validator runs the lock script of each input (under the codex `input_config`) before
+/// running the operation verifier, any operation which spends a sealed allocation fails, while
+/// unlocked allocations remain transferable with the normal [`FN_FUNGIBLE_TRANSFER`].
+///
+/// Sealing is irreversible: the lock can't be removed or replaced, since this would require
+/// spending the allocation, and neither the issuer nor the owner can ever do that. Thus, sealing
+/// an allocation takes its amount out of circulation forever, while the issued supply still
+/// accounts for it.
+///
+/// A codex using sealing must set `input_config` to a configuration allowing the lock script
+/// execution.
+pub const FN_FUNGIBLE_LOCK_UNSPENDABLE: u16 = 12;
 
-        // Validate circulating supply
-        put     E1, ERRNO_NO_ISSUED; // Set error code for the case of failure
-        ldo     immutable;      // Read last global state - circulating supply
-        chk     CO;             // It must exist
-        put     E8, G_SUPPLY;   // Load supply type
-        eq      EA, E8;         // It must have a correct state type
-        chk     CO;             // Or fail otherwise
-        test    EB;             // It must be set
-        chk     CO;             // Or we should fail
-        mov     E2, EB;         // Save supply
-        test    EC;             // ensure other field elements are empty
-        not     CO;             // invert CO value (we need the test to fail)
-        chk     CO;             // fail if not
-        test    ED;             // ensure other field elements are empty
-        not     CO;             // invert CO value (we need the test to fail)
-        chk     CO;             // fail if not
+/// Issue verification ensuring that the supply can be converted into the display denomination.
+///
+/// Works like [`FN_FUNGIBLE_ISSUE`], but additionally requires the issued supply to not exceed
+/// `u64::MAX / 10^precision`, such that multiplying it by `10^precision` doesn't overflow.
+pub const FN_FUNGIBLE_ISSUE_SCALED: u16 = 13;
 
-        // Validate that the issued amount is equal to the sum of the outputs
-        put     E3, 0;          // E3 will contain the sum of outputs
-        call    FN_FUNGIBLE_SUM_OUTPUTS;// Compute a sum of outputs
-        put     E1, ERRNO_SUM_ISSUE_MISMATCH; // Set error code for the case of failure
-        eq      E2, E3;         // check that circulating supply equals to the sum of outputs
-        chk     CO;             // fail if not
+/// Checks that the supply multiplied by `10^precision` fits into `u64`.
+///
+/// # Input
+///
+/// `E2` contains the supply, `E4` contains the precision.
+///
+/// # Output
+///
+/// None
+///
+/// # Reset registers
+///
+/// `E5`-`E8`.
+pub const FN_FUNGIBLE_SUPPLY_SCALE: u16 = 14;
 
-        // Check there is no more global state
-        put     E1, ERRNO_UNEXPECTED_GLOBAL; // Set error code for the case of failure
-        ldo     immutable;
-        not     CO;
-        chk     CO;
+/// Blank transition verification.
+///
+/// Blank transitions move the state of the contract to new seals without a transfer, and thus must
+/// only repartition the existing value: the routine delegates to [`FN_FUNGIBLE_TRANSFER`], which
+/// forbids global state and requires the sum of inputs to be equal to the sum of outputs. Owners of
+/// the state are not accessible to the VM and are checked by the client-side validation instead.
+///
+/// The routine is a separate entry point to allow adding blank-specific rules in the future
+/// without changing the verifier of the codices using it.
+pub const FN_FUNGIBLE_BLANK: u16 = 17;
 
-        clr     E1;             // Clear the error code
-        ret;
+/// Issue verification for RGB25 assets, which require asset details.
+///
+/// Works like [`FN_FUNGIBLE_ISSUE`] (using [`FN_FUNGIBLE_ISSUE_ALLOCATION`]), but instead of the
+/// ticker value requires the first global state to be the [`G_DETAILS`] global with no typed value,
+/// failing with [`ERRNO_NO_DETAILS`] if the details are missing, misplaced or typed. RGB25 details
+/// share the field element with the RGB20 ticker (`G_DETAILS == G_TICKER`), and are distinguished
+/// from it by the interface only: the details are always declared first and keep all their data in
+/// the raw (non-verifiable) state, thus their verifiable state may hold at most a single field
+/// element. The details are checked before the rest of the specification, thus the routine doesn't
+/// report the missing ticker of [`FN_ASSET_SPEC`].
+pub const FN_FUNGIBLE_ISSUE_RGB25: u16 = 18;
 
-     routine FN_FUNGIBLE_TRANSFER:
-        // Verify that no global state is defined
-        call    shared, FN_GLOBAL_ABSENT;
+/// Renomination: re-declaration of the asset ticker and/or name.
+///
+/// Works like [`FN_FUNGIBLE_TRANSFER`], conserving the owned value, but allows the operation to
+/// declare new [`G_TICKER`] and [`G_NAME`] globals (in any order and number), each of which must
+/// carry a value, failing with [`ERRNO_EMPTY_TICKER`] or [`ERRNO_EMPTY_NAME`] otherwise. Declaring
+/// [`G_PRECISION`] or [`G_SUPPLY`] fails with [`ERRNO_RENAME_CHANGED_SUPPLY`], and any other global
+/// state fails with [`ERRNO_UNEXPECTED_GLOBAL`].
+///
+/// NB: The routine doesn't check who renominates the asset: any party able to create an operation
+/// may re-declare the ticker and the name. Thus, it is not used by the standard issuers, and is
+/// provided only by the [`crate::demo::nfa`] demo codex.
+pub const FN_FUNGIBLE_RENAME: u16 = 19;
 
-        // Verify owned state
-        call    FN_FUNGIBLE_SUM_INPUTS; // Compute a sum of inputs into E2
-        call    FN_FUNGIBLE_SUM_OUTPUTS; // Compute a sum of outputs into E3
-        put     E1, ERRNO_SUM_MISMATCH; // Set error code for the case of failure
-        // TODO: Check the sum is not zero
-        eq      E2, E3;         // check that the sum of inputs equals the sum of outputs
-        chk     CO;             // fail if not
+/// Checks that the issued supply, if declared, follows the precision and is declared only once.
+///
+/// Scans all the global state, failing with [`ERRNO_SUPPLY_MISPLACED`] if the supply precedes the
+/// precision, and with [`ERRNO_MULTIPLE_SUPPLY`] if more than one supply is declared, regardless of
+/// their position.
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `EH`, `E7`.
+///
+/// # Side effects
+///
+/// Extinguishes the global state output iterator
+pub const FN_FUNGIBLE_SUPPLY_PLACEMENT: u16 = 23;
 
-        clr     E1;             // Clear the error code
-        ret;
+/// Issue verification for airdrop lists, where each output may be tagged with a recipient class.
+///
+/// Works like [`FN_FUNGIBLE_ISSUE`], but additionally requires the [`G_RECIPIENT_CLASSES`] global
+/// to follow the supply declaration. The global commits to an allowlist of up to three recipient
+/// classes, kept in its second to fourth field elements; at least one of them must be set, or the
+/// verification fails with [`ERRNO_NO_RECIPIENT_CLASSES`].
+///
+/// A genesis output may carry a recipient class in its third field element (`EC`), which must be
+/// one of the listed classes, or the verification fails with [`ERRNO_UNLISTED_RECIPIENT_CLASS`].
+/// Outputs with no class are always allowed. The class is a genesis-only tag: tagged allocations
+/// are not accepted as inputs by [`FN_FUNGIBLE_TRANSFER`].
+pub const FN_FUNGIBLE_ISSUE_CLASSED: u16 = 26;
 
-     proc FN_FUNGIBLE_SUM_INPUTS:
-        put     E2, 0;          // Set initial sum to zero
-        put     EH, O_AMOUNT;   // Set EH to the field element representing the owned value
-        rsti    destructible;   // Start iteration over inputs
+/// Sum output owned state, checking the recipient class of each of the outputs.
+///
+/// # Input
+///
+/// `EF`, `EG` and `E6` contain the allowed recipient classes (unset registers match no class).
+///
+/// # Output
+///
+/// `E3` contains the sum of outputs.
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `EH`, `E8`.
+///
+/// # Side effects
+///
+/// Extinguishes the output destructible state iterator
+pub const FN_FUNGIBLE_SUM_OUTPUTS_CLASSED: u16 = 27;
 
-     label LOOP_INPUTS:
-        ldi     destructible;   // load next state value
+/// Transfer requiring a change output when several allocations are spent.
+///
+/// Works like [`FN_FUNGIBLE_TRANSFER`], but additionally requires an operation spending two or
+/// more inputs to have at least two outputs, failing with [`ERRNO_NO_CHANGE_OUTPUT`] otherwise.
+/// This discourages fully-consolidating transfers, which reveal that all the spent allocations
+/// belong to the same owner.
+pub const FN_FUNGIBLE_TRANSFER_WITH_CHANGE: u16 = 30;
 
-        // Finish if no more elements are present
-        not     CO;
-        jif     CO, +3;
-        ret;
+/// Issue verification for an asset traded in lots.
+///
+/// Works like [`FN_FUNGIBLE_ISSUE_CAPPED`], but instead of the cap requires the [`G_LOT_SIZE`]
+/// global to follow the supply declaration (failing with [`ERRNO_NO_LOT_SIZE`] otherwise), which
+/// may be followed by the details and the reference. Like all the issue verifiers, it rejects a
+/// zero supply with [`ERRNO_ZERO_SUPPLY`] (see [`FN_FUNGIBLE_ISSUE_SPEC`]): otherwise, an issue
+/// with no outputs would trivially pass the lot size check. Each of the outputs must be a multiple
+/// of the lot size, as checked by [`FN_FUNGIBLE_LOTS_OUTPUTS`].
+pub const FN_FUNGIBLE_ISSUE_LOTS: u16 = 32;
 
-        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_IN; // Set error code for the case of failure
-        eq      EA, EH;         // do we have a correct state type?
-        chk     CO;             // fail if not
+/// Transfer of an asset traded in lots.
+///
+/// Works like [`FN_FUNGIBLE_TRANSFER`], but requires the [`G_LOT_SIZE`] global declared in genesis
+/// to be provided as the only global input of the operation (failing with [`ERRNO_NO_LOT_SIZE`]
+/// otherwise). Each of the outputs must be a multiple of the lot size, as checked by
+/// [`FN_FUNGIBLE_LOTS_OUTPUTS`].
+pub const FN_FUNGIBLE_TRANSFER_LOTS: u16 = 33;
 
-        put     E1, ERRNO_INVALID_BALANCE_IN; // Set error code for the case of failure
-        eq      EC, EE;         // ensure EC is not set
-        not     CO;
-        chk     CO;             // fail if not
+/// Checks that each of the outputs is a multiple of the lot size.
+///
+/// Since there is no division instruction, the remainder is computed by repeatedly subtracting the
+/// largest multiple of the lot size of the form `lot * 2^k` not exceeding it. This takes up to
+/// `64 * 64` iterations per output, so the procedure is more expensive than a simple sum.
+///
+/// # Input
+///
+/// `E6` contains the lot size, which must be a non-zero 64-bit value.
+///
+/// The output values must be already checked to fit into 64 bits, like it is done by
+/// [`FN_FUNGIBLE_SUM_OUTPUTS`].
+///
+/// # Output
+///
+/// None
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `E5`, `E7`, `E8`, `EH`.
+///
+/// # Side effects
+///
+/// Extinguishes the output destructible state iterator
+///
+/// # Errors
+///
+/// Fails with [`ERRNO_NON_LOT_OUTPUT`] if an output is not a multiple of the lot size.
+pub const FN_FUNGIBLE_LOTS_OUTPUTS: u16 = 34;
 
-        test    ED;             // ensure ED is not set
-        not     CO;
-        chk     CO;             // fail if not
+/// Validates the lot size global and saves it into `E6`.
+///
+/// # Input
+///
+/// `EA`-`ED` contain the loaded [`G_LOT_SIZE`] global; `E1` contains the error code to fail with.
+///
+/// # Output
+///
+/// `E6` contains the lot size.
+///
+/// # Reset registers
+///
+/// `E8`.
+///
+/// # Errors
+///
+/// Fails with the error code provided in `E1` if the global has a different state type, or its
+/// value is not a non-zero 64-bit value in a single field element.
+pub const FN_FUNGIBLE_LOT_SIZE: u16 = 40;
 
-        fits    EB, 64.bits;    // ensure the value fits in u64
-        chk     CO;             // fail if not
-        add     E2, EB;         // add input to input accumulator
-        fits    E2, 64.bits;    // ensure we do not overflow
-        chk     CO;             // fail if not
+/// Delegated transfer, spending an operator allowance.
+///
+/// An allowance ([`O_ALLOWANCE`]) is an escrow: it holds the approved amount itself, and is
+/// assigned to a seal of the operator (see [`FN_FUNGIBLE_APPROVE`]). Thus, the authority to spend
+/// it comes from the seal, checked by the client-side validation like for any other state, and
+/// not from the VM, which has no access to the owners of the state.
+///
+/// The operation must spend exactly one allowance, failing with [`ERRNO_NO_ALLOWANCE`] if there is
+/// no allowance and with [`ERRNO_INVALID_ALLOWANCE`] if there is more than one; it may also spend
+/// balances ([`O_AMOUNT`]) owned by the operator. The sum of the spent allowance and balances must
+/// be equal to the sum of the balance outputs and the remaining allowance, like in
+/// [`FN_FUNGIBLE_TRANSFER`].
+///
+/// The operation may keep the unused part of the allowance as a single allowance output, which
+/// must keep the grantee of the spent allowance (failing with [`ERRNO_INVALID_ALLOWANCE`]
+/// otherwise) and must not exceed the spent allowance amount (failing with
+/// [`ERRNO_ALLOWANCE_EXCEEDED`] otherwise). The operator returns the unused allowance to the
+/// holder by transferring it into a balance.
+pub const FN_FUNGIBLE_TRANSFER_FROM: u16 = 41;
 
-        jmp     LOOP_INPUTS;    // loop
+/// Transfer granting operator allowances.
+///
+/// Works like [`FN_FUNGIBLE_TRANSFER`], but the operation may additionally define any number of
+/// allowance ([`O_ALLOWANCE`]) outputs, which are later spent with [`FN_FUNGIBLE_TRANSFER_FROM`].
+/// Each allowance must have an amount fitting into 64 bits and a grantee commitment, failing with
+/// [`ERRNO_INVALID_ALLOWANCE`] otherwise.
+///
+/// The allowances are counted in the value conservation: the approved amount moves from the
+/// balances of the holder into the allowance, which must be assigned to a seal of the operator.
+/// The holder can't revoke the allowance afterwards; only the operator can spend it.
+pub const FN_FUNGIBLE_APPROVE: u16 = 48;
 
-     proc FN_FUNGIBLE_SUM_OUTPUTS:
-        put     E3, 0;          // Set initial sum to zero
-        put     EH, O_AMOUNT;   // Set EH to the field element representing the owned value
-        rsto    destructible;   // Start iteration over outputs
+/// Validates the value of an allowance ([`O_ALLOWANCE`]).
+///
+/// # Input
+///
+/// `EB`-`ED` contain the loaded allowance value; `E1` contains the error code to fail with.
+///
+/// # Output
+///
+/// None
+///
+/// # Errors
+///
+/// Fails with the error code provided in `E1` if the amount in `EB` is not set or doesn't fit into
+/// 64 bits, if the grantee commitment in `EC` is not set, or if `ED` is set.
+pub const FN_FUNGIBLE_ALLOWANCE_VALUE: u16 = 52;
 
-     label LOOP_OUTPUTS:
-        ldo     destructible;   // load next state value
+/// Issue verification for an asset forbidding dust outputs.
+///
+/// Works like [`FN_FUNGIBLE_ISSUE`], but additionally requires the [`G_MIN_OUTPUT`] global to
+/// follow the supply declaration (failing with [`ERRNO_NO_MIN_OUTPUT`] otherwise), which may be
+/// followed by the details and the reference. The outputs are checked against the minimal output
+/// value with [`FN_FUNGIBLE_MIN_OUTPUTS`].
+pub const FN_FUNGIBLE_ISSUE_MIN_OUT: u16 = 53;
 
-        // Finish if no more elements are present
-        not     CO;
-        jif     CO, +3;
-        ret;
+/// Transfer of an asset forbidding dust outputs.
+///
+/// Works like [`FN_FUNGIBLE_TRANSFER`], but requires the [`G_MIN_OUTPUT`] global declared in
+/// genesis to be provided as the only global input of the operation (failing with
+/// [`ERRNO_NO_MIN_OUTPUT`] otherwise). The outputs are checked against the minimal output value
+/// with [`FN_FUNGIBLE_MIN_OUTPUTS`].
+pub const FN_FUNGIBLE_TRANSFER_MIN_OUT: u16 = 54;
 
-        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_OUT; // Set error code for the case of failure
-        eq      EA, EH;         // do we have a correct state type?
-        chk     CO;             // fail if not
+/// Checks that the outputs are not below the minimal output value.
+///
+/// The VM can't distinguish the change from the payment outputs, thus a single output below the
+/// minimal value is allowed and is treated as the change; any other output below the minimal value
+/// fails the verification with [`ERRNO_DUST_OUTPUT`].
+///
+/// # Input
+///
+/// `E6` contains the minimal output value.
+///
+/// The output values must be already checked to fit into 64 bits, like it is done by
+/// [`FN_FUNGIBLE_SUM_OUTPUTS`].
+///
+/// # Output
+///
+/// None
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `E5`, `E7`.
+///
+/// # Side effects
+///
+/// Extinguishes the output destructible state iterator
+pub const FN_FUNGIBLE_MIN_OUTPUTS: u16 = 55;
 
-        put     E1, ERRNO_INVALID_BALANCE_OUT; // Set error code for the case of failure
-        test    EC;             // ensure EC is not set
-        not     CO;
-        chk     CO;             // fail if not
+/// Validates the minimal output value global and saves it into `E6`.
+///
+/// # Input
+///
+/// `EA`-`ED` contain the loaded [`G_MIN_OUTPUT`] global; `E1` contains the error code to fail
+/// with.
+///
+/// # Output
+///
+/// `E6` contains the minimal output value.
+///
+/// # Reset registers
+///
+/// `E8`.
+///
+/// # Errors
+///
+/// Fails with the error code provided in `E1` if the global has a different state type, or its
+/// value is not a non-zero 64-bit value in a single field element.
+pub const FN_FUNGIBLE_MIN_OUTPUT: u16 = 57;
+
+/// Retirement of the contract by its issuer.
+///
+/// The operation must spend the issuer right ([`O_RETIRE_RIGHT`]) assigned in genesis by
+/// [`FN_FUNGIBLE_ISSUE_RETIRABLE`], failing with [`ERRNO_NO_RETIRE_RIGHT`] if it is absent and with
+/// [`ERRNO_INVALID_RETIRE_RIGHT`] if there is more than one or it has a value. Thus, the
+/// retirement always has at least one input, and only the owner of the right may retire the
+/// contract. The other inputs are balances, verified like in [`FN_FUNGIBLE_TRANSFER`].
+///
+/// Unlike a burn, which destroys a part of the value, the retirement consumes the inputs without
+/// defining any owned outputs (failing with [`ERRNO_RETIRE_HAS_OUTPUTS`] otherwise), and publishes
+/// the [`G_RETIRED`] marker as the only global state of the operation (failing with
+/// [`ERRNO_NO_RETIRED`] if it is absent).
+///
+/// The retirement is not terminal: the VM has no knowledge of the contract state which is not
+/// spent by the operation, and the transfers do not check the marker, so the allocations which
+/// the retirement doesn't spend remain transferable. Retiring the whole supply requires the
+/// operation to spend all the allocations, which is checked by the issuer and the wallets.
+pub const FN_FUNGIBLE_RETIRE: u16 = 58;
+
+/// Transfer of the allocations which may carry a recipient class.
+///
+/// Works like [`FN_FUNGIBLE_TRANSFER`], but allows the inputs to keep a recipient class in the
+/// third field element, as assigned by [`FN_FUNGIBLE_ISSUE_CLASSED`]. The outputs must have no
+/// recipient class: the class marks only the initial (genesis) allocations, and is dropped once
+/// they are spent.
+pub const FN_FUNGIBLE_TRANSFER_CLASSED: u16 = 62;
+
+/// Sum input owned state, which may carry a recipient class.
+///
+/// Works like [`FN_FUNGIBLE_SUM_INPUTS`], but allows the third field element of the inputs to be
+/// set.
+///
+/// # Output
+///
+/// `E2` contains the sum of inputs.
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `E8`, `EH`.
+///
+/// # Side effects
+///
+/// Extinguishes the input destructible state iterator
+pub const FN_FUNGIBLE_SUM_INPUTS_CLASSED: u16 = 63;
+
+/// Issue of a retirable asset, assigning the issuer right to retire it.
+///
+/// Works like [`FN_FUNGIBLE_ISSUE`], but the outputs must additionally include a single issuer
+/// right ([`O_RETIRE_RIGHT`]) with no value, which is later spent by [`FN_FUNGIBLE_RETIRE`]. The
+/// verification fails with [`ERRNO_NO_RETIRE_RIGHT`] if the right is absent and with
+/// [`ERRNO_INVALID_RETIRE_RIGHT`] if there is more than one or it has a value. The right is not
+/// counted in the issued supply.
+///
+/// The transfers can't spend the right, so its owner must not spend its seal until the
+/// retirement, or the contract can't be retired anymore.
+pub const FN_FUNGIBLE_ISSUE_RETIRABLE: u16 = 65;
+
+/// Validates an issuer right ([`O_RETIRE_RIGHT`]), allowing only a single right per operation.
+///
+/// # Input
+///
+/// `EB`-`ED` contain the loaded right value; `E5` is set if a right was already seen by the
+/// operation; `E1` contains the error code to fail with.
+///
+/// # Output
+///
+/// `E5` is set to mark the right as seen.
+///
+/// # Errors
+///
+/// Fails with the error code provided in `E1` if a right was already seen, or if the right has a
+/// value or extra field elements.
+pub const FN_FUNGIBLE_RETIRE_RIGHT: u16 = 69;
+
+pub const ERRNO_PRECISION_OVERFLOW: u256 = u256::from_inner([1, 1, 0, 0]);
+pub const ERRNO_NO_ISSUED: u256 = u256::from_inner([2, 1, 0, 0]);
+pub const ERRNO_SUM_ISSUE_MISMATCH: u256 = u256::from_inner([3, 1, 0, 0]);
+pub const ERRNO_UNEXPECTED_GLOBAL: u256 = u256::from_inner([4, 1, 0, 0]);
+pub const ERRNO_SUM_MISMATCH: u256 = u256::from_inner([5, 1, 0, 0]);
+pub const ERRNO_UNEXPECTED_OWNED_TYPE_IN: u256 = u256::from_inner([6, 1, 0, 0]);
+pub const ERRNO_INVALID_BALANCE_IN: u256 = u256::from_inner([7, 1, 0, 0]);
+pub const ERRNO_UNEXPECTED_OWNED_TYPE_OUT: u256 = u256::from_inner([8, 1, 0, 0]);
+pub const ERRNO_INVALID_BALANCE_OUT: u256 = u256::from_inner([9, 1, 0, 0]);
+pub const ERRNO_NO_OUTPUT_CAP: u256 = u256::from_inner([10, 1, 0, 0]);
+pub const ERRNO_OUTPUT_OVER_CAP: u256 = u256::from_inner([11, 1, 0, 0]);
+pub const ERRNO_SEALED_INPUT: u256 = u256::from_inner([12, 1, 0, 0]);
+pub const ERRNO_SUPPLY_PRECISION_OVERFLOW: u256 = u256::from_inner([13, 1, 0, 0]);
+pub const ERRNO_NO_DETAILS: u256 = u256::from_inner([14, 1, 0, 0]);
+pub const ERRNO_TOO_MANY_IO: u256 = u256::from_inner([15, 1, 0, 0]);
+pub const ERRNO_RENAME_CHANGED_SUPPLY: u256 = u256::from_inner([16, 1, 0, 0]);
+pub const ERRNO_ZERO_SUPPLY: u256 = u256::from_inner([17, 1, 0, 0]);
+pub const ERRNO_SUPPLY_MISPLACED: u256 = u256::from_inner([18, 1, 0, 0]);
+pub const ERRNO_NO_RECIPIENT_CLASSES: u256 = u256::from_inner([19, 1, 0, 0]);
+pub const ERRNO_UNLISTED_RECIPIENT_CLASS: u256 = u256::from_inner([20, 1, 0, 0]);
+pub const ERRNO_NO_CHANGE_OUTPUT: u256 = u256::from_inner([21, 1, 0, 0]);
+pub const ERRNO_NON_LOT_OUTPUT: u256 = u256::from_inner([22, 1, 0, 0]);
+pub const ERRNO_NO_LOT_SIZE: u256 = u256::from_inner([23, 1, 0, 0]);
+pub const ERRNO_ALLOWANCE_EXCEEDED: u256 = u256::from_inner([24, 1, 0, 0]);
+pub const ERRNO_NO_ALLOWANCE: u256 = u256::from_inner([25, 1, 0, 0]);
+pub const ERRNO_INVALID_ALLOWANCE: u256 = u256::from_inner([26, 1, 0, 0]);
+pub const ERRNO_DUST_OUTPUT: u256 = u256::from_inner([27, 1, 0, 0]);
+pub const ERRNO_NO_MIN_OUTPUT: u256 = u256::from_inner([28, 1, 0, 0]);
+pub const ERRNO_RETIRE_HAS_OUTPUTS: u256 = u256::from_inner([29, 1, 0, 0]);
+pub const ERRNO_NO_RETIRED: u256 = u256::from_inner([30, 1, 0, 0]);
+pub const ERRNO_MULTIPLE_SUPPLY: u256 = u256::from_inner([31, 1, 0, 0]);
+pub const ERRNO_EMPTY_TICKER: u256 = u256::from_inner([32, 1, 0, 0]);
+pub const ERRNO_NO_RETIRE_RIGHT: u256 = u256::from_inner([33, 1, 0, 0]);
+pub const ERRNO_INVALID_RETIRE_RIGHT: u256 = u256::from_inner([34, 1, 0, 0]);
+
+/// Computes the issued supply ([`G_SUPPLY`]) matching the genesis outputs.
+///
+/// Mirrors the checks of [`FN_FUNGIBLE_SUM_OUTPUTS`]: each output must be an [`O_AMOUNT`]
+/// allocation with a value fitting into 64 bits, and the sum of the values must not overflow 64
+/// bits. Returns `None` if any of the checks fails, since such outputs fail the validation with any
+/// supply declared.
+pub fn expected_supply(outputs: &[StateCell]) -> Option<u64> {
+    outputs.iter().try_fold(0u64, |sum, cell| {
+        let StateValue::Double { first, second } = &cell.data else {
+            return None;
+        };
+        if first.to_u256() != O_AMOUNT {
+            return None;
+        }
+        let value = second.to_u256();
+        if value > u256::from(u64::MAX) {
+            return None;
+        }
+        sum.checked_add(value.low_u64())
+    })
+}
+
+// TODO: Add `FN_FUNGIBLE_ISSUE_SIGNED` verifying an issuer-signed genesis. The issuer public key
+//       commitment would be declared in a genesis global following the asset specification, and
+//       the operation witness would have to be `StateValue::Double { first: <global type>,
+//       second: <commitment> }`, failing with `ERRNO_BAD_ISSUER_WITNESS` on mismatch. This is
+//       blocked until the VM instruction reading the operation witness (`VmContext::witness`) is
+//       available to the scripts.
+
+// TODO: Add `FN_FUNGIBLE_TRANSFER_EXPIRING` for vouchers which can't be spent after an expiry
+//       height. The height would be declared in genesis as `G_EXPIRY` global following the supply
+//       and read by the transfer from its immutable inputs, like the lot size is read by
+//       `FN_FUNGIBLE_TRANSFER_LOTS`, failing with `ERRNO_EXPIRED` if the spending witness is mined
+//       after the expiry. This is blocked since the VM has no access to the height or the time of
+//       the witness: the operation witness data are provided by the party creating the operation
+//       and are not bound to the transaction mining, thus the expiry may be checked only by the
+//       client-side validation.
+
+// TODO: Add `FN_FUNGIBLE_TRANSFER_COVENANT` for covenant-style assets, where the lock script of a
+//       spent allocation must persist: if any of the inputs carries a lock, at least one of the
+//       outputs must carry the same lock, failing with `ERRNO_LOCK_DROPPED` otherwise. This is
+//       blocked since the state loading instructions put only the state data into the registers:
+//       the lock (`StateCell::lock`), like the auth token, is not accessible to the VM, and it is
+//       run by the validator as a separate script instead.
+
+// TODO: Add `FN_FUNGIBLE_TRANSFER_CONFIDENTIAL` for the codices requiring the owned amounts to be
+//       committed instead of being revealed. The owned state would be `StateValue::Double {
+//       first: O_AMOUNT, second: <commitment> }`, and a plaintext value (i.e. one fitting into 64
+//       bits) would fail with `ERRNO_PLAINTEXT_AMOUNT`. This is blocked since the VM provides only
+//       prime field arithmetics (GFA) over the state field elements: a hiding and binding amount
+//       commitment (like Pedersen commitments) requires elliptic curve group operations to check
+//       the sum of the input commitments against the sum of the output ones, together with range
+//       proofs on the outputs, none of which can be expressed with the available instructions.
+
+/// Instructions initializing and counting down the number of the inputs or outputs in `E5`, which
+/// are emitted only if the number is limited (see [`fungible_with_max_io`]).
+fn io_counter(max_io: Option<u64>) -> (Vec<Instr<LibId>>, Vec<Instr<LibId>>) {
+    let Some(max_io) = max_io.map(u256::from) else {
+        return (vec![], vec![]);
+    };
+    let init = uasm! {
+        put     E5, max_io;     // E5 will hold the number of elements which are still allowed
+        put     E7, 1;
+        neg     E7, E7;         // E7 will hold -1 as a constant for the counter decrement
+    };
+    let next = uasm! {
+        put     E1, ERRNO_TOO_MANY_IO; // Set error code for the case of failure
+        add     E5, E7;         // Count the element down, which wraps around the field order (and
+        fits    E5, 64.bits;    // does not fit u64) if there are more elements than allowed
+        chk     CO;             // fail if not
+    };
+    (init, next)
+}
+
+/// Fungible asset library with no limit on the number of inputs and outputs.
+pub fn fungible() -> CompiledLib {
+    fungible_with_max_io(None)
+}
+
+/// Fungible asset library, optionally limiting the number of inputs and outputs of an operation.
+///
+/// Validation of an operation with lots of tiny inputs or outputs may approach the complexity
+/// limit; the `max_io` cap allows to fail such operations early with [`ERRNO_TOO_MANY_IO`]. The
+/// cap is compiled into the library code, so libraries with different caps have different ids.
+/// With no cap, the library doesn't count the inputs and outputs at all, and never fails with
+/// [`ERRNO_TOO_MANY_IO`].
+pub fn fungible_with_max_io(max_io: Option<u64>) -> CompiledLib {
+    const LOOP_INPUTS: u16 = 6;
+    const LOOP_OUTPUTS: u16 = 8;
+    const LOOP_OUTPUTS_CAPPED: u16 = 11;
+    const LOOP_SCALE: u16 = 15;
+    const END_SCALE: u16 = 16;
+    const LOOP_RENAME: u16 = 20;
+    const RENAMED: u16 = 21;
+    const END_RENAME: u16 = 22;
+    const LOOP_PLACEMENT: u16 = 24;
+    const PRECISION_SEEN: u16 = 25;
+    const LOOP_OUTPUTS_CLASSED: u16 = 28;
+    const CLASS_LISTED: u16 = 29;
+    const CHANGE_CHECKED: u16 = 31;
+    const LOOP_LOTS: u16 = 35;
+    const LOT_REDUCE: u16 = 36;
+    const LOT_DOUBLE: u16 = 37;
+    const LOT_DOUBLED: u16 = 38;
+    const LOT_REMAINDER: u16 = 39;
+    const LOOP_FROM_INPUTS: u16 = 42;
+    const FROM_ALLOWANCE_IN: u16 = 43;
+    const FROM_INPUTS_DONE: u16 = 44;
+    const LOOP_FROM_OUTPUTS: u16 = 45;
+    const FROM_ALLOWANCE_OUT: u16 = 46;
+    const FROM_OUTPUTS_DONE: u16 = 47;
+    const LOOP_APPROVE_OUTPUTS: u16 = 49;
+    const APPROVE_ALLOWANCE_OUT: u16 = 50;
+    const APPROVE_OUTPUTS_DONE: u16 = 51;
+    const LOOP_MIN_OUTPUTS: u16 = 56;
+    const LOOP_RETIRE_INPUTS: u16 = 59;
+    const RETIRE_RIGHT_IN: u16 = 60;
+    const RETIRE_INPUTS_DONE: u16 = 61;
+    const LOOP_INPUTS_CLASSED: u16 = 64;
+    const LOOP_RETIRABLE_OUTPUTS: u16 = 66;
+    const RETIRE_RIGHT_OUT: u16 = 67;
+    const RETIRABLE_OUTPUTS_DONE: u16 = 68;
+
+    let shared = shared_lib().into_lib().lib_id();
+    let max_precision = u256::from(MAX_PRECISION);
+
+    let (count_init, count_next) = io_counter(max_io);
+
+    let mut code = uasm! {
+     routine FN_FUNGIBLE_ISSUE:
+        call    FN_FUNGIBLE_ISSUE_ALLOCATION; // Verify the specification, the supply and its allocation
+        call    FN_FUNGIBLE_TICKER; // The ticker must have a value
+        ret;
+
+     proc FN_FUNGIBLE_ISSUE_ALLOCATION:
+        call    FN_FUNGIBLE_ISSUE_SPEC; // Verify the specification and the supply, saved into `E2`
+
+        // Validate that the issued amount is equal to the sum of the outputs
+        put     E3, 0;          // E3 will contain the sum of outputs
+        call    FN_FUNGIBLE_SUM_OUTPUTS;// Compute a sum of outputs
+        put     E1, ERRNO_SUM_ISSUE_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that circulating supply equals to the sum of outputs
+        chk     CO;             // fail if not
+
+        // Optional details and reference must follow the supply
+        call    shared, FN_ASSET_DETAILS;
+
+        // Check there is no more global state
+        put     E1, ERRNO_UNEXPECTED_GLOBAL; // Set error code for the case of failure
+        ldo     immutable;
+        not     CO;
+        chk     CO;
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     proc FN_FUNGIBLE_TICKER:
+        put     E1, ERRNO_EMPTY_TICKER; // Set error code for the case of failure
+        rsto    immutable;      // Restart iteration over the global state
+        ldo     immutable;      // Read the first global state - ticker, which type is already checked
+        test    EB;             // - it must have a value
+        chk     CO;             // - or fail otherwise
+        clr     E1;             // Clear the error code
+        ret;
+
+     proc FN_FUNGIBLE_ISSUE_SPEC:
+        // Genesis must not use global state; we do not rely on `FN_ASSET_SPEC` checking this
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        rsti    immutable;
+        cknxi   immutable;
+        not     CO;
+        chk     CO;
+
+        call    FN_FUNGIBLE_SUPPLY_PLACEMENT; // Check the supply is not misplaced
+        rsto    immutable;      // Restart iteration over the global state
+
+        call    shared, FN_ASSET_SPEC;// Call asset check, returning the precision in `E4`
+
+        put     E1, ERRNO_PRECISION_OVERFLOW; // Set error code for the case of failure
+        fits    E4, 8.bits;     // The precision must fit into a byte
+        chk     CO;             // - or fail otherwise
+        put     E8, max_precision; // E8 will hold the maximal precision
+        neg     E7, E4;         // E7 = -precision
+        add     E7, E8;         // E7 = max - precision, which wraps around the field order if the
+        fits    E7, 64.bits;    // precision exceeds the maximum and no longer fits in u64
+        chk     CO;             // - or fail otherwise
+
+        // Validate circulating supply
+        put     E1, ERRNO_NO_ISSUED; // Set error code for the case of failure
+        ldo     immutable;      // Read global state following the specification - circulating supply
+        chk     CO;             // It must exist
+        put     E8, G_SUPPLY;   // Load supply type
+        eq      EA, E8;         // It must have a correct state type
+        chk     CO;             // Or fail otherwise
+        test    EB;             // It must be set
+        chk     CO;             // Or we should fail
+        mov     E2, EB;         // Save supply
+        test    EC;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        test    ED;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
 
-        test    ED;             // ensure ED is not set
+        // Validate that the supply is not zero (which otherwise matches empty outputs)
+        put     E1, ERRNO_ZERO_SUPPLY; // Set error code for the case of failure
+        put     E8, 0;
+        eq      E2, E8;         // check the supply against zero
+        not     CO;             // invert CO value (we need the check to fail)
+        chk     CO;             // fail if not
+
+        ret;
+
+     routine FN_FUNGIBLE_TRANSFER:
+        // Verify that no global state is defined
+        call    shared, FN_GLOBAL_ABSENT;
+
+        // Verify owned state
+        call    FN_FUNGIBLE_SUM_INPUTS; // Compute a sum of inputs into E2
+        call    FN_FUNGIBLE_SUM_OUTPUTS; // Compute a sum of outputs into E3
+        put     E1, ERRNO_SUM_MISMATCH; // Set error code for the case of failure
+        // TODO: Check the sum is not zero
+        eq      E2, E3;         // check that the sum of inputs equals the sum of outputs
+        chk     CO;             // fail if not
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     proc FN_FUNGIBLE_SUM_INPUTS:
+        put     E2, 0;          // Set initial sum to zero
+        put     EH, O_AMOUNT;   // Set EH to the field element representing the owned value
+    };
+    code.extend(count_init.iter().cloned());
+    code.extend(uasm! {
+        rsti    destructible;   // Start iteration over inputs
+
+     label LOOP_INPUTS:
+        ldi     destructible;   // load next state value
+
+        // Finish if no more elements are present
         not     CO;
+        jif     CO, +3;
+        ret;
+    });
+    code.extend(count_next.iter().cloned());
+    code.extend(uasm! {
+        put     E1, ERRNO_EMPTY_STATE; // Set error code for the case of failure
+        test    EA;             // a genuine owned state must carry a type tag
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_IN; // Set error code for the case of failure
+        eq      EA, EH;         // do we have a correct state type?
         chk     CO;             // fail if not
 
+        put     E1, ERRNO_INVALID_BALANCE_IN; // Set error code for the case of failure
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+
         fits    EB, 64.bits;    // ensure the value fits in u64
         chk     CO;             // fail if not
-        add     E3, EB;         // add input to input accumulator
-        fits    E3, 64.bits;    // ensure we do not overflow
+        mov     E8, E2;         // add input to input accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E2, E8;
+
+        jmp     LOOP_INPUTS;    // loop
+
+     proc FN_FUNGIBLE_SUM_OUTPUTS:
+        put     E3, 0;          // Set initial sum to zero
+        put     EH, O_AMOUNT;   // Set EH to the field element representing the owned value
+    });
+    code.extend(count_init.iter().cloned());
+    code.extend(uasm! {
+        rsto    destructible;   // Start iteration over outputs
+
+     label LOOP_OUTPUTS:
+        ldo     destructible;   // load next state value
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+    });
+    code.extend(count_next.iter().cloned());
+    code.extend(uasm! {
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_OUT; // Set error code for the case of failure
+        eq      EA, EH;         // do we have a correct state type?
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_OUT; // Set error code for the case of failure
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+
+        fits    EB, 64.bits;    // ensure the value fits in u64
         chk     CO;             // fail if not
+        mov     E8, E3;         // add output to output accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E3, E8;
 
         jmp     LOOP_OUTPUTS;   // loop
-    };
 
-    CompiledLib::compile(&mut code, &[&shared_lib()])
-        .unwrap_or_else(|err| panic!("Invalid script: {err}"))
-}
+     routine FN_FUNGIBLE_ISSUE_CAPPED:
+        call    FN_FUNGIBLE_ISSUE_SPEC; // Verify the specification and the supply, saved into `E2`
 
-#[cfg(test)]
-mod tests {
-    use amplify::num::u256;
-    use hypersonic::{AuthToken, Input, Instr, StateCell, StateData, StateValue, VmContext};
-    use strict_types::StrictDumb;
-    use zkaluvm::alu::{CoreConfig, CoreExt, Lib, LibId, Supercore, Vm};
-    use zkaluvm::{GfaConfig, GfaCore, RegE, FIELD_ORDER_SECP};
+        // Read per-output cap
+        put     E1, ERRNO_NO_OUTPUT_CAP; // Set error code for the case of failure
+        ldo     immutable;      // Read global state following the supply - per-output cap
+        chk     CO;             // It must exist
+        put     E8, G_MAX_PER_OUTPUT; // Load cap type
+        eq      EA, E8;         // It must have a correct state type
+        chk     CO;             // Or fail otherwise
+        test    EB;             // It must be set
+        chk     CO;             // Or we should fail
+        fits    EB, 64.bits;    // It must fit in u64
+        chk     CO;             // Or we should fail
+        mov     E5, EB;         // Save cap
+        test    EC;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        test    ED;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
 
-    use super::*;
-    use crate::{G_NAME, G_PRECISION, G_SUPPLY, G_TICKER, O_AMOUNT};
+        // Validate that the issued amount is equal to the sum of the outputs
+        call    FN_FUNGIBLE_SUM_OUTPUTS_CAPPED;// Compute a sum of outputs
+        put     E1, ERRNO_SUM_ISSUE_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that circulating supply equals to the sum of outputs
+        chk     CO;             // fail if not
 
-    const CONFIG: CoreConfig = CoreConfig {
-        halt: true,
-        complexity_lim: Some(500_000_000),
-    };
+        // Optional details and reference must follow the cap
+        call    shared, FN_ASSET_DETAILS;
 
-    fn harness() -> (CompiledLib, Vm<Instr<LibId>>, impl Fn(LibId) -> Option<Lib>) {
-        let vm = Vm::<Instr<LibId>>::with(
-            CONFIG,
-            GfaConfig {
-                field_order: FIELD_ORDER_SECP,
-            },
-        );
-        fn resolver(id: LibId) -> Option<Lib> {
-            let fungible = fungible();
-            let shared = shared_lib();
-            if fungible.as_lib().lib_id() == id {
-                return Some(fungible.into_lib());
+        // Check there is no more global state
+        put     E1, ERRNO_UNEXPECTED_GLOBAL; // Set error code for the case of failure
+        ldo     immutable;
+        not     CO;
+        chk     CO;
+
+        call    FN_FUNGIBLE_TICKER; // The ticker must have a value
+        ret;
+
+     proc FN_FUNGIBLE_SUM_OUTPUTS_CAPPED:
+        put     E3, 0;          // Set initial sum to zero
+        put     EH, O_AMOUNT;   // Set EH to the field element representing the owned value
+        rsto    destructible;   // Start iteration over outputs
+
+     label LOOP_OUTPUTS_CAPPED:
+        ldo     destructible;   // load next state value
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_OUT; // Set error code for the case of failure
+        eq      EA, EH;         // do we have a correct state type?
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_OUT; // Set error code for the case of failure
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_OUTPUT_OVER_CAP; // Set error code for the case of failure
+        neg     E6, EB;         // E6 = -value
+        add     E6, E5;         // E6 = cap - value, which wraps around the field order if the value
+        fits    E6, 64.bits;    // exceeds the cap and no longer fits in u64
+        chk     CO;             // fail if not
+
+        mov     E8, E3;         // add output to output accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E3, E8;
+
+        jmp     LOOP_OUTPUTS_CAPPED; // loop
+
+     routine FN_FUNGIBLE_LOCK_UNSPENDABLE:
+        put     E1, ERRNO_SEALED_INPUT; // Set error code for the failure: the state is never spendable
+        clr     E8;             // Make sure the register is empty
+        test    E8;             // which always resets CO
+        chk     CO;             // and fails
+        ret;
+
+     routine FN_FUNGIBLE_ISSUE_SCALED:
+        call    FN_FUNGIBLE_ISSUE; // Verify the issue, keeping the supply in `E2` and precision in `E4`
+        call    FN_FUNGIBLE_SUPPLY_SCALE;
+        clr     E1;             // Clear the error code
+        ret;
+
+     proc FN_FUNGIBLE_SUPPLY_SCALE:
+        put     E1, ERRNO_SUPPLY_PRECISION_OVERFLOW; // Set error code for the case of failure
+        put     E5, 0;          // Counter of the multiplications
+        put     E7, 1;          // E7 will hold 1 as a constant for counter-increment operation
+        put     E8, 10;         // E8 will hold 10 as a multiplier
+        mov     E6, E2;         // E6 will contain the scaled supply
+
+     label LOOP_SCALE:
+        eq      E5, E4;         // Did we multiply `precision` times?
+        jif     CO, END_SCALE;  // - then we are done
+        mul     E6, E8;         // Scale the supply by 10
+        fits    E6, 64.bits;    // The scaled supply must fit into u64 (the precision is already
+        chk     CO;             // checked to fit a byte, so the field order can't be reached)
+        add     E5, E7;         // Increment the counter
+        jmp     LOOP_SCALE;
+
+     label END_SCALE:
+        ret;
+
+     routine FN_FUNGIBLE_BLANK:
+        call    FN_FUNGIBLE_TRANSFER; // Blank transitions follow the transfer rules
+        ret;
+
+     routine FN_FUNGIBLE_ISSUE_RGB25:
+        // Genesis must not use global state; checked before the details to be reported first
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        rsti    immutable;
+        cknxi   immutable;
+        not     CO;
+        chk     CO;
+
+        put     E1, ERRNO_NO_DETAILS; // Set error code for the case of failure
+        rsto    immutable;      // Start iteration over the global state
+        ldo     immutable;      // Read the first global state - details
+        chk     CO;             // - they must exist
+        put     EH, G_DETAILS;
+        eq      EA, EH;         // - they must have the correct state type
+        chk     CO;             // - - or fail otherwise
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // - they must have no other field elements than in EB
+
+        call    FN_FUNGIBLE_ISSUE_ALLOCATION; // Verify the asset as a fungible issue, with the details first
+        ret;
+
+     routine FN_FUNGIBLE_RENAME:
+        // Verify that no global state is used
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        rsti    immutable;
+        cknxi   immutable;
+        not     CO;
+        chk     CO;
+
+        // Only the ticker and the name may be re-declared
+        put     E5, G_TICKER;
+        put     E6, G_NAME;
+        put     E7, G_PRECISION;
+        put     E8, G_SUPPLY;
+        rsto    immutable;      // Start iteration over the new global state
+     label LOOP_RENAME:
+        ldo     immutable;      // Read next global state
+        not     CO;
+        jif     CO, END_RENAME; // Verify owned state if no more global state is left
+        put     E1, ERRNO_EMPTY_TICKER; // Set error code for the case of failure
+        eq      EA, E5;         // Is it a ticker?
+        jif     CO, RENAMED;    // - then it is allowed
+        put     E1, ERRNO_EMPTY_NAME; // Set error code for the case of failure
+        eq      EA, E6;         // Is it a name?
+        jif     CO, RENAMED;    // - then it is allowed
+        put     E1, ERRNO_RENAME_CHANGED_SUPPLY; // Set error code for the case of failure
+        eq      EA, E7;         // The precision must not change
+        not     CO;
+        chk     CO;
+        eq      EA, E8;         // The supply must not change
+        not     CO;
+        chk     CO;
+        put     E1, ERRNO_UNEXPECTED_GLOBAL; // No other global state is allowed
+        not     CO;             // CO is set here, so this always fails
+        chk     CO;
+
+     label RENAMED:
+        test    EB;             // The new ticker or name must have a value
+        chk     CO;             // - or fail otherwise
+        jmp     LOOP_RENAME;    // Process to the next global state
+
+     label END_RENAME:
+        call    FN_FUNGIBLE_SUM_INPUTS; // Compute a sum of inputs into E2
+        call    FN_FUNGIBLE_SUM_OUTPUTS; // Compute a sum of outputs into E3
+        put     E1, ERRNO_SUM_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that the sum of inputs equals the sum of outputs
+        chk     CO;             // fail if not
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     proc FN_FUNGIBLE_SUPPLY_PLACEMENT:
+        clr     E7;             // E7 is set once the supply is seen
+        rsto    immutable;      // Start iteration over the global state
+
+     label LOOP_PLACEMENT:
+        ldo     immutable;      // Read next global state
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+
+        put     EH, G_PRECISION;
+        eq      EA, EH;         // Is it a precision?
+        jif     CO, PRECISION_SEEN;
+        put     EH, G_SUPPLY;
+        eq      EA, EH;         // Is it a supply?
+        not     CO;
+        jif     CO, LOOP_PLACEMENT; // - skip any other global state
+        put     E1, ERRNO_MULTIPLE_SUPPLY; // Set error code for the case of failure
+        test    E7;             // The supply must not be repeated
+        not     CO;
+        chk     CO;             // - or fail otherwise
+        put     E7, 1;          // Mark that we have seen the supply
+        jmp     LOOP_PLACEMENT;
+
+     label PRECISION_SEEN:
+        put     E1, ERRNO_SUPPLY_MISPLACED; // Set error code for the case of failure
+        test    E7;             // The supply must not precede the precision
+        not     CO;
+        chk     CO;             // - or fail otherwise
+        jmp     LOOP_PLACEMENT;
+
+     routine FN_FUNGIBLE_ISSUE_CLASSED:
+        call    FN_FUNGIBLE_ISSUE_SPEC; // Verify the specification and the supply, saved into `E2`
+
+        // Read the allowlist of recipient classes
+        put     E1, ERRNO_NO_RECIPIENT_CLASSES; // Set error code for the case of failure
+        ldo     immutable;      // Read next global state - recipient classes
+        chk     CO;             // It must exist
+        put     E8, G_RECIPIENT_CLASSES; // Load allowlist type
+        eq      EA, E8;         // It must have a correct state type
+        chk     CO;             // Or fail otherwise
+        test    EB;             // At least the first class must be set
+        chk     CO;             // Or we should fail
+        mov     EF, EB;         // Save the allowed classes
+        mov     EG, EC;
+        mov     E6, ED;
+
+        // Validate that the issued amount is equal to the sum of the outputs
+        call    FN_FUNGIBLE_SUM_OUTPUTS_CLASSED;// Compute a sum of outputs
+        put     E1, ERRNO_SUM_ISSUE_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that circulating supply equals to the sum of outputs
+        chk     CO;             // fail if not
+
+        // Optional details and reference must follow the allowlist
+        call    shared, FN_ASSET_DETAILS;
+
+        // Check there is no more global state
+        put     E1, ERRNO_UNEXPECTED_GLOBAL; // Set error code for the case of failure
+        ldo     immutable;
+        not     CO;
+        chk     CO;
+
+        call    FN_FUNGIBLE_TICKER; // The ticker must have a value
+        ret;
+
+     proc FN_FUNGIBLE_SUM_OUTPUTS_CLASSED:
+        put     E3, 0;          // Set initial sum to zero
+        put     EH, O_AMOUNT;   // Set EH to the field element representing the owned value
+        rsto    destructible;   // Start iteration over outputs
+
+     label LOOP_OUTPUTS_CLASSED:
+        ldo     destructible;   // load next state value
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_OUT; // Set error code for the case of failure
+        eq      EA, EH;         // do we have a correct state type?
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_OUT; // Set error code for the case of failure
+        call    shared, FN_ASSERT_PAIR_VALUE; // ensure there are no extra field elements
+
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+
+        test    EC;             // An output with no recipient class is always allowed
+        not     CO;
+        jif     CO, CLASS_LISTED;
+
+        put     E1, ERRNO_UNLISTED_RECIPIENT_CLASS; // Set error code for the case of failure
+        eq      EC, EF;         // Is it the first listed class?
+        jif     CO, CLASS_LISTED;
+        eq      EC, EG;         // Is it the second listed class?
+        jif     CO, CLASS_LISTED;
+        eq      EC, E6;         // It must be the third listed class
+        chk     CO;             // - or fail otherwise
+
+     label CLASS_LISTED:
+        mov     E8, E3;         // add output to output accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E3, E8;
+
+        jmp     LOOP_OUTPUTS_CLASSED; // loop
+
+     routine FN_FUNGIBLE_TRANSFER_WITH_CHANGE:
+        call    FN_FUNGIBLE_TRANSFER; // Verify the transfer
+
+        // Check whether there is more than a single input
+        rsti    destructible;   // Start iteration over inputs
+        ldi     destructible;   // Skip the first input
+        ldi     destructible;   // Try to read the second input
+        not     CO;
+        jif     CO, CHANGE_CHECKED; // A single input requires no change
+
+        put     E1, ERRNO_NO_CHANGE_OUTPUT; // Set error code for the case of failure
+        rsto    destructible;   // Start iteration over outputs
+        ldo     destructible;   // Skip the first output
+        ldo     destructible;   // The second output must exist
+        chk     CO;             // - or fail otherwise
+
+     label CHANGE_CHECKED:
+        clr     E1;             // Clear the error code
+        ret;
+
+     routine FN_FUNGIBLE_ISSUE_LOTS:
+        call    FN_FUNGIBLE_ISSUE_SPEC; // Verify the specification and the supply, saved into `E2`
+
+        // Read the lot size
+        put     E1, ERRNO_NO_LOT_SIZE; // Set error code for the case of failure
+        ldo     immutable;      // Read global state - lot size
+        chk     CO;             // It must exist
+        call    FN_FUNGIBLE_LOT_SIZE; // Validate and save the lot size into `E6`
+
+        // Validate that the issued amount is equal to the sum of the outputs
+        call    FN_FUNGIBLE_SUM_OUTPUTS;// Compute a sum of outputs
+        put     E1, ERRNO_SUM_ISSUE_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that circulating supply equals to the sum of outputs
+        chk     CO;             // fail if not
+        call    FN_FUNGIBLE_LOTS_OUTPUTS; // Check the outputs are multiples of the lot size
+
+        // Optional details and reference must follow the lot size
+        call    shared, FN_ASSET_DETAILS;
+
+        // Check there is no more global state
+        put     E1, ERRNO_UNEXPECTED_GLOBAL; // Set error code for the case of failure
+        ldo     immutable;
+        not     CO;
+        chk     CO;
+
+        call    FN_FUNGIBLE_TICKER; // The ticker must have a value
+        ret;
+
+     routine FN_FUNGIBLE_TRANSFER_LOTS:
+        // The lot size must be provided as the only global input
+        put     E1, ERRNO_NO_LOT_SIZE; // Set error code for the case of failure
+        rsti    immutable;
+        ldi     immutable;      // Read the global input - lot size
+        chk     CO;             // It must exist
+        call    FN_FUNGIBLE_LOT_SIZE; // Validate and save the lot size into `E6`
+
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        ldi     immutable;      // There must be no other global inputs
+        not     CO;
+        chk     CO;
+
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_OUT; // Set error code for the case of failure
+        rsto    immutable;
+        cknxo   immutable;      // There must be no global state defined
+        not     CO;
+        chk     CO;
+
+        // Verify owned state
+        call    FN_FUNGIBLE_SUM_INPUTS; // Compute a sum of inputs into E2
+        call    FN_FUNGIBLE_SUM_OUTPUTS; // Compute a sum of outputs into E3
+        put     E1, ERRNO_SUM_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that the sum of inputs equals the sum of outputs
+        chk     CO;             // fail if not
+        call    FN_FUNGIBLE_LOTS_OUTPUTS; // Check the outputs are multiples of the lot size
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     proc FN_FUNGIBLE_LOTS_OUTPUTS:
+        put     E1, ERRNO_NON_LOT_OUTPUT; // Set error code for the case of failure
+        rsto    destructible;   // Start iteration over outputs
+
+     label LOOP_LOTS:
+        ldo     destructible;   // load next state value
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+
+        mov     E7, EB;         // E7 will hold the remainder of the output value
+
+     label LOT_REDUCE:
+        neg     E5, E6;         // E5 = -lot
+        add     E5, E7;         // E5 = remainder - lot, which wraps around the field order (and
+        fits    E5, 64.bits;    // does not fit u64) if the remainder is less than the lot size
+        not     CO;
+        jif     CO, LOT_REMAINDER; // - then the remainder is final
+        mov     E8, E6;         // E8 will hold the largest multiple `lot * 2^k` not exceeding it
+
+     label LOT_DOUBLE:
+        mov     E5, E8;
+        add     E5, E8;         // E5 = 2 * E8, which fits in 65 bits and doesn't wrap
+        neg     EH, E5;         // EH = -2 * E8
+        add     EH, E7;         // EH = remainder - 2 * E8, which wraps around the field order if
+        fits    EH, 64.bits;    // the doubled multiple exceeds the remainder
+        not     CO;
+        jif     CO, LOT_DOUBLED; // - then E8 is the largest multiple
+        mov     E8, E5;         // double the multiple
+        jmp     LOT_DOUBLE;
+
+     label LOT_DOUBLED:
+        neg     E5, E8;
+        add     E7, E5;         // subtract the multiple from the remainder
+        jmp     LOT_REDUCE;
+
+     label LOT_REMAINDER:
+        put     E5, 0;
+        eq      E7, E5;         // the remainder must be zero
+        chk     CO;             // fail if not
+        jmp     LOOP_LOTS;      // loop
+
+        // Validates the lot size global loaded into `EA`-`ED` and saves it into `E6`
+     routine FN_FUNGIBLE_LOT_SIZE:
+        put     E8, G_LOT_SIZE; // Load lot size type
+        eq      EA, E8;         // It must have a correct state type
+        chk     CO;             // Or fail otherwise
+        test    EB;             // It must be set
+        chk     CO;             // Or we should fail
+        fits    EB, 64.bits;    // It must fit in u64
+        chk     CO;             // Or we should fail
+        put     E8, 0;
+        eq      EB, E8;         // It must not be zero
+        not     CO;
+        chk     CO;             // Or we should fail
+        mov     E6, EB;         // Save the lot size
+        test    EC;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        test    ED;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        ret;
+
+     routine FN_FUNGIBLE_TRANSFER_FROM:
+        // Verify that no global state is defined
+        call    shared, FN_GLOBAL_ABSENT;
+
+        put     E2, 0;          // E2 will contain the sum of the spent balances
+        clr     E4;             // E4 will contain the allowance amount once it is seen
+        clr     E6;             // E6 will contain the allowance grantee
+        rsti    destructible;   // Start iteration over inputs
+
+     label LOOP_FROM_INPUTS:
+        ldi     destructible;   // load next state value
+        not     CO;
+        jif     CO, FROM_INPUTS_DONE; // Finish if no more elements are present
+
+        put     E1, ERRNO_EMPTY_STATE; // Set error code for the case of failure
+        test    EA;             // a genuine owned state must carry a type tag
+        chk     CO;             // fail if not
+
+        put     EH, O_ALLOWANCE;
+        eq      EA, EH;         // Is it an allowance?
+        jif     CO, FROM_ALLOWANCE_IN;
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_IN; // Set error code for the case of failure
+        put     EH, O_AMOUNT;
+        eq      EA, EH;         // otherwise it must be a balance
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_IN; // Set error code for the case of failure
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+        mov     E8, E2;         // add input to input accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E2, E8;
+        jmp     LOOP_FROM_INPUTS;
+
+     label FROM_ALLOWANCE_IN:
+        put     E1, ERRNO_INVALID_ALLOWANCE; // Set error code for the case of failure
+        test    E4;             // Only a single allowance may be spent
+        not     CO;
+        chk     CO;             // - or fail otherwise
+        call    FN_FUNGIBLE_ALLOWANCE_VALUE; // Validate the allowance
+        mov     E4, EB;         // Save the allowance amount
+        mov     E6, EC;         // Save the grantee
+        mov     E8, E2;         // add the allowance to input accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E2, E8;
+        jmp     LOOP_FROM_INPUTS;
+
+     label FROM_INPUTS_DONE:
+        put     E1, ERRNO_NO_ALLOWANCE; // Set error code for the case of failure
+        test    E4;             // An allowance must be spent
+        chk     CO;             // - or fail otherwise
+
+        put     E3, 0;          // E3 will contain the sum of the balance outputs
+        clr     E5;             // E5 is set once the remaining allowance is seen
+        rsto    destructible;   // Start iteration over outputs
+
+     label LOOP_FROM_OUTPUTS:
+        ldo     destructible;   // load next state value
+        not     CO;
+        jif     CO, FROM_OUTPUTS_DONE; // Finish if no more elements are present
+
+        put     EH, O_ALLOWANCE;
+        eq      EA, EH;         // Is it the remaining allowance?
+        jif     CO, FROM_ALLOWANCE_OUT;
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_OUT; // Set error code for the case of failure
+        put     EH, O_AMOUNT;
+        eq      EA, EH;         // otherwise it must be a balance
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_OUT; // Set error code for the case of failure
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+        mov     E8, E3;         // add output to output accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E3, E8;
+        jmp     LOOP_FROM_OUTPUTS;
+
+     label FROM_ALLOWANCE_OUT:
+        put     E1, ERRNO_INVALID_ALLOWANCE; // Set error code for the case of failure
+        test    E5;             // Only a single remaining allowance may be defined
+        not     CO;
+        chk     CO;             // - or fail otherwise
+        put     E5, 1;          // Mark that we have seen the remaining allowance
+        call    FN_FUNGIBLE_ALLOWANCE_VALUE; // Validate the allowance
+        eq      EC, E6;         // It must keep the grantee
+        chk     CO;             // - or fail otherwise
+
+        put     E1, ERRNO_ALLOWANCE_EXCEEDED; // Set error code for the case of failure
+        neg     E8, EB;         // E8 = -remaining
+        add     E8, E4;         // E8 = allowance - remaining, which wraps around the field order
+        fits    E8, 64.bits;    // if the remaining allowance is too large
+        chk     CO;             // fail if not
+        mov     E8, E3;         // add the remaining allowance to output accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E3, E8;
+        jmp     LOOP_FROM_OUTPUTS;
+
+     label FROM_OUTPUTS_DONE:
+        put     E1, ERRNO_SUM_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that the sum of inputs equals the sum of outputs
+        chk     CO;             // fail if not
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     routine FN_FUNGIBLE_APPROVE:
+        // Verify that no global state is defined
+        call    shared, FN_GLOBAL_ABSENT;
+
+        call    FN_FUNGIBLE_SUM_INPUTS; // Compute a sum of inputs into E2
+        put     E3, 0;          // E3 will contain the sum of the balance outputs
+        rsto    destructible;   // Start iteration over outputs
+
+     label LOOP_APPROVE_OUTPUTS:
+        ldo     destructible;   // load next state value
+        not     CO;
+        jif     CO, APPROVE_OUTPUTS_DONE; // Finish if no more elements are present
+
+        put     EH, O_ALLOWANCE;
+        eq      EA, EH;         // Is it an allowance?
+        jif     CO, APPROVE_ALLOWANCE_OUT;
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_OUT; // Set error code for the case of failure
+        put     EH, O_AMOUNT;
+        eq      EA, EH;         // otherwise it must be a balance
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_OUT; // Set error code for the case of failure
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+        mov     E8, E3;         // add output to output accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E3, E8;
+        jmp     LOOP_APPROVE_OUTPUTS;
+
+     label APPROVE_ALLOWANCE_OUT:
+        put     E1, ERRNO_INVALID_ALLOWANCE; // Set error code for the case of failure
+        call    FN_FUNGIBLE_ALLOWANCE_VALUE; // Validate the allowance
+        mov     E8, E3;         // add the allowance to output accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E3, E8;
+        jmp     LOOP_APPROVE_OUTPUTS;
+
+     label APPROVE_OUTPUTS_DONE:
+        put     E1, ERRNO_SUM_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that the sum of inputs equals the sum of outputs
+        chk     CO;             // fail if not
+
+        clr     E1;             // Clear the error code
+        ret;
+
+        // Validates the allowance loaded into `EA`-`ED`, failing with the error code from `E1`
+     routine FN_FUNGIBLE_ALLOWANCE_VALUE:
+        test    EB;             // The amount must be set
+        chk     CO;             // Or we should fail
+        fits    EB, 64.bits;    // It must fit in u64
+        chk     CO;             // Or we should fail
+        test    EC;             // The grantee must be set
+        chk     CO;             // Or we should fail
+        call    shared, FN_ASSERT_PAIR_VALUE; // ensure there are no extra field elements
+        ret;
+
+     routine FN_FUNGIBLE_ISSUE_MIN_OUT:
+        call    FN_FUNGIBLE_ISSUE_SPEC; // Verify the specification and the supply, saved into `E2`
+
+        // Read the minimal output value
+        put     E1, ERRNO_NO_MIN_OUTPUT; // Set error code for the case of failure
+        ldo     immutable;      // Read global state - minimal output value
+        chk     CO;             // It must exist
+        call    FN_FUNGIBLE_MIN_OUTPUT; // Validate and save the minimal output value into `E6`
+
+        // Validate that the issued amount is equal to the sum of the outputs
+        call    FN_FUNGIBLE_SUM_OUTPUTS;// Compute a sum of outputs
+        put     E1, ERRNO_SUM_ISSUE_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that circulating supply equals to the sum of outputs
+        chk     CO;             // fail if not
+        call    FN_FUNGIBLE_MIN_OUTPUTS; // Check there are no dust outputs
+
+        // Optional details and reference must follow the minimal output value
+        call    shared, FN_ASSET_DETAILS;
+
+        // Check there is no more global state
+        put     E1, ERRNO_UNEXPECTED_GLOBAL; // Set error code for the case of failure
+        ldo     immutable;
+        not     CO;
+        chk     CO;
+
+        call    FN_FUNGIBLE_TICKER; // The ticker must have a value
+        ret;
+
+     routine FN_FUNGIBLE_TRANSFER_MIN_OUT:
+        // The minimal output value must be provided as the only global input
+        put     E1, ERRNO_NO_MIN_OUTPUT; // Set error code for the case of failure
+        rsti    immutable;
+        ldi     immutable;      // Read the global input - minimal output value
+        chk     CO;             // It must exist
+        call    FN_FUNGIBLE_MIN_OUTPUT; // Validate and save the minimal output value into `E6`
+
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        ldi     immutable;      // There must be no other global inputs
+        not     CO;
+        chk     CO;
+
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_OUT; // Set error code for the case of failure
+        rsto    immutable;
+        cknxo   immutable;      // There must be no global state defined
+        not     CO;
+        chk     CO;
+
+        // Verify owned state
+        call    FN_FUNGIBLE_SUM_INPUTS; // Compute a sum of inputs into E2
+        call    FN_FUNGIBLE_SUM_OUTPUTS; // Compute a sum of outputs into E3
+        put     E1, ERRNO_SUM_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that the sum of inputs equals the sum of outputs
+        chk     CO;             // fail if not
+        call    FN_FUNGIBLE_MIN_OUTPUTS; // Check there are no dust outputs
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     proc FN_FUNGIBLE_MIN_OUTPUTS:
+        put     E1, ERRNO_DUST_OUTPUT; // Set error code for the case of failure
+        clr     E5;             // E5 is set once the change output is seen
+        rsto    destructible;   // Start iteration over outputs
+
+     label LOOP_MIN_OUTPUTS:
+        ldo     destructible;   // load next state value
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+
+        neg     E7, E6;         // E7 = -min
+        add     E7, EB;         // E7 = value - min, which wraps around the field order (and does
+        fits    E7, 64.bits;    // not fit u64) if the value is below the minimum
+        jif     CO, LOOP_MIN_OUTPUTS; // - otherwise the output is allowed
+        test    E5;             // Only a single output, treated as the change, may be below it
+        not     CO;
+        chk     CO;             // fail if not
+        put     E5, 1;          // Mark that we have seen the change output
+        jmp     LOOP_MIN_OUTPUTS; // loop
+
+        // Validates the minimal output value global loaded into `EA`-`ED` and saves it into `E6`
+     routine FN_FUNGIBLE_MIN_OUTPUT:
+        put     E8, G_MIN_OUTPUT; // Load minimal output value type
+        eq      EA, E8;         // It must have a correct state type
+        chk     CO;             // Or fail otherwise
+        test    EB;             // It must be set
+        chk     CO;             // Or we should fail
+        fits    EB, 64.bits;    // It must fit in u64
+        chk     CO;             // Or we should fail
+        put     E8, 0;
+        eq      EB, E8;         // It must not be zero
+        not     CO;
+        chk     CO;             // Or we should fail
+        mov     E6, EB;         // Save the minimal output value
+        test    EC;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        test    ED;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        ret;
+
+     routine FN_FUNGIBLE_RETIRE:
+        // Verify that no global state is used
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        rsti    immutable;
+        cknxi   immutable;
+        not     CO;
+        chk     CO;
+
+        // The retirement marker must be published
+        put     E1, ERRNO_NO_RETIRED; // Set error code for the case of failure
+        rsto    immutable;
+        ldo     immutable;      // Read the first global state - retirement marker
+        chk     CO;             // - it must exist
+        put     EH, G_RETIRED;
+        eq      EA, EH;         // - it must have the correct state type
+        chk     CO;             // - - or fail otherwise
+        put     E1, ERRNO_UNEXPECTED_GLOBAL; // Set error code for the case of failure
+        ldo     immutable;      // There must be no other global state
+        not     CO;
+        chk     CO;
+
+        // Consume the inputs, which must include the issuer right, without defining any outputs
+        clr     E5;             // E5 is set once the issuer right is seen
+        rsti    destructible;   // Start iteration over inputs
+
+     label LOOP_RETIRE_INPUTS:
+        ldi     destructible;   // load next state value
+        not     CO;
+        jif     CO, RETIRE_INPUTS_DONE; // Finish if no more elements are present
+
+        put     E1, ERRNO_EMPTY_STATE; // Set error code for the case of failure
+        test    EA;             // a genuine owned state must carry a type tag
+        chk     CO;             // fail if not
+
+        put     EH, O_RETIRE_RIGHT;
+        eq      EA, EH;         // Is it the issuer right?
+        jif     CO, RETIRE_RIGHT_IN;
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_IN; // Set error code for the case of failure
+        put     EH, O_AMOUNT;
+        eq      EA, EH;         // otherwise it must be a balance
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_IN; // Set error code for the case of failure
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+        jmp     LOOP_RETIRE_INPUTS;
+
+     label RETIRE_RIGHT_IN:
+        put     E1, ERRNO_INVALID_RETIRE_RIGHT; // Set error code for the case of failure
+        call    FN_FUNGIBLE_RETIRE_RIGHT; // Validate the issuer right
+        jmp     LOOP_RETIRE_INPUTS;
+
+     label RETIRE_INPUTS_DONE:
+        put     E1, ERRNO_NO_RETIRE_RIGHT; // Set error code for the case of failure
+        test    E5;             // The issuer right must be spent
+        chk     CO;             // - or fail otherwise
+
+        put     E1, ERRNO_RETIRE_HAS_OUTPUTS; // Set error code for the case of failure
+        rsto    destructible;
+        cknxo   destructible;   // There must be no owned state defined
+        not     CO;
+        chk     CO;
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     routine FN_FUNGIBLE_TRANSFER_CLASSED:
+        // Verify that no global state is defined
+        call    shared, FN_GLOBAL_ABSENT;
+
+        // Verify owned state
+        call    FN_FUNGIBLE_SUM_INPUTS_CLASSED; // Compute a sum of inputs into E2
+        call    FN_FUNGIBLE_SUM_OUTPUTS; // Compute a sum of outputs into E3
+        put     E1, ERRNO_SUM_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that the sum of inputs equals the sum of outputs
+        chk     CO;             // fail if not
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     proc FN_FUNGIBLE_SUM_INPUTS_CLASSED:
+        put     E2, 0;          // Set initial sum to zero
+        put     EH, O_AMOUNT;   // Set EH to the field element representing the owned value
+    });
+    code.extend(count_init.iter().cloned());
+    code.extend(uasm! {
+        rsti    destructible;   // Start iteration over inputs
+
+     label LOOP_INPUTS_CLASSED:
+        ldi     destructible;   // load next state value
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+    });
+    code.extend(count_next.iter().cloned());
+    code.extend(uasm! {
+        put     E1, ERRNO_EMPTY_STATE; // Set error code for the case of failure
+        test    EA;             // a genuine owned state must carry a type tag
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_IN; // Set error code for the case of failure
+        eq      EA, EH;         // do we have a correct state type?
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_IN; // Set error code for the case of failure
+        call    shared, FN_ASSERT_PAIR_VALUE; // `EC` may hold the recipient class
+
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+        mov     E8, E2;         // add input to input accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E2, E8;
+
+        jmp     LOOP_INPUTS_CLASSED; // loop
+
+     routine FN_FUNGIBLE_ISSUE_RETIRABLE:
+        call    FN_FUNGIBLE_ISSUE_SPEC; // Verify the specification and the supply, saved into `E2`
+
+        // Sum the balances, which must be accompanied by a single issuer right
+        put     E3, 0;          // E3 will contain the sum of the balance outputs
+        clr     E5;             // E5 is set once the issuer right is seen
+        rsto    destructible;   // Start iteration over outputs
+
+     label LOOP_RETIRABLE_OUTPUTS:
+        ldo     destructible;   // load next state value
+        not     CO;
+        jif     CO, RETIRABLE_OUTPUTS_DONE; // Finish if no more elements are present
+
+        put     EH, O_RETIRE_RIGHT;
+        eq      EA, EH;         // Is it the issuer right?
+        jif     CO, RETIRE_RIGHT_OUT;
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_OUT; // Set error code for the case of failure
+        put     EH, O_AMOUNT;
+        eq      EA, EH;         // otherwise it must be a balance
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_OUT; // Set error code for the case of failure
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+        mov     E8, E3;         // add output to output accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E3, E8;
+        jmp     LOOP_RETIRABLE_OUTPUTS;
+
+     label RETIRE_RIGHT_OUT:
+        put     E1, ERRNO_INVALID_RETIRE_RIGHT; // Set error code for the case of failure
+        call    FN_FUNGIBLE_RETIRE_RIGHT; // Validate the issuer right
+        jmp     LOOP_RETIRABLE_OUTPUTS;
+
+     label RETIRABLE_OUTPUTS_DONE:
+        put     E1, ERRNO_NO_RETIRE_RIGHT; // Set error code for the case of failure
+        test    E5;             // The issuer right must be assigned
+        chk     CO;             // - or fail otherwise
+
+        put     E1, ERRNO_SUM_ISSUE_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that circulating supply equals to the sum of outputs
+        chk     CO;             // fail if not
+
+        // Optional details and reference must follow the supply
+        call    shared, FN_ASSET_DETAILS;
+
+        // Check there is no more global state
+        put     E1, ERRNO_UNEXPECTED_GLOBAL; // Set error code for the case of failure
+        ldo     immutable;
+        not     CO;
+        chk     CO;
+
+        call    FN_FUNGIBLE_TICKER; // The ticker must have a value
+        ret;
+
+        // Validates the issuer right loaded into `EA`-`ED`, failing with the error code from `E1`
+     routine FN_FUNGIBLE_RETIRE_RIGHT:
+        test    E5;             // Only a single right may be present
+        not     CO;
+        chk     CO;             // - or fail otherwise
+        put     E5, 1;          // Mark that we have seen the right
+        test    EB;             // The right must have no value
+        not     CO;
+        chk     CO;             // - or fail otherwise
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+        ret;
+    });
+
+    let deps = [&shared_lib()];
+    let lib = CompiledLib::compile(&mut code, &deps)
+        .unwrap_or_else(|err| panic!("Invalid script: {err}"));
+    debug_assert_eq!(validate_dependencies(&lib, &deps), Ok(()));
+    lib
+}
+
+#[cfg(test)]
+mod tests {
+    use amplify::num::u256;
+    use hypersonic::{AuthToken, Input, Instr, StateCell, StateData, StateValue, VmContext};
+    use strict_types::StrictDumb;
+    use zkaluvm::alu::{CoreConfig, CoreExt, Lib, LibId, Supercore, Vm};
+    use zkaluvm::{GfaConfig, GfaCore, RegE, FIELD_ORDER_SECP};
+
+    use super::*;
+    use crate::test_helpers::{fungible_ctx, TestContext};
+    use crate::{
+        ERRNO_INVALID_DETAILS, ERRNO_INVALID_REFERENCE, ERRNO_NO_NAME, ERRNO_NO_PRECISION,
+        ERRNO_NO_TICKER, ERRNO_SUM_OVERFLOW, G_DETAILS, G_LOT_SIZE, G_NAME, G_PRECISION,
+        G_RECIPIENT_CLASSES, G_REFERENCE, G_RETIRED, G_RGB20_DETAILS, G_SUPPLY, G_TICKER, O_AMOUNT,
+        O_AMOUNT_B,
+    };
+
+    const CONFIG: CoreConfig = CoreConfig {
+        halt: true,
+        complexity_lim: Some(500_000_000),
+    };
+
+    fn harness() -> (CompiledLib, Vm<Instr<LibId>>, impl Fn(LibId) -> Option<Lib>) {
+        let vm = Vm::<Instr<LibId>>::with(
+            CONFIG,
+            GfaConfig {
+                field_order: FIELD_ORDER_SECP,
+            },
+        );
+        fn resolver(id: LibId) -> Option<Lib> {
+            let fungible = fungible();
+            let shared = shared_lib();
+            if fungible.as_lib().lib_id() == id {
+                return Some(fungible.into_lib());
+            }
+            if shared.as_lib().lib_id() == id {
+                return Some(shared.into_lib());
+            }
+            panic!("Unknown library: {id}");
+        }
+        (fungible(), vm, resolver)
+    }
+
+    fn errno(vm: &Vm<Instr<LibId>>) -> u256 {
+        let gfa: GfaCore = vm.core.cx.subcore();
+        gfa.get(RegE::E1).map(|fe| fe.to_u256()).unwrap_or_default()
+    }
+
+    const AMOUNTS_OVERFLOW: &[&[u64]] = &[
+        &[u64::MAX, 1, 1],
+        &[u64::MAX - 1, 2],
+        &[u64::MAX, u64::MAX - 1],
+        &[u64::MAX, u64::MAX - 1, 1],
+        &[u64::MAX, u64::MAX],
+        &[u64::MAX / 2 + 1, u64::MAX / 2 + 1],
+        &[u64::MAX / 2, u64::MAX / 2, 2],
+    ];
+    const AMOUNTS_OK: &[&[u64]] = &[
+        &[],
+        &[0u64],
+        &[1u64; 4],
+        &[10u64; 100],
+        &[u64::MAX - 1, 1],
+        &[u64::MAX],
+        &[u64::MAX / 2 - 1, u64::MAX / 2],
+    ];
+
+    #[test]
+    fn expected_supply_sums() {
+        for amounts in AMOUNTS_OK {
+            let outputs = fungible_ctx(&[], amounts).destructible_output;
+            assert_eq!(expected_supply(&outputs), Some(amounts.iter().sum()));
+        }
+        for amounts in AMOUNTS_OVERFLOW {
+            let outputs = fungible_ctx(&[], amounts).destructible_output;
+            assert_eq!(expected_supply(&outputs), None);
+        }
+    }
+
+    #[test]
+    fn expected_supply_invalid() {
+        let cell = crate::test_helpers::cell;
+        assert_eq!(
+            expected_supply(&[cell(StateValue::new(O_AMOUNT_B, 10u64))]),
+            None
+        );
+        assert_eq!(
+            expected_supply(&[
+                cell(StateValue::new(O_AMOUNT, 10u64)),
+                cell(StateValue::None)
+            ]),
+            None
+        );
+        let supply = expected_supply(&fungible_ctx(&[], &[100, 500, 400]).destructible_output);
+        assert_eq!(supply, Some(1000));
+    }
+
+    #[test]
+    fn sum_inputs_overflow() {
+        for input in AMOUNTS_OVERFLOW {
+            let (lib, mut vm, resolver) = harness();
+            let context = fungible_ctx(input, &[]);
+            let res = vm
+                .exec(
+                    lib.routine(FN_FUNGIBLE_SUM_INPUTS),
+                    &context.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            assert!(!res);
+            assert_eq!(errno(&vm), ERRNO_SUM_OVERFLOW);
+        }
+    }
+
+    #[test]
+    fn sum_outputs_overflow() {
+        for output in AMOUNTS_OVERFLOW {
+            let (lib, mut vm, resolver) = harness();
+            let context = fungible_ctx(&[], output);
+            let res = vm
+                .exec(
+                    lib.routine(FN_FUNGIBLE_SUM_OUTPUTS),
+                    &context.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            assert!(!res);
+            assert_eq!(errno(&vm), ERRNO_SUM_OVERFLOW);
+        }
+    }
+
+    #[test]
+    fn sum_inputs() {
+        for input in AMOUNTS_OK {
+            let (lib, mut vm, resolver) = harness();
+            let sum = input.iter().sum::<u64>();
+            let context = fungible_ctx(input, &[]);
+            let res = vm
+                .exec(
+                    lib.routine(FN_FUNGIBLE_SUM_INPUTS),
+                    &context.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            let gfa: GfaCore = vm.core.cx.subcore();
+            assert_eq!(gfa.get(RegE::E2).unwrap().to_u256(), u256::from(sum));
+            assert!(res);
+        }
+    }
+
+    #[test]
+    fn sum_outputs() {
+        for output in AMOUNTS_OK {
+            let (lib, mut vm, resolver) = harness();
+            let sum = output.iter().sum::<u64>();
+            let context = fungible_ctx(&[], output);
+            let res = vm
+                .exec(
+                    lib.routine(FN_FUNGIBLE_SUM_OUTPUTS),
+                    &context.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            let gfa: GfaCore = vm.core.cx.subcore();
+            assert_eq!(gfa.get(RegE::E3).unwrap().to_u256(), u256::from(sum));
+            assert!(res);
+        }
+    }
+
+    #[test]
+    fn sum_extra_field_elements() {
+        let non_canonical = [
+            StateValue::Triple {
+                first: O_AMOUNT.into(),
+                second: 1000u64.into(),
+                third: 1u64.into(),
+            },
+            StateValue::Quadruple {
+                first: O_AMOUNT.into(),
+                second: 1000u64.into(),
+                third: 0u64.into(),
+                fourth: 1u64.into(),
+            },
+        ];
+        for value in non_canonical {
+            for (routine, context, expected) in [
+                (
+                    FN_FUNGIBLE_SUM_INPUTS,
+                    TestContext::with(&[value], &[]),
+                    ERRNO_INVALID_BALANCE_IN,
+                ),
+                (
+                    FN_FUNGIBLE_SUM_OUTPUTS,
+                    TestContext::with(&[], &[value]),
+                    ERRNO_INVALID_BALANCE_OUT,
+                ),
+            ] {
+                let (lib, mut vm, resolver) = harness();
+                let res = vm
+                    .exec(lib.routine(routine), &context.as_context(), resolver)
+                    .is_ok();
+                assert!(!res, "{value:?}");
+                assert_eq!(errno(&vm), expected, "{value:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn genesis_empty() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[],
+            immutable_output: &[],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_FUNGIBLE_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(!res);
+        assert_eq!(errno(&vm), ERRNO_NO_TICKER);
+    }
+
+    #[test]
+    fn genesis_empty_ticker() {
+        let empty = StateData {
+            value: StateValue::Single {
+                first: G_TICKER.into(),
+            },
+            raw: None,
+        };
+        let spec = [
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ];
+        for (ticker, expected) in [
+            (StateData::new(G_TICKER, 0u8), None),
+            (empty, Some(ERRNO_EMPTY_TICKER)),
+        ] {
+            let context = fungible_ctx(&[], &[1000])
+                .with_globals([ticker].into_iter().chain(spec.iter().cloned()));
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(
+                    lib.routine(FN_FUNGIBLE_ISSUE),
+                    &context.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            assert_eq!(res, expected.is_none());
+            if let Some(errno_expected) = expected {
+                assert_eq!(errno(&vm), errno_expected);
+            }
+        }
+    }
+
+    #[test]
+    fn genesis_rgb25_details_no_value() {
+        let details = StateData {
+            value: StateValue::Single {
+                first: G_DETAILS.into(),
+            },
+            raw: None,
+        };
+        let (res, _) = genesis_rgb25(&[
+            details,
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ]);
+        assert!(res);
+    }
+
+    #[test]
+    fn genesis_missing_globals() {
+        let mut context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[StateCell {
+                data: StateValue::new(O_AMOUNT, 1000_u64),
+                auth: AuthToken::strict_dumb(),
+                lock: None,
+            }],
+            immutable_output: &[],
+        };
+        let globals = [
+            (
+                &[
+                    StateData::new(G_TICKER, 0u8),
+                    StateData::new(G_PRECISION, 18_u8),
+                    StateData::new(G_SUPPLY, 1000_u64),
+                ][..],
+                ERRNO_NO_NAME,
+            ),
+            (
+                &[
+                    StateData::new(G_NAME, 0u8),
+                    StateData::new(G_PRECISION, 18_u8),
+                    StateData::new(G_SUPPLY, 1000_u64),
+                ],
+                ERRNO_NO_TICKER,
+            ),
+            (
+                &[
+                    StateData::new(G_TICKER, 0u8),
+                    StateData::new(G_NAME, 0u8),
+                    StateData::new(G_SUPPLY, 1000_u64),
+                ],
+                ERRNO_NO_PRECISION,
+            ),
+            (
+                &[
+                    StateData::new(G_TICKER, 0u8),
+                    StateData::new(G_NAME, 0u8),
+                    StateData::new(G_PRECISION, 18_u8),
+                ],
+                ERRNO_NO_ISSUED,
+            ),
+            (
+                &[StateData::new(G_TICKER, 0u8), StateData::new(G_NAME, 0u8)],
+                ERRNO_NO_PRECISION,
+            ),
+        ];
+        for (global, expected) in globals {
+            context.immutable_output = global;
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(lib.routine(FN_FUNGIBLE_ISSUE), &context, resolver)
+                .is_ok();
+            assert!(!res);
+            assert_eq!(errno(&vm), expected);
+        }
+    }
+
+    #[test]
+    fn genesis_missing_owned() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[],
+            immutable_output: &[
+                StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, 18_u8),
+                StateData::new(G_SUPPLY, 1000_u64),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_FUNGIBLE_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(!res);
+        assert_eq!(errno(&vm), ERRNO_SUM_ISSUE_MISMATCH);
+    }
+
+    #[test]
+    fn genesis_supply_mismatch() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[StateCell {
+                data: StateValue::new(O_AMOUNT, 1001_u64),
+                auth: AuthToken::strict_dumb(),
+                lock: None,
+            }],
+            immutable_output: &[
+                StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, 18_u8),
+                StateData::new(G_SUPPLY, 1000_u64),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_FUNGIBLE_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(!res);
+        assert_eq!(errno(&vm), ERRNO_SUM_ISSUE_MISMATCH);
+    }
+
+    #[test]
+    fn genesis_correct() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[StateCell {
+                data: StateValue::new(O_AMOUNT, 1000_u64),
+                auth: AuthToken::strict_dumb(),
+                lock: None,
+            }],
+            immutable_output: &[
+                StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, 18_u8),
+                StateData::new(G_SUPPLY, 1000_u64),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_FUNGIBLE_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(res);
+    }
+
+    fn genesis_globals(globals: &[StateData]) -> (bool, u256) {
+        let context = fungible_ctx(&[], &[1000]).with_globals(globals.iter().cloned());
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_ISSUE),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    fn genesis_scaled(precision: u8, supply: u64) -> (bool, u256) {
+        let context = fungible_ctx(&[], &[supply]).with_globals([
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, precision),
+            StateData::new(G_SUPPLY, supply),
+        ]);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_ISSUE_SCALED),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn genesis_zero_supply() {
+        let globals = |supply: u64| {
+            [
+                StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, 2u8),
+                StateData::new(G_SUPPLY, supply),
+            ]
+        };
+        let genesis = |supply: u64| {
+            let context = fungible_ctx(&[], &[supply]).with_globals(globals(supply));
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(
+                    lib.routine(FN_FUNGIBLE_ISSUE),
+                    &context.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            (res, errno(&vm))
+        };
+        assert_eq!(genesis(0), (false, ERRNO_ZERO_SUPPLY));
+        assert_eq!(genesis(1), (true, u256::ZERO));
+        assert_eq!(genesis(1000), (true, u256::ZERO));
+    }
+
+    #[test]
+    fn genesis_precision() {
+        let globals = |precision: u64| {
+            [
+                StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, precision),
+                StateData::new(G_SUPPLY, 1000u64),
+            ]
+        };
+        assert_eq!(genesis_globals(&globals(0)), (true, u256::ZERO));
+        assert_eq!(
+            genesis_globals(&globals(MAX_PRECISION as u64)),
+            (true, u256::ZERO)
+        );
+        for precision in [MAX_PRECISION as u64 + 1, 255, 256] {
+            assert_eq!(
+                genesis_globals(&globals(precision)),
+                (false, ERRNO_PRECISION_OVERFLOW)
+            );
+        }
+    }
+
+    #[test]
+    fn genesis_supply_scale() {
+        const PRECISION: u8 = 18;
+        let max = u64::MAX / 10u64.pow(PRECISION as u32);
+        assert!(genesis_scaled(PRECISION, max).0);
+        let (res, errno) = genesis_scaled(PRECISION, max + 1);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_SUPPLY_PRECISION_OVERFLOW);
+
+        assert!(genesis_scaled(0, u64::MAX).0);
+        assert!(genesis_scaled(8, u64::MAX / 100_000_000).0);
+        assert!(!genesis_scaled(8, u64::MAX / 100_000_000 + 1).0);
+    }
+
+    #[test]
+    fn genesis_details() {
+        let (res, _) = genesis_globals(&[
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+            StateData::new(G_RGB20_DETAILS, 0u8),
+        ]);
+        assert!(res);
+    }
+
+    #[test]
+    fn genesis_details_misplaced() {
+        let (res, errno) = genesis_globals(&[
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_RGB20_DETAILS, 0u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_NO_ISSUED);
+
+        let (res, errno) = genesis_globals(&[
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+            StateData::new(G_RGB20_DETAILS, 0u8),
+            StateData::new(G_RGB20_DETAILS, 0u8),
+        ]);
+        assert!(!res);
+        // The slot after the details is reserved for the reference
+        assert_eq!(errno, ERRNO_INVALID_REFERENCE);
+    }
+
+    #[test]
+    fn genesis_supply_misplaced() {
+        let (res, errno) = genesis_globals(&[
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+            StateData::new(G_PRECISION, 18_u8),
+        ]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_SUPPLY_MISPLACED);
+
+        let (res, errno) = genesis_globals(&[
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_MULTIPLE_SUPPLY);
+    }
+
+    #[test]
+    fn genesis_multiple_supply() {
+        let (res, errno) = genesis_globals(&[
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ]);
+        assert!(res);
+        assert_eq!(errno, u256::ZERO);
+
+        // The second supply would otherwise be reported as an unexpected global only
+        let (res, errno) = genesis_globals(&[
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+            StateData::new(G_RGB20_DETAILS, 0u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_MULTIPLE_SUPPLY);
+    }
+
+    #[test]
+    fn genesis_multiple_precision() {
+        // A repeated precision following the supply is misplaced, thus it is checked before it
+        let (res, errno) = genesis_globals(&[
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 2_u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ]);
+        assert!(!res);
+        assert_eq!(errno, crate::ERRNO_MULTIPLE_PRECISION);
+    }
+
+    fn genesis_classed(allowlist: StateValue, outputs: &[StateValue]) -> (bool, u256) {
+        let context = TestContext::with(&[], outputs).with_globals([
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 2u8),
+            StateData::new(G_SUPPLY, 1000u64),
+            StateData {
+                value: allowlist,
+                raw: None,
+            },
+        ]);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_ISSUE_CLASSED),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    fn classed(amount: u64, class: u64) -> StateValue {
+        StateValue::Triple {
+            first: O_AMOUNT.into(),
+            second: amount.into(),
+            third: class.into(),
+        }
+    }
+
+    #[test]
+    fn genesis_classed_listed() {
+        let allowlist = StateValue::Triple {
+            first: G_RECIPIENT_CLASSES.into(),
+            second: 10u64.into(),
+            third: 20u64.into(),
+        };
+        let outputs = [
+            classed(400, 10),
+            classed(300, 20),
+            StateValue::new(O_AMOUNT, 300u64),
+        ];
+        assert_eq!(genesis_classed(allowlist, &outputs), (true, u256::ZERO));
+
+        let allowlist = StateValue::Quadruple {
+            first: G_RECIPIENT_CLASSES.into(),
+            second: 10u64.into(),
+            third: 20u64.into(),
+            fourth: 30u64.into(),
+        };
+        let outputs = [classed(500, 30), classed(500, 10)];
+        assert_eq!(genesis_classed(allowlist, &outputs), (true, u256::ZERO));
+    }
+
+    #[test]
+    fn genesis_classed_unlisted() {
+        let allowlist = StateValue::Triple {
+            first: G_RECIPIENT_CLASSES.into(),
+            second: 10u64.into(),
+            third: 20u64.into(),
+        };
+        let outputs = [classed(500, 10), classed(500, 30)];
+        assert_eq!(
+            genesis_classed(allowlist, &outputs),
+            (false, ERRNO_UNLISTED_RECIPIENT_CLASS)
+        );
+
+        let allowlist = StateValue::new(G_RECIPIENT_CLASSES, 10u64);
+        let outputs = [classed(500, 10), classed(500, 20)];
+        assert_eq!(
+            genesis_classed(allowlist, &outputs),
+            (false, ERRNO_UNLISTED_RECIPIENT_CLASS)
+        );
+    }
+
+    #[test]
+    fn genesis_classed_no_allowlist() {
+        let outputs = [StateValue::new(O_AMOUNT, 1000u64)];
+        assert_eq!(
+            genesis_classed(StateValue::new(G_REFERENCE, 10u64), &outputs),
+            (false, ERRNO_NO_RECIPIENT_CLASSES)
+        );
+        assert_eq!(
+            genesis_classed(
+                StateValue::Single {
+                    first: G_RECIPIENT_CLASSES.into()
+                },
+                &outputs
+            ),
+            (false, ERRNO_NO_RECIPIENT_CLASSES)
+        );
+    }
+
+    fn transfer_classed(inputs: &[StateValue], outputs: &[StateValue]) -> (bool, u256) {
+        let context = TestContext::with(inputs, outputs);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_TRANSFER_CLASSED),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn transfer_classed_spends_class() {
+        let inputs = [classed(400, 10), StateValue::new(O_AMOUNT, 100u64)];
+        let outputs = [
+            StateValue::new(O_AMOUNT, 250u64),
+            StateValue::new(O_AMOUNT, 250u64),
+        ];
+        assert_eq!(transfer_classed(&inputs, &outputs), (true, u256::ZERO));
+        assert_eq!(
+            transfer_classed(&inputs, &[StateValue::new(O_AMOUNT, 600u64)]),
+            (false, ERRNO_SUM_MISMATCH)
+        );
+        // The plain transfer can't spend a classed allocation
+        let context = TestContext::with(&inputs, &outputs);
+        let (lib, mut vm, resolver) = harness();
+        assert!(vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_TRANSFER),
+                &context.as_context(),
+                resolver
+            )
+            .is_err());
+        assert_eq!(errno(&vm), ERRNO_INVALID_BALANCE_IN);
+    }
+
+    #[test]
+    fn transfer_classed_plain_outputs() {
+        let inputs = [classed(400, 10)];
+        assert_eq!(
+            transfer_classed(&inputs, &[classed(400, 10)]),
+            (false, ERRNO_INVALID_BALANCE_OUT)
+        );
+        let invalid = StateValue::Quadruple {
+            first: O_AMOUNT.into(),
+            second: 400u64.into(),
+            third: 10u64.into(),
+            fourth: 1u64.into(),
+        };
+        assert_eq!(
+            transfer_classed(&[invalid], &[StateValue::new(O_AMOUNT, 400u64)]),
+            (false, ERRNO_INVALID_BALANCE_IN)
+        );
+    }
+
+    #[test]
+    fn genesis_extra_global() {
+        let (res, errno) = genesis_globals(&[
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+            StateData::new(G_RGB20_DETAILS, 0u8),
+            StateData::new(G_REFERENCE, 0u8),
+            StateData::new(G_MAX_PER_OUTPUT, 10_u64),
+        ]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_UNEXPECTED_GLOBAL);
+    }
+
+    #[test]
+    fn genesis_details_invalid() {
+        let (res, errno) = genesis_globals(&[
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+            StateData::new(G_NAME, 0u8),
+        ]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_INVALID_DETAILS);
+    }
+
+    #[test]
+    fn genesis_reference() {
+        let spec = [
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ];
+        let details = StateData::new(G_RGB20_DETAILS, 0u8);
+        let reference = StateData::new(G_REFERENCE, 0u8);
+
+        assert!(genesis_globals(&spec).0);
+        assert!(genesis_globals(&[&spec[..], &[reference.clone()]].concat()).0);
+        assert!(genesis_globals(&[&spec[..], &[details.clone(), reference.clone()]].concat()).0);
+    }
+
+    #[test]
+    fn genesis_reference_misplaced() {
+        let spec = [
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ];
+        let details = StateData::new(G_RGB20_DETAILS, 0u8);
+        let reference = StateData::new(G_REFERENCE, 0u8);
+
+        let (res, errno) =
+            genesis_globals(&[&spec[..], &[reference.clone(), details.clone()]].concat());
+        assert!(!res);
+        assert_eq!(errno, ERRNO_UNEXPECTED_GLOBAL);
+
+        let (res, errno) =
+            genesis_globals(&[&spec[..], &[details, reference.clone(), reference]].concat());
+        assert!(!res);
+        assert_eq!(errno, ERRNO_UNEXPECTED_GLOBAL);
+    }
+
+    fn genesis_rgb25(globals: &[StateData]) -> (bool, u256) {
+        let context = fungible_ctx(&[], &[1000]).with_globals(globals.iter().cloned());
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_ISSUE_RGB25),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn genesis_rgb25_details() {
+        let (res, _) = genesis_rgb25(&[
+            StateData::new(G_DETAILS, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ]);
+        assert!(res);
+    }
+
+    #[test]
+    fn genesis_rgb25_no_details() {
+        let (res, errno) = genesis_rgb25(&[
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_NO_DETAILS);
+
+        let (res, errno) = genesis_rgb25(&[]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_NO_DETAILS);
+    }
+
+    #[test]
+    fn genesis_rgb25_details_invalid() {
+        let (res, errno) = genesis_rgb25(&[
+            StateData {
+                value: StateValue::Triple {
+                    first: G_DETAILS.into(),
+                    second: 0u64.into(),
+                    third: 1u64.into(),
+                },
+                raw: None,
+            },
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 1000_u64),
+        ]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_NO_DETAILS);
+    }
+
+    #[test]
+    fn transfer_empty_input() {
+        let valid = StateValue::new(O_AMOUNT, 1000u64);
+        for inputs in [&[StateValue::None][..], &[valid, StateValue::None]] {
+            let context = TestContext::with(inputs, &[valid]);
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(
+                    lib.routine(FN_FUNGIBLE_TRANSFER),
+                    &context.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            assert!(!res);
+            assert_eq!(errno(&vm), ERRNO_EMPTY_STATE);
+        }
+    }
+
+    #[test]
+    fn transfer_invalid_amount() {
+        let amounts = [
+            u256::from_inner([0, 1, 0, 0]),
+            u256::from_inner([u64::MAX, u64::MAX, 0, 0]),
+            FIELD_ORDER_SECP - u256::ONE,
+            u256::MAX,
+        ];
+        for amount in amounts {
+            let invalid = StateValue::new(O_AMOUNT, amount);
+            let valid = StateValue::new(O_AMOUNT, 1000u64);
+
+            for (inputs, outputs, expected) in [
+                ([invalid], [valid], ERRNO_INVALID_BALANCE_IN),
+                ([valid], [invalid], ERRNO_INVALID_BALANCE_OUT),
+            ] {
+                let context = TestContext::with(&inputs, &outputs);
+                let (lib, mut vm, resolver) = harness();
+                let res = vm
+                    .exec(
+                        lib.routine(FN_FUNGIBLE_TRANSFER),
+                        &context.as_context(),
+                        resolver,
+                    )
+                    .is_ok();
+                assert!(!res, "{amount}");
+                // Values exceeding the field order may not be loaded into the registers at all
+                if amount < FIELD_ORDER_SECP {
+                    assert_eq!(errno(&vm), expected, "{amount}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transfer_unexpected_owned_type() {
+        let other = StateValue::new(O_AMOUNT_B, 1000u64);
+        let valid = StateValue::new(O_AMOUNT, 1000u64);
+        for (inputs, outputs, expected) in [
+            (
+                vec![valid, other],
+                vec![valid],
+                ERRNO_UNEXPECTED_OWNED_TYPE_IN,
+            ),
+            (vec![other], vec![valid], ERRNO_UNEXPECTED_OWNED_TYPE_IN),
+            (
+                vec![valid],
+                vec![valid, other],
+                ERRNO_UNEXPECTED_OWNED_TYPE_OUT,
+            ),
+        ] {
+            let context = TestContext::with(&inputs, &outputs);
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(
+                    lib.routine(FN_FUNGIBLE_TRANSFER),
+                    &context.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            assert!(!res);
+            assert_eq!(errno(&vm), expected);
+        }
+    }
+
+    fn transfer_with_change(inputs: &[u64], outputs: &[u64]) -> (bool, u256) {
+        let context = fungible_ctx(inputs, outputs);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_TRANSFER_WITH_CHANGE),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn transfer_consolidation() {
+        assert_eq!(
+            transfer_with_change(&[100, 200], &[300]),
+            (false, ERRNO_NO_CHANGE_OUTPUT)
+        );
+        assert_eq!(
+            transfer_with_change(&[100, 200, 300], &[600]),
+            (false, ERRNO_NO_CHANGE_OUTPUT)
+        );
+    }
+
+    #[test]
+    fn transfer_with_change_output() {
+        assert_eq!(
+            transfer_with_change(&[100, 200], &[250, 50]),
+            (true, u256::ZERO)
+        );
+        assert_eq!(transfer_with_change(&[300], &[300]), (true, u256::ZERO));
+        assert_eq!(
+            transfer_with_change(&[100, 200], &[250, 51]),
+            (false, ERRNO_SUM_MISMATCH)
+        );
+    }
+
+    fn balance(amount: u64) -> StateValue {
+        StateValue::new(O_AMOUNT, amount)
+    }
+
+    fn allowance(amount: u64, grantee: u64) -> StateValue {
+        StateValue::Triple {
+            first: O_ALLOWANCE.into(),
+            second: amount.into(),
+            third: grantee.into(),
+        }
+    }
+
+    fn delegated(routine: u16, inputs: &[StateValue], outputs: &[StateValue]) -> (bool, u256) {
+        let context = TestContext::with(inputs, outputs);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(routine), &context.as_context(), resolver)
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn transfer_from_within_allowance() {
+        for outputs in [
+            &[balance(300), balance(300)][..],
+            &[balance(500), allowance(100, 7)],
+            &[allowance(600, 7)],
+        ] {
+            assert_eq!(
+                delegated(FN_FUNGIBLE_TRANSFER_FROM, &[allowance(600, 7)], outputs),
+                (true, u256::ZERO)
+            );
+        }
+        // The operator may add its own balances
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_TRANSFER_FROM,
+                &[balance(200), allowance(500, 7), balance(300)],
+                &[balance(1000)]
+            ),
+            (true, u256::ZERO)
+        );
+    }
+
+    #[test]
+    fn transfer_from_over_allowance() {
+        // The allowance holds the approved amount, so moving more is an inflation
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_TRANSFER_FROM,
+                &[allowance(499, 7)],
+                &[balance(500)]
+            ),
+            (false, ERRNO_SUM_MISMATCH)
+        );
+        // The remaining allowance must not exceed the spent one
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_TRANSFER_FROM,
+                &[balance(500), allowance(600, 7)],
+                &[balance(499), allowance(601, 7)]
+            ),
+            (false, ERRNO_ALLOWANCE_EXCEEDED)
+        );
+    }
+
+    #[test]
+    fn transfer_from_invalid_allowance() {
+        assert_eq!(
+            delegated(FN_FUNGIBLE_TRANSFER_FROM, &[balance(500)], &[balance(500)]),
+            (false, ERRNO_NO_ALLOWANCE)
+        );
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_TRANSFER_FROM,
+                &[allowance(300, 7), allowance(300, 7)],
+                &[balance(600)]
+            ),
+            (false, ERRNO_INVALID_ALLOWANCE)
+        );
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_TRANSFER_FROM,
+                &[allowance(600, 7)],
+                &[balance(500), allowance(100, 8)]
+            ),
+            (false, ERRNO_INVALID_ALLOWANCE)
+        );
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_TRANSFER_FROM,
+                &[StateValue::new(O_ALLOWANCE, 600u64)],
+                &[balance(600)]
+            ),
+            (false, ERRNO_INVALID_ALLOWANCE)
+        );
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_TRANSFER_FROM,
+                &[allowance(600, 7)],
+                &[balance(500)]
+            ),
+            (false, ERRNO_SUM_MISMATCH)
+        );
+    }
+
+    #[test]
+    fn approve() {
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_APPROVE,
+                &[balance(500)],
+                &[balance(300), allowance(100, 7), allowance(100, 8)]
+            ),
+            (true, u256::ZERO)
+        );
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_APPROVE,
+                &[balance(500)],
+                &[balance(400), StateValue::new(O_ALLOWANCE, 100u64)]
+            ),
+            (false, ERRNO_INVALID_ALLOWANCE)
+        );
+        // Allowances are counted in the value conservation
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_APPROVE,
+                &[balance(500)],
+                &[balance(500), allowance(100, 7)]
+            ),
+            (false, ERRNO_SUM_MISMATCH)
+        );
+        // Allowances can't be spent by the normal transfer
+        assert_eq!(
+            delegated(
+                FN_FUNGIBLE_TRANSFER,
+                &[balance(500), allowance(100, 7)],
+                &[balance(500)]
+            ),
+            (false, ERRNO_UNEXPECTED_OWNED_TYPE_IN)
+        );
+    }
+
+    fn genesis_min_out(min: Option<u64>, outputs: &[u64]) -> (bool, u256) {
+        let supply = outputs.iter().sum::<u64>();
+        let mut globals = vec![
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 2u8),
+            StateData::new(G_SUPPLY, supply),
+        ];
+        globals.extend(min.map(|min| StateData::new(G_MIN_OUTPUT, min)));
+        let context = fungible_ctx(&[], outputs).with_globals(globals);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_ISSUE_MIN_OUT),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    fn transfer_min_out(min: StateValue, inputs: &[u64], outputs: &[u64]) -> (bool, u256) {
+        let context = fungible_ctx(inputs, outputs).with_global_inputs([min]);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_TRANSFER_MIN_OUT),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn genesis_min_out_above() {
+        assert_eq!(
+            genesis_min_out(Some(100), &[100, 500, 1000]),
+            (true, u256::ZERO)
+        );
+        // A single output below the minimum is treated as the change
+        assert_eq!(genesis_min_out(Some(100), &[500, 1]), (true, u256::ZERO));
+    }
+
+    #[test]
+    fn genesis_min_out_dust() {
+        assert_eq!(
+            genesis_min_out(Some(100), &[500, 99, 1]),
+            (false, ERRNO_DUST_OUTPUT)
+        );
+        assert_eq!(genesis_min_out(None, &[500]), (false, ERRNO_NO_MIN_OUTPUT));
+    }
+
+    #[test]
+    fn transfer_min_out_above() {
+        let min = StateValue::new(G_MIN_OUTPUT, 100u64);
+        assert_eq!(
+            transfer_min_out(min, &[1000], &[100, 900]),
+            (true, u256::ZERO)
+        );
+        assert_eq!(
+            transfer_min_out(min, &[1000], &[950, 50]),
+            (true, u256::ZERO)
+        );
+    }
+
+    #[test]
+    fn transfer_min_out_dust() {
+        let min = StateValue::new(G_MIN_OUTPUT, 100u64);
+        assert_eq!(
+            transfer_min_out(min, &[1000], &[900, 50, 50]),
+            (false, ERRNO_DUST_OUTPUT)
+        );
+        assert_eq!(
+            transfer_min_out(min, &[1000], &[950, 49]),
+            (false, ERRNO_SUM_MISMATCH)
+        );
+        assert_eq!(
+            transfer_min_out(StateValue::new(G_MIN_OUTPUT, 0u64), &[1000], &[1000]),
+            (false, ERRNO_NO_MIN_OUTPUT)
+        );
+        assert_eq!(
+            transfer_min_out(StateValue::new(G_LOT_SIZE, 100u64), &[1000], &[1000]),
+            (false, ERRNO_NO_MIN_OUTPUT)
+        );
+    }
+
+    fn retire_right() -> StateValue {
+        StateValue::Single {
+            first: O_RETIRE_RIGHT.into(),
+        }
+    }
+
+    /// Retires the balances together with the issuer right.
+    fn retire(globals: &[StateData], inputs: &[u64], outputs: &[u64]) -> (bool, u256) {
+        let inputs = inputs
+            .iter()
+            .map(|val| balance(*val))
+            .chain([retire_right()])
+            .collect::<Vec<_>>();
+        let outputs = outputs.iter().map(|val| balance(*val)).collect::<Vec<_>>();
+        retire_values(globals, &inputs, &outputs)
+    }
+
+    fn retire_values(
+        globals: &[StateData],
+        inputs: &[StateValue],
+        outputs: &[StateValue],
+    ) -> (bool, u256) {
+        let context = TestContext::with(inputs, outputs).with_globals(globals.iter().cloned());
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_RETIRE),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn retire_no_outputs() {
+        let marker = [StateData::new(G_RETIRED, 0u8)];
+        assert_eq!(retire(&marker, &[100, 900], &[]), (true, u256::ZERO));
+    }
+
+    #[test]
+    fn retire_leftover_output() {
+        let marker = [StateData::new(G_RETIRED, 0u8)];
+        assert_eq!(
+            retire(&marker, &[100, 900], &[900]),
+            (false, ERRNO_RETIRE_HAS_OUTPUTS)
+        );
+        assert_eq!(
+            retire(&marker, &[100], &[0]),
+            (false, ERRNO_RETIRE_HAS_OUTPUTS)
+        );
+    }
+
+    #[test]
+    fn retire_no_marker() {
+        assert_eq!(retire(&[], &[100], &[]), (false, ERRNO_NO_RETIRED));
+        assert_eq!(
+            retire(&[StateData::new(G_NAME, 0u8)], &[100], &[]),
+            (false, ERRNO_NO_RETIRED)
+        );
+        assert_eq!(
+            retire(
+                &[
+                    StateData::new(G_RETIRED, 0u8),
+                    StateData::new(G_SUPPLY, 0u8)
+                ],
+                &[100],
+                &[]
+            ),
+            (false, ERRNO_UNEXPECTED_GLOBAL)
+        );
+    }
+
+    #[test]
+    fn retire_requires_right() {
+        let marker = [StateData::new(G_RETIRED, 0u8)];
+        assert_eq!(
+            retire_values(&marker, &[retire_right()], &[]),
+            (true, u256::ZERO)
+        );
+        assert_eq!(
+            retire_values(&marker, &[], &[]),
+            (false, ERRNO_NO_RETIRE_RIGHT)
+        );
+        assert_eq!(
+            retire_values(&marker, &[balance(100), balance(900)], &[]),
+            (false, ERRNO_NO_RETIRE_RIGHT)
+        );
+        assert_eq!(
+            retire_values(&marker, &[retire_right(), retire_right()], &[]),
+            (false, ERRNO_INVALID_RETIRE_RIGHT)
+        );
+        assert_eq!(
+            retire_values(&marker, &[StateValue::new(O_RETIRE_RIGHT, 1u64)], &[]),
+            (false, ERRNO_INVALID_RETIRE_RIGHT)
+        );
+    }
+
+    fn genesis_retirable(outputs: &[StateValue]) -> (bool, u256) {
+        let context = TestContext::with(&[], outputs).with_globals([
+            StateData::new(G_TICKER, 1u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 2u8),
+            StateData::new(G_SUPPLY, 1000u64),
+        ]);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_ISSUE_RETIRABLE),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn genesis_retire_right() {
+        assert_eq!(
+            genesis_retirable(&[balance(400), retire_right(), balance(600)]),
+            (true, u256::ZERO)
+        );
+        assert_eq!(
+            genesis_retirable(&[balance(1000)]),
+            (false, ERRNO_NO_RETIRE_RIGHT)
+        );
+        assert_eq!(
+            genesis_retirable(&[balance(1000), retire_right(), retire_right()]),
+            (false, ERRNO_INVALID_RETIRE_RIGHT)
+        );
+        assert_eq!(
+            genesis_retirable(&[balance(1000), StateValue::new(O_RETIRE_RIGHT, 1000u64)]),
+            (false, ERRNO_INVALID_RETIRE_RIGHT)
+        );
+        // The right is not counted in the issued supply
+        assert_eq!(
+            genesis_retirable(&[balance(999), retire_right()]),
+            (false, ERRNO_SUM_ISSUE_MISMATCH)
+        );
+        // The transfers can't spend the right
+        let context = TestContext::with(&[balance(1000), retire_right()], &[balance(1000)]);
+        let (lib, mut vm, resolver) = harness();
+        assert!(vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_TRANSFER),
+                &context.as_context(),
+                resolver
+            )
+            .is_err());
+        assert_eq!(errno(&vm), ERRNO_UNEXPECTED_OWNED_TYPE_IN);
+    }
+
+    fn genesis_lots(lot_size: Option<u64>, outputs: &[u64]) -> (bool, u256) {
+        let supply = outputs.iter().sum::<u64>();
+        let mut globals = vec![
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 2u8),
+            StateData::new(G_SUPPLY, supply),
+        ];
+        globals.extend(lot_size.map(|lot| StateData::new(G_LOT_SIZE, lot)));
+        let context = fungible_ctx(&[], outputs).with_globals(globals);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_ISSUE_LOTS),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    fn transfer_lots(lot_size: StateValue, inputs: &[u64], outputs: &[u64]) -> (bool, u256) {
+        let context = fungible_ctx(inputs, outputs).with_global_inputs([lot_size]);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_TRANSFER_LOTS),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn genesis_lots_aligned() {
+        assert_eq!(genesis_lots(Some(100), &[1000]), (true, u256::ZERO));
+        assert_eq!(genesis_lots(Some(100), &[300, 700]), (true, u256::ZERO));
+        assert_eq!(genesis_lots(Some(1), &[12345]), (true, u256::ZERO));
+    }
+
+    #[test]
+    fn genesis_lots_misaligned() {
+        assert_eq!(
+            genesis_lots(Some(100), &[250, 750]),
+            (false, ERRNO_NON_LOT_OUTPUT)
+        );
+        assert_eq!(
+            genesis_lots(Some(100), &[1000, 1]),
+            (false, ERRNO_NON_LOT_OUTPUT)
+        );
+    }
+
+    #[test]
+    fn genesis_lots_zero_supply() {
+        assert_eq!(genesis_lots(Some(100), &[]), (false, ERRNO_ZERO_SUPPLY));
+        assert_eq!(genesis_lots(Some(100), &[0]), (false, ERRNO_ZERO_SUPPLY));
+    }
+
+    #[test]
+    fn genesis_lots_no_lot_size() {
+        assert_eq!(genesis_lots(None, &[1000]), (false, ERRNO_NO_LOT_SIZE));
+        assert_eq!(genesis_lots(Some(0), &[1000]), (false, ERRNO_NO_LOT_SIZE));
+    }
+
+    #[test]
+    fn transfer_lots_aligned() {
+        let lot = StateValue::new(G_LOT_SIZE, 100u64);
+        assert_eq!(
+            transfer_lots(lot, &[300, 200], &[100, 400]),
+            (true, u256::ZERO)
+        );
+        assert_eq!(transfer_lots(lot, &[500], &[500]), (true, u256::ZERO));
+
+        let lot = StateValue::new(G_LOT_SIZE, 7u64);
+        assert_eq!(transfer_lots(lot, &[7000], &[6993, 7]), (true, u256::ZERO));
+    }
+
+    #[test]
+    fn transfer_lots_misaligned() {
+        let lot = StateValue::new(G_LOT_SIZE, 100u64);
+        assert_eq!(
+            transfer_lots(lot, &[300, 200], &[150, 350]),
+            (false, ERRNO_NON_LOT_OUTPUT)
+        );
+        assert_eq!(
+            transfer_lots(lot, &[500], &[99, 401]),
+            (false, ERRNO_NON_LOT_OUTPUT)
+        );
+
+        let lot = StateValue::new(G_LOT_SIZE, 7u64);
+        assert_eq!(
+            transfer_lots(lot, &[7000], &[6995, 5]),
+            (false, ERRNO_NON_LOT_OUTPUT)
+        );
+    }
+
+    #[test]
+    fn transfer_lots_no_lot_size() {
+        let context = fungible_ctx(&[500], &[500]);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_TRANSFER_LOTS),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        assert!(!res);
+        assert_eq!(errno(&vm), ERRNO_NO_LOT_SIZE);
+
+        for lot in [
+            StateValue::new(G_LOT_SIZE, 0u64),
+            StateValue::new(G_SUPPLY, 100u64),
+        ] {
+            assert_eq!(
+                transfer_lots(lot, &[500], &[500]),
+                (false, ERRNO_NO_LOT_SIZE)
+            );
+        }
+    }
+
+    fn transfer_harness(inp: &[&[u64]], out: &[&[u64]], should_success: bool) {
+        for (input, output) in inp
+            .iter()
+            .flat_map(|input| out.iter().map(move |output| (input, output)))
+        {
+            let (lib, mut vm, resolver) = harness();
+            let context = fungible_ctx(input, output);
+            let res = vm
+                .exec(
+                    lib.routine(FN_FUNGIBLE_TRANSFER),
+                    &context.as_context(),
+                    resolver,
+                )
+                .is_ok();
+            if should_success {
+                assert!(res);
+            } else {
+                assert!(!res);
+            }
+        }
+    }
+
+    /// Linear congruential generator (Knuth's MMIX constants) for reproducible property tests.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0
+        }
+
+        fn below(&mut self, max: u64) -> u64 {
+            (self.next() >> 32) % max
+        }
+
+        fn amount(&mut self) -> u64 {
+            match self.below(4) {
+                0 => u64::MAX - self.below(1000),
+                _ => self.below(1_000_000),
             }
-            if shared.as_lib().lib_id() == id {
-                return Some(shared.into_lib());
+        }
+
+        fn amounts(&mut self) -> Vec<u64> {
+            let len = self.below(5) + 1;
+            (0..len).map(|_| self.amount()).collect()
+        }
+
+        /// Splits the sum into a random number of amounts.
+        fn split(&mut self, sum: u64) -> Vec<u64> {
+            let len = self.below(5) + 1;
+            let mut rest = sum;
+            let mut amounts = Vec::with_capacity(len as usize);
+            for _ in 1..len {
+                let amount = if rest == 0 {
+                    0
+                } else {
+                    self.next() % (rest + 1)
+                };
+                amounts.push(amount);
+                rest -= amount;
             }
-            panic!("Unknown library: {id}");
+            amounts.push(rest);
+            amounts
         }
-        (fungible(), vm, resolver)
     }
 
-    const AMOUNTS_OVERFLOW: &[&[u64]] = &[
-        &[u64::MAX, 1, 1],
-        &[u64::MAX - 1, 2],
-        &[u64::MAX, u64::MAX - 1],
-        &[u64::MAX, u64::MAX - 1, 1],
-        &[u64::MAX, u64::MAX],
-        &[u64::MAX / 2 + 1, u64::MAX / 2 + 1],
-        &[u64::MAX / 2, u64::MAX / 2, 2],
-    ];
-    const AMOUNTS_OK: &[&[u64]] = &[
-        &[],
-        &[0u64],
-        &[1u64; 4],
-        &[10u64; 100],
-        &[u64::MAX - 1, 1],
-        &[u64::MAX],
-        &[u64::MAX / 2 - 1, u64::MAX / 2],
-    ];
-
     #[test]
-    fn sum_inputs_overflow() {
-        for input in AMOUNTS_OVERFLOW {
-            let (lib, mut vm, resolver) = harness();
-            let input = input
-                .into_iter()
-                .map(|val| {
-                    (
-                        Input::strict_dumb(),
-                        StateCell {
-                            data: StateValue::new(O_AMOUNT, *val),
-                            auth: strict_dumb!(),
-                            lock: None,
-                        },
-                    )
-                })
-                .collect::<Vec<_>>();
-            let context = VmContext {
-                witness: none!(),
-                destructible_input: input.as_slice(),
-                immutable_input: &[],
-                destructible_output: &[],
-                immutable_output: &[],
+    fn transfer_conservation_property() {
+        let mut rng = Lcg(0x5EED);
+        for _ in 0..200 {
+            let inputs = rng.amounts();
+            let input_sum = inputs.iter().map(|v| *v as u128).sum::<u128>();
+            let outputs = match (rng.below(3), u64::try_from(input_sum)) {
+                (0, Ok(sum)) => rng.split(sum),
+                (1, Ok(sum)) => {
+                    let mut outputs = rng.split(sum);
+                    let last = outputs.last_mut().unwrap();
+                    *last = if *last == 0 { 1 } else { *last - 1 };
+                    outputs
+                }
+                _ => rng.amounts(),
             };
+            let output_sum = outputs.iter().map(|v| *v as u128).sum::<u128>();
+            let expected = input_sum <= u64::MAX as u128 && input_sum == output_sum;
+
+            let (lib, mut vm, resolver) = harness();
+            let context = fungible_ctx(&inputs, &outputs);
             let res = vm
-                .exec(lib.routine(FN_FUNGIBLE_SUM_INPUTS), &context, resolver)
+                .exec(
+                    lib.routine(FN_FUNGIBLE_TRANSFER),
+                    &context.as_context(),
+                    resolver,
+                )
                 .is_ok();
-            assert!(!res);
+            assert_eq!(res, expected, "inputs {inputs:?}, outputs {outputs:?}");
         }
     }
 
+    fn transfer_max_io(max_io: u64, inputs: usize, outputs: usize) -> (bool, u256) {
+        let lib = fungible_with_max_io(Some(max_io));
+        let site = lib.routine(FN_FUNGIBLE_TRANSFER);
+        let libs = [lib.into_lib(), shared_lib().into_lib()];
+        let resolver = |id: LibId| libs.iter().find(|lib| lib.lib_id() == id).cloned();
+        let mut vm = Vm::<Instr<LibId>>::with(
+            CONFIG,
+            GfaConfig {
+                field_order: FIELD_ORDER_SECP,
+            },
+        );
+        let context = fungible_ctx(&vec![1; inputs], &vec![1; outputs]);
+        let res = vm.exec(site, &context.as_context(), resolver).is_ok();
+        (res, errno(&vm))
+    }
+
     #[test]
-    fn sum_outputs_overflow() {
-        for output in AMOUNTS_OVERFLOW {
-            let (lib, mut vm, resolver) = harness();
-            let output = output
-                .into_iter()
-                .map(|val| StateCell {
-                    data: StateValue::new(O_AMOUNT, *val),
-                    auth: AuthToken::strict_dumb(),
-                    lock: None,
-                })
-                .collect::<Vec<_>>();
-            let context = VmContext {
-                witness: none!(),
-                destructible_input: &[],
-                immutable_input: &[],
-                destructible_output: output.as_slice(),
-                immutable_output: &[],
-            };
-            let res = vm
-                .exec(lib.routine(FN_FUNGIBLE_SUM_OUTPUTS), &context, resolver)
-                .is_ok();
-            assert!(!res);
+    fn transfer_max_io_at_cap() {
+        assert_eq!(transfer_max_io(5, 5, 5), (true, u256::ZERO));
+    }
+
+    #[test]
+    fn transfer_max_io_uncapped() {
+        let capped = fungible_with_max_io(Some(u64::MAX));
+        let uncapped = fungible();
+        assert_ne!(capped.as_lib().lib_id(), uncapped.as_lib().lib_id());
+        // The counter is not emitted at all: three instructions initializing it and four counting
+        // down each element, for the inputs, the classed inputs and the outputs
+        let instructions = crate::scripts::inspect::instructions;
+        assert_eq!(
+            instructions(&capped).len() - instructions(&uncapped).len(),
+            3 * (3 + 4)
+        );
+        assert_eq!(
+            uncapped.as_lib().lib_id(),
+            fungible_with_max_io(None).as_lib().lib_id()
+        );
+    }
+
+    #[test]
+    fn transfer_max_io_over_cap() {
+        assert_eq!(transfer_max_io(5, 6, 6), (false, ERRNO_TOO_MANY_IO));
+        assert_eq!(transfer_max_io(5, 6, 1), (false, ERRNO_TOO_MANY_IO));
+        assert_eq!(transfer_max_io(5, 1, 6), (false, ERRNO_TOO_MANY_IO));
+    }
+
+    fn rename(globals: &[StateData], inputs: &[u64], outputs: &[u64]) -> (bool, u256) {
+        let context = fungible_ctx(inputs, outputs).with_globals(globals.iter().cloned());
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_RENAME),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
+    }
+
+    #[test]
+    fn rename_only() {
+        let globals = [StateData::new(G_TICKER, 1u8), StateData::new(G_NAME, 2u8)];
+        assert_eq!(rename(&globals, &[600, 400], &[1000]), (true, u256::ZERO));
+        assert_eq!(rename(&globals[1..], &[1000], &[1000]), (true, u256::ZERO));
+    }
+
+    #[test]
+    fn rename_changed_supply() {
+        for changed in [
+            StateData::new(G_SUPPLY, 2000u64),
+            StateData::new(G_PRECISION, 2u8),
+        ] {
+            let globals = [StateData::new(G_TICKER, 1u8), changed];
+            assert_eq!(
+                rename(&globals, &[1000], &[1000]),
+                (false, ERRNO_RENAME_CHANGED_SUPPLY)
+            );
         }
+        let globals = [StateData::new(G_REFERENCE, 1u8)];
+        assert_eq!(
+            rename(&globals, &[1000], &[1000]),
+            (false, ERRNO_UNEXPECTED_GLOBAL)
+        );
     }
 
     #[test]
-    fn sum_inputs() {
-        for input in AMOUNTS_OK {
-            let (lib, mut vm, resolver) = harness();
-            let sum = input.iter().sum::<u64>();
-            let input = input
-                .into_iter()
-                .map(|val| {
-                    (
-                        Input::strict_dumb(),
-                        StateCell {
-                            data: StateValue::new(O_AMOUNT, *val),
-                            auth: strict_dumb!(),
-                            lock: None,
-                        },
-                    )
-                })
-                .collect::<Vec<_>>();
-            let context = VmContext {
-                witness: none!(),
-                destructible_input: input.as_slice(),
-                immutable_input: &[],
-                destructible_output: &[],
-                immutable_output: &[],
+    fn rename_empty() {
+        for (renamed, expected) in [(G_TICKER, ERRNO_EMPTY_TICKER), (G_NAME, ERRNO_EMPTY_NAME)] {
+            let empty = StateData {
+                value: StateValue::Single {
+                    first: renamed.into(),
+                },
+                raw: None,
             };
-            let res = vm
-                .exec(lib.routine(FN_FUNGIBLE_SUM_INPUTS), &context, resolver)
-                .is_ok();
-            let gfa: GfaCore = vm.core.cx.subcore();
-            assert_eq!(gfa.get(RegE::E2).unwrap().to_u256(), u256::from(sum));
-            assert!(res);
+            assert_eq!(rename(&[empty], &[1000], &[1000]), (false, expected));
         }
     }
 
     #[test]
-    fn sum_outputs() {
-        let lock = None;
-        let auth = AuthToken::strict_dumb();
-        for output in AMOUNTS_OK {
+    fn rename_changed_balance() {
+        let globals = [StateData::new(G_NAME, 2u8)];
+        assert_eq!(
+            rename(&globals, &[1000], &[1001]),
+            (false, ERRNO_SUM_MISMATCH)
+        );
+    }
+
+    #[test]
+    fn transfer_deflation() {
+        transfer_harness(&[&[1001], &[99, 900]], &[&[1000], &[100, 900]], false);
+    }
+
+    #[test]
+    fn transfer_inflation() {
+        transfer_harness(&[&[999], &[101, 900]], &[&[1000], &[100, 900]], false);
+    }
+
+    #[test]
+    fn transfer_overflow() {
+        transfer_harness(&[&[1]], &[&[u64::MAX - 1, 2]], false);
+    }
+
+    #[test]
+    fn transfer_correct() {
+        transfer_harness(&[&[1000], &[100, 900]], &[&[1000], &[100, 900]], true);
+    }
+
+    #[test]
+    fn blank() {
+        for (inputs, outputs, success) in [
+            (&[1000][..], &[1000][..], true),
+            (&[100, 900], &[500, 500], true),
+            (&[], &[], true),
+            (&[1000], &[1001], false),
+            (&[1000], &[999], false),
+            (&[], &[1], false),
+        ] {
             let (lib, mut vm, resolver) = harness();
-            let sum = output.iter().sum::<u64>();
-            let output = output
-                .into_iter()
-                .map(|val| StateCell {
-                    data: StateValue::new(O_AMOUNT, *val),
-                    auth,
-                    lock,
-                })
-                .collect::<Vec<_>>();
-            let context = VmContext {
-                witness: none!(),
-                destructible_input: &[],
-                immutable_input: &[],
-                destructible_output: output.as_slice(),
-                immutable_output: &[],
-            };
+            let context = fungible_ctx(inputs, outputs);
             let res = vm
-                .exec(lib.routine(FN_FUNGIBLE_SUM_OUTPUTS), &context, resolver)
+                .exec(
+                    lib.routine(FN_FUNGIBLE_BLANK),
+                    &context.as_context(),
+                    resolver,
+                )
                 .is_ok();
-            let gfa: GfaCore = vm.core.cx.subcore();
-            assert_eq!(gfa.get(RegE::E3).unwrap().to_u256(), u256::from(sum));
-            assert!(res);
+            assert_eq!(res, success, "{inputs:?} -> {outputs:?}");
+            if !success {
+                assert_eq!(errno(&vm), ERRNO_SUM_MISMATCH);
+            }
         }
-    }
 
-    #[test]
-    fn genesis_empty() {
-        let context = VmContext {
-            witness: none!(),
-            destructible_input: &[],
-            immutable_input: &[],
-            destructible_output: &[],
-            immutable_output: &[],
-        };
+        let context = fungible_ctx(&[1000], &[1000]).with_globals([StateData::new(G_NAME, 0u8)]);
         let (lib, mut vm, resolver) = harness();
         let res = vm
-            .exec(lib.routine(FN_FUNGIBLE_ISSUE), &context, resolver)
+            .exec(
+                lib.routine(FN_FUNGIBLE_BLANK),
+                &context.as_context(),
+                resolver,
+            )
             .is_ok();
         assert!(!res);
     }
 
-    #[test]
-    fn genesis_missing_globals() {
-        let mut context = VmContext {
-            witness: none!(),
-            destructible_input: &[],
-            immutable_input: &[],
-            destructible_output: &[StateCell {
-                data: StateValue::new(O_AMOUNT, 1000_u64),
+    /// Runs the lock scripts of all inputs and then the transfer verifier, like a validator does.
+    fn spend_sealable(inp: &[(u64, bool)], out: &[u64]) -> (bool, u256) {
+        let (lib, mut vm, resolver) = harness();
+        let seal = lib.routine(FN_FUNGIBLE_LOCK_UNSPENDABLE);
+        let input = inp
+            .iter()
+            .map(|(val, sealed)| {
+                (
+                    Input::strict_dumb(),
+                    StateCell {
+                        data: StateValue::new(O_AMOUNT, *val),
+                        auth: strict_dumb!(),
+                        lock: sealed.then_some(seal),
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        let output = out
+            .iter()
+            .map(|val| StateCell {
+                data: StateValue::new(O_AMOUNT, *val),
                 auth: AuthToken::strict_dumb(),
                 lock: None,
-            }],
+            })
+            .collect::<Vec<_>>();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: input.as_slice(),
+            immutable_input: &[],
+            destructible_output: output.as_slice(),
             immutable_output: &[],
         };
-        let globals = [
-            &[
-                StateData::new(G_TICKER, 0u8),
-                StateData::new(G_PRECISION, 18_u8),
-                StateData::new(G_SUPPLY, 1000_u64),
-            ][..],
-            &[
-                StateData::new(G_NAME, 0u8),
-                StateData::new(G_PRECISION, 18_u8),
-                StateData::new(G_SUPPLY, 1000_u64),
-            ],
-            &[
-                StateData::new(G_NAME, 0u8),
-                StateData::new(G_TICKER, 0u8),
-                StateData::new(G_SUPPLY, 1000_u64),
-            ],
-            &[
-                StateData::new(G_NAME, 0u8),
-                StateData::new(G_TICKER, 0u8),
-                StateData::new(G_PRECISION, 18_u8),
-            ],
-            &[StateData::new(G_NAME, 0u8), StateData::new(G_TICKER, 0u8)],
-        ];
-        for global in globals {
-            context.immutable_output = global;
-            let (lib, mut vm, resolver) = harness();
-            let res = vm
-                .exec(lib.routine(FN_FUNGIBLE_ISSUE), &context, resolver)
-                .is_ok();
-            assert!(!res);
+        for (_, cell) in &input {
+            if let Some(lock) = cell.lock {
+                if vm.exec(lock, &context, &resolver).is_err() {
+                    return (false, errno(&vm));
+                }
+            }
         }
+        let res = vm
+            .exec(lib.routine(FN_FUNGIBLE_TRANSFER), &context, &resolver)
+            .is_ok();
+        (res, errno(&vm))
     }
 
     #[test]
-    fn genesis_missing_owned() {
+    fn transfer_unsealed() {
+        assert!(spend_sealable(&[(1000, false)], &[1000]).0);
+        assert!(spend_sealable(&[(100, false), (900, false)], &[400, 600]).0);
+    }
+
+    #[test]
+    fn transfer_sealed() {
+        let (res, errno) = spend_sealable(&[(1000, true)], &[1000]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_SEALED_INPUT);
+
+        let (res, errno) = spend_sealable(&[(100, false), (900, true)], &[1000]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_SEALED_INPUT);
+    }
+
+    #[test]
+    fn genesis_sealed() {
+        let (lib, mut vm, resolver) = harness();
         let context = VmContext {
             witness: none!(),
             destructible_input: &[],
             immutable_input: &[],
-            destructible_output: &[],
+            destructible_output: &[
+                StateCell {
+                    data: StateValue::new(O_AMOUNT, 600_u64),
+                    auth: AuthToken::strict_dumb(),
+                    lock: Some(lib.routine(FN_FUNGIBLE_LOCK_UNSPENDABLE)),
+                },
+                StateCell {
+                    data: StateValue::new(O_AMOUNT, 400_u64),
+                    auth: AuthToken::strict_dumb(),
+                    lock: None,
+                },
+            ],
             immutable_output: &[
-                StateData::new(G_NAME, 0u8),
                 StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 0u8),
                 StateData::new(G_PRECISION, 18_u8),
                 StateData::new(G_SUPPLY, 1000_u64),
             ],
         };
-        let (lib, mut vm, resolver) = harness();
         let res = vm
             .exec(lib.routine(FN_FUNGIBLE_ISSUE), &context, resolver)
             .is_ok();
-        assert!(!res);
+        assert!(res);
+    }
+
+    fn genesis_capped(cap: u64, outputs: &[u64]) -> (bool, u256) {
+        let supply = outputs.iter().sum::<u64>();
+        let context = fungible_ctx(&[], outputs).with_globals([
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, supply),
+            StateData::new(G_MAX_PER_OUTPUT, cap),
+        ]);
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(
+                lib.routine(FN_FUNGIBLE_ISSUE_CAPPED),
+                &context.as_context(),
+                resolver,
+            )
+            .is_ok();
+        (res, errno(&vm))
     }
 
     #[test]
-    fn genesis_supply_mismatch() {
-        let context = VmContext {
-            witness: none!(),
-            destructible_input: &[],
-            immutable_input: &[],
-            destructible_output: &[StateCell {
-                data: StateValue::new(O_AMOUNT, 1001_u64),
-                auth: AuthToken::strict_dumb(),
-                lock: None,
-            }],
-            immutable_output: &[
-                StateData::new(G_NAME, 0u8),
-                StateData::new(G_TICKER, 0u8),
-                StateData::new(G_PRECISION, 18_u8),
-                StateData::new(G_SUPPLY, 1000_u64),
-            ],
-        };
+    fn genesis_under_cap() {
+        assert!(genesis_capped(500, &[100, 500, 400]).0);
+        assert!(genesis_capped(u64::MAX, &[u64::MAX]).0);
+    }
+
+    #[test]
+    fn genesis_capped_zero_supply() {
+        assert_eq!(genesis_capped(1, &[]), (false, ERRNO_ZERO_SUPPLY));
+        assert_eq!(genesis_capped(1, &[0]), (false, ERRNO_ZERO_SUPPLY));
+    }
+
+    #[test]
+    fn genesis_capped_details() {
+        let context = fungible_ctx(&[], &[100]).with_globals([
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 18_u8),
+            StateData::new(G_SUPPLY, 100u64),
+            StateData::new(G_MAX_PER_OUTPUT, 100u64),
+            StateData::new(G_NAME, 0u8),
+        ]);
         let (lib, mut vm, resolver) = harness();
         let res = vm
-            .exec(lib.routine(FN_FUNGIBLE_ISSUE), &context, resolver)
+            .exec(
+                lib.routine(FN_FUNGIBLE_ISSUE_CAPPED),
+                &context.as_context(),
+                resolver,
+            )
             .is_ok();
+        assert_eq!((res, errno(&vm)), (false, ERRNO_INVALID_DETAILS));
+    }
+
+    #[test]
+    fn genesis_over_cap() {
+        let (res, errno) = genesis_capped(499, &[100, 500, 400]);
         assert!(!res);
+        assert_eq!(errno, ERRNO_OUTPUT_OVER_CAP);
+
+        let (res, errno) = genesis_capped(0, &[1]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_OUTPUT_OVER_CAP);
     }
 
     #[test]
-    fn genesis_correct() {
+    fn genesis_no_cap() {
         let context = VmContext {
             witness: none!(),
             destructible_input: &[],
@@ -519,79 +3759,9 @@ mod tests {
         };
         let (lib, mut vm, resolver) = harness();
         let res = vm
-            .exec(lib.routine(FN_FUNGIBLE_ISSUE), &context, resolver)
+            .exec(lib.routine(FN_FUNGIBLE_ISSUE_CAPPED), &context, resolver)
             .is_ok();
-        assert!(res);
-    }
-
-    fn transfer_harness(inp: &[&[u64]], out: &[&[u64]], should_success: bool) {
-        let inputs = inp.into_iter().map(|vals| {
-            vals.into_iter()
-                .map(|val| {
-                    (
-                        Input::strict_dumb(),
-                        StateCell {
-                            data: StateValue::new(O_AMOUNT, *val),
-                            auth: strict_dumb!(),
-                            lock: None,
-                        },
-                    )
-                })
-                .collect::<Vec<_>>()
-        });
-        let lock = None;
-        let auth = AuthToken::strict_dumb();
-        let outputs = out.into_iter().map(|vals| {
-            vals.into_iter()
-                .map(|val| StateCell {
-                    data: StateValue::new(O_AMOUNT, *val),
-                    auth,
-                    lock,
-                })
-                .collect::<Vec<_>>()
-        });
-        for (input, output) in inputs.flat_map(|inp| {
-            outputs
-                .clone()
-                .into_iter()
-                .map(move |out| (inp.clone(), out))
-        }) {
-            let (lib, mut vm, resolver) = harness();
-            let context = VmContext {
-                witness: none!(),
-                destructible_input: input.as_slice(),
-                immutable_input: &[],
-                destructible_output: output.as_slice(),
-                immutable_output: &[],
-            };
-            let res = vm
-                .exec(lib.routine(FN_FUNGIBLE_TRANSFER), &context, resolver)
-                .is_ok();
-            if should_success {
-                assert!(res);
-            } else {
-                assert!(!res);
-            }
-        }
-    }
-
-    #[test]
-    fn transfer_deflation() {
-        transfer_harness(&[&[1001], &[99, 900]], &[&[1000], &[100, 900]], false);
-    }
-
-    #[test]
-    fn transfer_inflation() {
-        transfer_harness(&[&[999], &[101, 900]], &[&[1000], &[100, 900]], false);
-    }
-
-    #[test]
-    fn transfer_overflow() {
-        transfer_harness(&[&[1]], &[&[u64::MAX - 1, 2]], false);
-    }
-
-    #[test]
-    fn transfer_correct() {
-        transfer_harness(&[&[1000], &[100, 900]], &[&[1000], &[100, 900]], true);
+        assert!(!res);
+        assert_eq!(errno(&vm), ERRNO_NO_OUTPUT_CAP);
     }
 }
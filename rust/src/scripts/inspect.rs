@@ -0,0 +1,335 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use amplify::num::u256;
+use hypersonic::Instr;
+use zkaluvm::alu::isa::{Bytecode, CtrlInstr};
+use zkaluvm::alu::{CompiledLib, Lib, LibId, LibSite};
+use zkaluvm::{FieldInstr, RegE};
+
+/// Difference between two compiled libraries at a specific instruction position.
+#[derive(Clone, PartialEq, Debug)]
+pub struct InstrDiff {
+    /// Byte offset of the instruction in the code of the first library (matching the
+    /// `routine(..).offset` of the library entry points), or in the code of the second library if
+    /// the first one is shorter.
+    ///
+    /// The instructions are compared in their order, thus the offsets in both libraries are the
+    /// same up to the first difference in the instruction length.
+    pub offset: u16,
+    /// Instruction at the position in the first library, if the library code is long enough.
+    pub left: Option<Instr<LibId>>,
+    /// Instruction at the position in the second library, if the library code is long enough.
+    pub right: Option<Instr<LibId>>,
+}
+
+pub(super) fn instructions(lib: &CompiledLib) -> Vec<Instr<LibId>> {
+    lib.as_lib()
+        .disassemble::<Instr<LibId>>()
+        .unwrap_or_else(|err| panic!("Invalid compiled library: {err}"))
+}
+
+/// Compares two compiled libraries instruction-by-instruction.
+///
+/// Returns the list of instruction offsets at which the libraries differ; an empty list means that
+/// both libraries contain the same code. Useful to confirm that a refactoring of a script didn't
+/// change the resulting opcodes.
+pub fn diff_libs(a: &CompiledLib, b: &CompiledLib) -> Vec<InstrDiff> {
+    let left = instructions(a);
+    let right = instructions(b);
+    let len = left.len().max(right.len());
+    let mut diff = vec![];
+    let (mut left_offset, mut right_offset) = (0u16, 0u16);
+    for pos in 0..len {
+        let l = left.get(pos);
+        let r = right.get(pos);
+        if l != r {
+            diff.push(InstrDiff {
+                offset: l.map_or(right_offset, |_| left_offset),
+                left: l.cloned(),
+                right: r.cloned(),
+            });
+        }
+        left_offset += l.map_or(0, |instr| instr.code_byte_len());
+        right_offset += r.map_or(0, |instr| instr.code_byte_len());
+    }
+    diff
+}
+
+/// Produces human-readable listing of the library code.
+///
+/// Each instruction is prefixed with its byte offset, and each routine (procedure or label) entry
+/// point is marked with a `routine <no>:` line, allowing to check that the `uasm!` labels and jumps
+/// resolved to the intended positions.
+pub fn disassemble(lib: &CompiledLib) -> String {
+    let mut entries = BTreeMap::<u16, Vec<usize>>::new();
+    for no in 0..lib.routines_count() {
+        let site = lib.routine(no as u16);
+        entries.entry(site.offset).or_default().push(no);
+    }
+
+    let mut listing = String::new();
+    let mut offset = 0u16;
+    for instr in instructions(lib) {
+        for no in entries.get(&offset).into_iter().flatten() {
+            writeln!(listing, "routine {no}:").expect("writing to string");
+        }
+        writeln!(listing, "    {offset:04x}  {instr}").expect("writing to string");
+        offset += instr.code_byte_len();
+    }
+    listing
+}
+
+/// Returns the instruction at the entry point of a routine, or `None` if the routine entry doesn't
+/// match an instruction boundary.
+pub fn entry_instr(lib: &CompiledLib, routine: u16) -> Option<Instr<LibId>> {
+    let entry = lib.routine(routine).offset;
+    let mut offset = 0u16;
+    for instr in instructions(lib) {
+        if offset == entry {
+            return Some(instr);
+        }
+        offset += instr.code_byte_len();
+    }
+    None
+}
+
+/// Collects the error codes which may be reported by a routine: the constants of the
+/// `put E1, <errno>` instructions reachable from the routine entry point.
+///
+/// The code is traced statically, following both branches of the conditional jumps, as well as the
+/// calls into the local routines and into the routines of the other libraries from `libs`. Thus,
+/// the result may include the error codes of the branches which can't be taken in practice; calls
+/// into the libraries absent from `libs` are not followed.
+pub fn reachable_errnos<'lib>(
+    entry: LibSite,
+    libs: impl IntoIterator<Item = &'lib Lib>,
+) -> BTreeSet<u256> {
+    let mut code = BTreeMap::<LibId, BTreeMap<u16, Instr<LibId>>>::new();
+    for lib in libs {
+        let instrs = lib
+            .disassemble::<Instr<LibId>>()
+            .unwrap_or_else(|err| panic!("Invalid library: {err}"));
+        let lib_code = code.entry(lib.lib_id()).or_default();
+        let mut offset = 0u16;
+        for instr in instrs {
+            let len = instr.code_byte_len();
+            lib_code.insert(offset, instr);
+            offset += len;
+        }
+    }
+
+    let mut errnos = BTreeSet::new();
+    let mut visited = BTreeSet::new();
+    let mut queue = vec![(entry.prog_id, entry.offset)];
+    while let Some((lib_id, offset)) = queue.pop() {
+        if !visited.insert((lib_id, offset)) {
+            continue;
+        }
+        let Some(instr) = code.get(&lib_id).and_then(|lib_code| lib_code.get(&offset)) else {
+            continue;
+        };
+        let next = (lib_id, offset + instr.code_byte_len());
+        let local = |pos: u16| (lib_id, pos);
+        let shifted = |shift: i8| (lib_id, offset.wrapping_add_signed(shift as i16));
+        match instr {
+            Instr::Gfa(FieldInstr::PutD {
+                dst: RegE::E1,
+                data,
+            }) => {
+                let errno = data.to_u256();
+                if errno != u256::ZERO {
+                    errnos.insert(errno);
+                }
+                queue.push(next);
+            }
+            Instr::Ctrl(CtrlInstr::Ret | CtrlInstr::Stop) => {}
+            Instr::Ctrl(CtrlInstr::Jmp { pos }) => queue.push(local(*pos)),
+            Instr::Ctrl(CtrlInstr::Sh { shift }) => queue.push(shifted(*shift)),
+            Instr::Ctrl(CtrlInstr::Exec { site }) => queue.push((site.prog_id, site.offset)),
+            Instr::Ctrl(
+                CtrlInstr::JiNe { pos } | CtrlInstr::JiFail { pos } | CtrlInstr::Fn { pos },
+            ) => queue.extend([local(*pos), next]),
+            Instr::Ctrl(CtrlInstr::ShNe { shift } | CtrlInstr::ShFail { shift }) => {
+                queue.extend([shifted(*shift), next])
+            }
+            Instr::Ctrl(CtrlInstr::Call { site }) => {
+                queue.extend([(site.prog_id, site.offset), next])
+            }
+            _ => queue.push(next),
+        }
+    }
+    errnos
+}
+
+/// Library calls another library, which is absent from the list of the available libraries.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, Error)]
+#[display("library {lib} depends on library {missing}, which is not available")]
+pub struct MissingLib {
+    /// Id of the library containing the call.
+    pub lib: LibId,
+    /// Id of the library which is referenced, but not available.
+    pub missing: LibId,
+}
+
+/// Checks that all the libraries referenced by the `lib` code are present in `available`.
+///
+/// Otherwise, a missing dependency would only be detected by the VM during the execution of the
+/// call, failing with an unknown library.
+pub fn validate_dependencies(
+    lib: &CompiledLib,
+    available: &[&CompiledLib],
+) -> Result<(), MissingLib> {
+    let lib = lib.as_lib();
+    let available = available
+        .iter()
+        .map(|dep| dep.as_lib().lib_id())
+        .collect::<BTreeSet<_>>();
+    match lib.libs.iter().find(|id| !available.contains(id)) {
+        Some(missing) => Err(MissingLib {
+            lib: lib.lib_id(),
+            missing: *missing,
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::uasm;
+
+    use super::*;
+    use crate::{
+        collection, divisible, fungible, nft_lib, shared_lib, ERRNO_NO_RETIRE_RIGHT,
+        ERRNO_SUM_MISMATCH, ERRNO_SUM_OVERFLOW, FN_ASSET_DETAILS, FN_ASSET_SPEC,
+        FN_FUNGIBLE_TRANSFER, FN_GLOBAL_ABSENT,
+    };
+
+    fn test_lib(errno: u256) -> CompiledLib {
+        const FN_TEST: u16 = 0;
+
+        let mut code = uasm! {
+         routine FN_TEST:
+            put     E1, errno;
+            clr     E1;
+            ret;
+        };
+        CompiledLib::compile(&mut code, &[]).unwrap_or_else(|err| panic!("Invalid script: {err}"))
+    }
+
+    #[test]
+    fn same_lib() {
+        assert!(diff_libs(&fungible(), &fungible()).is_empty());
+        assert!(diff_libs(&shared_lib(), &shared_lib()).is_empty());
+    }
+
+    #[test]
+    fn modified_lib() {
+        let a = test_lib(u256::ZERO);
+        let b = test_lib(u256::ONE);
+        let diff = diff_libs(&a, &b);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].offset, a.routine(0).offset);
+        assert!(diff[0].left.is_some());
+        assert!(diff[0].right.is_some());
+    }
+
+    #[test]
+    fn diff_offsets() {
+        const FN_TEST: u16 = 0;
+        const FN_NEXT: u16 = 1;
+
+        let compile = |errno: u256| {
+            let mut code = uasm! {
+             routine FN_TEST:
+                clr     E1;
+                ret;
+             routine FN_NEXT:
+                put     E1, errno;
+                ret;
+            };
+            CompiledLib::compile(&mut code, &[])
+                .unwrap_or_else(|err| panic!("Invalid script: {err}"))
+        };
+        let (a, b) = (compile(u256::ZERO), compile(u256::ONE));
+        let diff = diff_libs(&a, &b);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].offset, a.routine(FN_NEXT).offset);
+        assert_ne!(diff[0].offset, 0);
+    }
+
+    #[test]
+    fn different_libs() {
+        let diff = diff_libs(&shared_lib(), &fungible());
+        assert!(!diff.is_empty());
+        assert!(diff.iter().any(|d| d.left.is_none() || d.right.is_none()));
+    }
+
+    #[test]
+    fn disassemble_shared() {
+        let listing = disassemble(&shared_lib());
+        assert!(listing.starts_with("routine 0:\n    0000  "));
+        for no in [FN_ASSET_SPEC, FN_GLOBAL_ABSENT, FN_ASSET_DETAILS] {
+            assert!(listing.contains(&format!("routine {no}:\n")), "{listing}");
+        }
+        let lines = listing.lines().filter(|line| !line.starts_with("routine"));
+        assert_eq!(lines.count(), instructions(&shared_lib()).len());
+    }
+
+    #[test]
+    fn errnos_of_routine() {
+        let errno = u256::from(0x10u8);
+        let lib = test_lib(errno);
+        assert_eq!(
+            reachable_errnos(lib.routine(0), [lib.as_lib()]),
+            bset![errno]
+        );
+
+        let (shared, fungible) = (shared_lib(), fungible());
+        let site = fungible.routine(FN_FUNGIBLE_TRANSFER);
+        let local = reachable_errnos(site, [fungible.as_lib()]);
+        let all = reachable_errnos(site, [fungible.as_lib(), shared.as_lib()]);
+        assert!(local.contains(&ERRNO_SUM_MISMATCH));
+        assert!(!local.contains(&ERRNO_SUM_OVERFLOW));
+        assert!(all.contains(&ERRNO_SUM_OVERFLOW));
+        assert!(all.is_superset(&local));
+        assert!(!all.contains(&ERRNO_NO_RETIRE_RIGHT));
+    }
+
+    #[test]
+    fn missing_dependency() {
+        let (shared, nft, frac) = (shared_lib(), nft_lib(), divisible());
+        let lib = collection();
+        assert_eq!(validate_dependencies(&lib, &[&shared, &nft, &frac]), Ok(()));
+        assert_eq!(
+            validate_dependencies(&lib, &[&shared, &nft]),
+            Err(MissingLib {
+                lib: lib.as_lib().lib_id(),
+                missing: frac.as_lib().lib_id(),
+            })
+        );
+        assert_eq!(validate_dependencies(&shared, &[]), Ok(()));
+    }
+}
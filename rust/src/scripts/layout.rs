@@ -0,0 +1,71 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use amplify::num::u256;
+
+use crate::{G_NAME, G_NFT, G_PRECISION, G_REFERENCE, G_RGB20_DETAILS, G_TICKER, O_AMOUNT};
+
+/// Field elements used by the scripts to identify the types of the global and owned state.
+///
+/// Allows generating the same scripts for interfaces which use a different state layout. The
+/// default layout matches the `G_*` and `O_*` constants of the crate.
+///
+/// The layout is taken by the shared, the NFT and the unique token libraries (see
+/// [`shared_lib_with`](super::shared_lib_with), [`nft_lib_with`](super::nft_lib_with) and
+/// [`unique_with`](super::unique_with)); the other libraries are compiled for the default layout
+/// only.
+///
+/// The layout has no issued supply field: none of these libraries reads the supply. The supply
+/// ([`G_SUPPLY`](crate::G_SUPPLY)) is checked only by the fungible library, which hardcodes the
+/// default layout, while in RGB21 the same field element is the token specification, relocated
+/// with [`Self::nft`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FieldLayout {
+    /// Global state type of the asset name.
+    pub name: u256,
+    /// Global state type of the asset ticker (or details, in RGB21 and RGB25).
+    pub ticker: u256,
+    /// Global state type of the asset precision (or the number of token fractions, in RGB21).
+    pub precision: u256,
+    /// Global state type of the NFT token specification.
+    pub nft: u256,
+    /// Global state type of the optional RGB20 details.
+    pub details: u256,
+    /// Global state type of the optional issuer reference.
+    pub reference: u256,
+    /// Owned state type of the asset allocations.
+    pub amount: u256,
+}
+
+impl Default for FieldLayout {
+    fn default() -> Self {
+        Self {
+            name: G_NAME,
+            ticker: G_TICKER,
+            precision: G_PRECISION,
+            nft: G_NFT,
+            details: G_RGB20_DETAILS,
+            reference: G_REFERENCE,
+            amount: O_AMOUNT,
+        }
+    }
+}
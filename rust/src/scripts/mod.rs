@@ -21,28 +21,88 @@
 // the License.
 
 mod collection;
+mod complexity;
 mod divisible;
 mod fungible;
+mod inspect;
+mod layout;
+mod multi;
+mod names;
+mod nft;
+pub mod routines;
 mod shared;
+#[cfg(feature = "trace")]
+mod trace;
 mod unique;
+pub mod v0;
+mod version;
 
-pub use collection::{collection, FN_FAC_TRANSFER};
-pub use divisible::{divisible, FN_DIVISIBLE_TRANSFER, FN_NFT_SUM_INPUTS, FN_NFT_SUM_OUTPUTS};
+pub use collection::{
+    collection, ERRNO_ALLOCATION_MISMATCH, ERRNO_COLLECTION_SIZE_MISMATCH, ERRNO_EMPTY_COLLECTION,
+    ERRNO_NO_COLLECTION_SIZE, ERRNO_TOKENS_UNSORTED, FN_FAC_TRANSFER,
+};
+pub use complexity::{
+    consumed_complexity, BUDGET_COLLECTION_ISSUE, BUDGET_DIVISIBLE_BATCH_ISSUE,
+    BUDGET_DIVISIBLE_ISSUE, BUDGET_FUNGIBLE_ISSUE, BUDGET_FUNGIBLE_TRANSFER, BUDGET_UNIQUE_ISSUE,
+    BUDGET_UNIQUE_TRANSFER,
+};
+pub use divisible::{
+    divisible, ERRNO_FRACTIONS_MISMATCH, ERRNO_OUTPUTS_NOT_GROUPED, ERRNO_SPLIT_FORBIDDEN,
+    ERRNO_UNDECLARED_TOKEN, ERRNO_UNKNOWN_OUTPUT_TOKEN, FN_DIVISIBLE_BATCH_ISSUE,
+    FN_DIVISIBLE_TRANSFER, FN_NFT_MERGE_ONLY_TRANSFER, FN_NFT_SUM_INPUTS, FN_NFT_SUM_OUTPUTS,
+};
 pub use fungible::{
-    fungible, ERRNO_INVALID_BALANCE_IN, ERRNO_INVALID_BALANCE_OUT, ERRNO_NO_ISSUED,
-    ERRNO_PRECISION_OVERFLOW, ERRNO_SUM_ISSUE_MISMATCH, ERRNO_SUM_MISMATCH,
-    ERRNO_UNEXPECTED_GLOBAL, ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT,
-    FN_FUNGIBLE_ISSUE, FN_FUNGIBLE_SUM_INPUTS, FN_FUNGIBLE_SUM_OUTPUTS, FN_FUNGIBLE_TRANSFER,
+    expected_supply, fungible, fungible_with_max_io, ERRNO_ALLOWANCE_EXCEEDED, ERRNO_DUST_OUTPUT,
+    ERRNO_EMPTY_TICKER, ERRNO_INVALID_ALLOWANCE, ERRNO_INVALID_BALANCE_IN,
+    ERRNO_INVALID_BALANCE_OUT, ERRNO_INVALID_RETIRE_RIGHT, ERRNO_MULTIPLE_SUPPLY,
+    ERRNO_NON_LOT_OUTPUT, ERRNO_NO_ALLOWANCE, ERRNO_NO_CHANGE_OUTPUT, ERRNO_NO_DETAILS,
+    ERRNO_NO_ISSUED, ERRNO_NO_LOT_SIZE, ERRNO_NO_MIN_OUTPUT, ERRNO_NO_OUTPUT_CAP,
+    ERRNO_NO_RECIPIENT_CLASSES, ERRNO_NO_RETIRED, ERRNO_NO_RETIRE_RIGHT, ERRNO_OUTPUT_OVER_CAP,
+    ERRNO_PRECISION_OVERFLOW, ERRNO_RENAME_CHANGED_SUPPLY, ERRNO_RETIRE_HAS_OUTPUTS,
+    ERRNO_SEALED_INPUT, ERRNO_SUM_ISSUE_MISMATCH, ERRNO_SUM_MISMATCH, ERRNO_SUPPLY_MISPLACED,
+    ERRNO_SUPPLY_PRECISION_OVERFLOW, ERRNO_TOO_MANY_IO, ERRNO_UNEXPECTED_GLOBAL,
+    ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT,
+    ERRNO_UNLISTED_RECIPIENT_CLASS, ERRNO_ZERO_SUPPLY, FN_FUNGIBLE_ALLOWANCE_VALUE,
+    FN_FUNGIBLE_APPROVE, FN_FUNGIBLE_BLANK, FN_FUNGIBLE_ISSUE, FN_FUNGIBLE_ISSUE_ALLOCATION,
+    FN_FUNGIBLE_ISSUE_CAPPED, FN_FUNGIBLE_ISSUE_CLASSED, FN_FUNGIBLE_ISSUE_LOTS,
+    FN_FUNGIBLE_ISSUE_MIN_OUT, FN_FUNGIBLE_ISSUE_RETIRABLE, FN_FUNGIBLE_ISSUE_RGB25,
+    FN_FUNGIBLE_ISSUE_SCALED, FN_FUNGIBLE_ISSUE_SPEC, FN_FUNGIBLE_LOCK_UNSPENDABLE,
+    FN_FUNGIBLE_LOTS_OUTPUTS, FN_FUNGIBLE_LOT_SIZE, FN_FUNGIBLE_MIN_OUTPUT,
+    FN_FUNGIBLE_MIN_OUTPUTS, FN_FUNGIBLE_RENAME, FN_FUNGIBLE_RETIRE, FN_FUNGIBLE_RETIRE_RIGHT,
+    FN_FUNGIBLE_SUM_INPUTS, FN_FUNGIBLE_SUM_INPUTS_CLASSED, FN_FUNGIBLE_SUM_OUTPUTS,
+    FN_FUNGIBLE_SUM_OUTPUTS_CAPPED, FN_FUNGIBLE_SUM_OUTPUTS_CLASSED, FN_FUNGIBLE_SUPPLY_PLACEMENT,
+    FN_FUNGIBLE_SUPPLY_SCALE, FN_FUNGIBLE_TICKER, FN_FUNGIBLE_TRANSFER,
+    FN_FUNGIBLE_TRANSFER_CLASSED, FN_FUNGIBLE_TRANSFER_FROM, FN_FUNGIBLE_TRANSFER_LOTS,
+    FN_FUNGIBLE_TRANSFER_MIN_OUT, FN_FUNGIBLE_TRANSFER_WITH_CHANGE,
+};
+pub use inspect::{
+    diff_libs, disassemble, entry_instr, reachable_errnos, validate_dependencies, InstrDiff,
+    MissingLib,
+};
+pub use layout::FieldLayout;
+pub use multi::{
+    multi_fungible, FN_MULTI_ISSUE, FN_MULTI_TRANSFER, FN_SUM_INPUTS_TYPED, FN_SUM_OUTPUTS_TYPED,
+};
+pub use names::{field_name, owned_field_name, ContextDisplay};
+pub use nft::{
+    nft_lib, nft_lib_with, FN_GLOBAL_VERIFY_ATTACHED_TOKEN, FN_GLOBAL_VERIFY_TOKEN, FN_OWNED_TOKEN,
 };
 pub use shared::{
-    shared_lib, ERRNO_INVALID_PRECISION, ERRNO_NO_NAME, ERRNO_NO_PRECISION, ERRNO_NO_TICKER,
-    ERRNO_UNEXPECTED_GLOBAL_IN, ERRNO_UNEXPECTED_GLOBAL_OUT, ERRNO_UNEXPECTED_OWNED_IN,
-    FN_ASSET_SPEC, FN_GLOBAL_ABSENT,
+    shared_lib, shared_lib_with, ERRNO_EMPTY_NAME, ERRNO_EMPTY_STATE, ERRNO_INVALID_DETAILS,
+    ERRNO_INVALID_PRECISION, ERRNO_INVALID_REFERENCE, ERRNO_MULTIPLE_PRECISION, ERRNO_NO_NAME,
+    ERRNO_NO_PRECISION, ERRNO_NO_TICKER, ERRNO_SUM_OVERFLOW, ERRNO_UNEXPECTED_GLOBAL_IN,
+    ERRNO_UNEXPECTED_GLOBAL_OUT, ERRNO_UNEXPECTED_OWNED_IN, FN_ADD_CHECKED, FN_ASSERT_PAIR_VALUE,
+    FN_ASSERT_SIMPLE_VALUE, FN_ASSET_DETAILS, FN_ASSET_SPEC, FN_GLOBAL_ABSENT,
 };
+#[cfg(feature = "trace")]
+pub use trace::{exec_traced, TraceReport};
 pub use unique::{
-    unique, ERRNO_FRACTIONALITY, ERRNO_INVALID_TOKEN_ID, ERRNO_NO_INPUT, ERRNO_NO_OUTPUT,
-    ERRNO_NO_TOKEN_ID, ERRNO_TOKEN_EXCESS, ERRNO_TOKEN_EXCESS_IN, ERRNO_TOKEN_EXCESS_OUT,
-    FN_GLOBAL_VERIFY_TOKEN, FN_OWNED_TOKEN, FN_UNIQUE_TRANSFER,
+    nft_allocation, unique, unique_with, ERRNO_FRACTIONALITY, ERRNO_GLOBAL_FRACTIONS_NOT_ONE,
+    ERRNO_INVALID_TOKEN_ID, ERRNO_MISSING_ATTACHMENT, ERRNO_NO_INPUT, ERRNO_NO_OUTPUT,
+    ERRNO_NO_TOKEN_ID, ERRNO_OWNED_FRACTIONS_NOT_ONE, ERRNO_TOKEN_EXCESS, ERRNO_TOKEN_EXCESS_IN,
+    ERRNO_TOKEN_EXCESS_OUT, ERRNO_ZERO_FRACTION, FN_UNIQUE_ISSUE_ATTACHED, FN_UNIQUE_TRANSFER,
+    FN_VERIFY_ATTACHMENTS,
 };
+pub use version::{fungible_v, shared_lib_v, unique_v, ScriptVersion};
 
 pub const FN_RGB21_ISSUE: u16 = 0; // In all libs it must be the first method
@@ -0,0 +1,435 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use amplify::num::u256;
+use hypersonic::uasm;
+use zkaluvm::alu::CompiledLib;
+
+use super::{
+    shared_lib, validate_dependencies, ERRNO_EMPTY_STATE, FN_ADD_CHECKED, FN_ASSERT_SIMPLE_VALUE,
+    FN_ASSET_SPEC, FN_GLOBAL_ABSENT,
+};
+use crate::{
+    ERRNO_INVALID_BALANCE_IN, ERRNO_INVALID_BALANCE_OUT, ERRNO_NO_ISSUED, ERRNO_PRECISION_OVERFLOW,
+    ERRNO_SUM_ISSUE_MISMATCH, ERRNO_SUM_MISMATCH, ERRNO_UNEXPECTED_GLOBAL,
+    ERRNO_UNEXPECTED_GLOBAL_IN, ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT,
+    G_SUPPLY, G_SUPPLY_B, O_AMOUNT_A, O_AMOUNT_B,
+};
+
+pub const FN_MULTI_ISSUE: u16 = 0;
+pub const FN_MULTI_TRANSFER: u16 = 1;
+
+/// Sum input owned state of a specific type.
+///
+/// Inputs of other types are skipped, such that each type can be conserved independently.
+///
+/// # Input
+///
+/// `EE` contains the field element of the owned state type to sum.
+///
+/// # Output
+///
+/// `E2` contains the sum of inputs.
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `E8`.
+///
+/// # Side effects
+///
+/// Extinguishes the input destructible state iterator
+pub const FN_SUM_INPUTS_TYPED: u16 = 2;
+
+/// Sum output owned state of a specific type.
+///
+/// Outputs of other types are skipped, such that each type can be conserved independently.
+///
+/// # Input
+///
+/// `EE` contains the field element of the owned state type to sum.
+///
+/// # Output
+///
+/// `E3` contains the sum of outputs.
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `E8`.
+///
+/// # Side effects
+///
+/// Extinguishes the output destructible state iterator
+pub const FN_SUM_OUTPUTS_TYPED: u16 = 4;
+
+pub fn multi_fungible() -> CompiledLib {
+    const LOOP_INPUTS: u16 = 3;
+    const LOOP_OUTPUTS: u16 = 5;
+    const OWNED_TYPES: u16 = 6;
+    const NEXT_IN_TYPE: u16 = 7;
+    const OUT_TYPES: u16 = 8;
+    const NEXT_OUT_TYPE: u16 = 9;
+    const ISSUED_TYPED: u16 = 10;
+    const CONSERVE_TYPED: u16 = 11;
+
+    let shared = shared_lib().into_lib().lib_id();
+
+    let mut code = uasm! {
+     routine FN_MULTI_ISSUE:
+        // Genesis must not use global state; we do not rely on `FN_ASSET_SPEC` checking this
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        rsti    immutable;
+        cknxi   immutable;
+        not     CO;
+        chk     CO;
+
+        call    shared, FN_ASSET_SPEC;// Call asset check, returning the precision in `E4`
+
+        put     E1, ERRNO_PRECISION_OVERFLOW; // Set error code for the case of failure
+        fits    E4, 8.bits;     // The precision must fit into a byte
+        chk     CO;             // - or fail otherwise
+
+        call    OWNED_TYPES;    // Ensure only known owned types are used
+
+        // Validate circulating supply of each of the assets
+        put     EE, O_AMOUNT_A; // First asset: owned type
+        put     E5, G_SUPPLY;   // - and its supply global type
+        call    ISSUED_TYPED;
+        put     EE, O_AMOUNT_B; // Second asset: owned type
+        put     E5, G_SUPPLY_B; // - and its supply global type
+        call    ISSUED_TYPED;
+
+        // Check there is no more global state
+        put     E1, ERRNO_UNEXPECTED_GLOBAL; // Set error code for the case of failure
+        ldo     immutable;
+        not     CO;
+        chk     CO;
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     routine FN_MULTI_TRANSFER:
+        // Verify that no global state is defined
+        call    shared, FN_GLOBAL_ABSENT;
+
+        call    OWNED_TYPES;    // Ensure only known owned types are used
+
+        // Conserve each of the assets independently
+        put     EE, O_AMOUNT_A;
+        call    CONSERVE_TYPED;
+        put     EE, O_AMOUNT_B;
+        call    CONSERVE_TYPED;
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     proc FN_SUM_INPUTS_TYPED:
+        put     E2, 0;          // Set initial sum to zero
+        rsti    destructible;   // Start iteration over inputs
+
+     label LOOP_INPUTS:
+        ldi     destructible;   // load next state value
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+
+        eq      EA, EE;         // is it the state type we sum?
+        not     CO;
+        jif     CO, LOOP_INPUTS;// - skip other types otherwise
+
+        put     E1, ERRNO_INVALID_BALANCE_IN; // Set error code for the case of failure
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+        mov     E8, E2;         // add input to input accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E2, E8;
+
+        jmp     LOOP_INPUTS;    // loop
+
+     proc FN_SUM_OUTPUTS_TYPED:
+        put     E3, 0;          // Set initial sum to zero
+        rsto    destructible;   // Start iteration over outputs
+
+     label LOOP_OUTPUTS:
+        ldo     destructible;   // load next state value
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+
+        eq      EA, EE;         // is it the state type we sum?
+        not     CO;
+        jif     CO, LOOP_OUTPUTS;// - skip other types otherwise
+
+        put     E1, ERRNO_INVALID_BALANCE_OUT; // Set error code for the case of failure
+        call    shared, FN_ASSERT_SIMPLE_VALUE; // ensure there are no extra field elements
+
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+        mov     E8, E3;         // add output to output accumulator
+        call    shared, FN_ADD_CHECKED; // - failing on overflow
+        mov     E3, E8;
+
+        jmp     LOOP_OUTPUTS;   // loop
+
+     // Check that all inputs and outputs have one of the known owned state types
+     proc OWNED_TYPES:
+        put     EF, O_AMOUNT_A;
+        put     EG, O_AMOUNT_B;
+        rsti    destructible;   // Start iteration over inputs
+
+     label NEXT_IN_TYPE:
+        ldi     destructible;   // load next state value
+        not     CO;
+        jif     CO, OUT_TYPES;  // - proceed to outputs if no more inputs left
+        put     E1, ERRNO_EMPTY_STATE; // Set error code for the case of failure
+        test    EA;             // a genuine owned state must carry a type tag
+        chk     CO;             // fail if not
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_IN; // Set error code for the case of failure
+        eq      EA, EF;         // is it the first asset?
+        jif     CO, NEXT_IN_TYPE;
+        eq      EA, EG;         // is it the second asset?
+        chk     CO;             // fail if neither
+        jmp     NEXT_IN_TYPE;
+
+     label OUT_TYPES:
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_OUT; // Set error code for the case of failure
+        rsto    destructible;   // Start iteration over outputs
+
+     label NEXT_OUT_TYPE:
+        ldo     destructible;   // load next state value
+        not     CO;
+        jif     CO, +3;
+        ret;
+        eq      EA, EF;         // is it the first asset?
+        jif     CO, NEXT_OUT_TYPE;
+        eq      EA, EG;         // is it the second asset?
+        chk     CO;             // fail if neither
+        jmp     NEXT_OUT_TYPE;
+
+     // Read the next global, which must be the supply of type `E5`, and check it against the sum
+     // of outputs of type `EE`
+     proc ISSUED_TYPED:
+        put     E1, ERRNO_NO_ISSUED; // Set error code for the case of failure
+        ldo     immutable;      // Read the next global state - circulating supply
+        chk     CO;             // It must exist
+        eq      EA, E5;         // It must have a correct state type
+        chk     CO;             // Or fail otherwise
+        test    EB;             // It must be set
+        chk     CO;             // Or we should fail
+        mov     E2, EB;         // Save supply
+        test    EC;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        test    ED;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+
+        call    FN_SUM_OUTPUTS_TYPED;// Compute a sum of outputs of the type
+        put     E1, ERRNO_SUM_ISSUE_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that circulating supply equals to the sum of outputs
+        chk     CO;             // fail if not
+        ret;
+
+     // Check that the sum of inputs of type `EE` equals the sum of outputs of the same type
+     proc CONSERVE_TYPED:
+        call    FN_SUM_INPUTS_TYPED; // Compute a sum of inputs into E2
+        call    FN_SUM_OUTPUTS_TYPED; // Compute a sum of outputs into E3
+        put     E1, ERRNO_SUM_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that the sum of inputs equals the sum of outputs
+        chk     CO;             // fail if not
+        ret;
+    };
+
+    let deps = [&shared_lib()];
+    let lib = CompiledLib::compile(&mut code, &deps)
+        .unwrap_or_else(|err| panic!("Invalid script: {err}"));
+    debug_assert_eq!(validate_dependencies(&lib, &deps), Ok(()));
+    lib
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::{AuthToken, Input, Instr, StateCell, StateData, StateValue, VmContext};
+    use strict_types::StrictDumb;
+    use zkaluvm::alu::{CoreConfig, CoreExt, Lib, LibId, Supercore, Vm};
+    use zkaluvm::{GfaConfig, GfaCore, RegE, FIELD_ORDER_SECP};
+
+    use super::*;
+    use crate::{G_NAME, G_PRECISION, G_TICKER};
+
+    const CONFIG: CoreConfig = CoreConfig {
+        halt: true,
+        complexity_lim: Some(500_000_000),
+    };
+
+    fn harness() -> (CompiledLib, Vm<Instr<LibId>>, impl Fn(LibId) -> Option<Lib>) {
+        let vm = Vm::<Instr<LibId>>::with(
+            CONFIG,
+            GfaConfig {
+                field_order: FIELD_ORDER_SECP,
+            },
+        );
+        fn resolver(id: LibId) -> Option<Lib> {
+            let lib = multi_fungible();
+            let shared = shared_lib();
+            if lib.as_lib().lib_id() == id {
+                return Some(lib.into_lib());
+            }
+            if shared.as_lib().lib_id() == id {
+                return Some(shared.into_lib());
+            }
+            panic!("Unknown library: {id}");
+        }
+        (multi_fungible(), vm, resolver)
+    }
+
+    fn cell(ty: u256, val: u64) -> StateCell {
+        StateCell {
+            data: StateValue::new(ty, val),
+            auth: AuthToken::strict_dumb(),
+            lock: None,
+        }
+    }
+
+    fn transfer(inp: &[(u256, u64)], out: &[(u256, u64)]) -> (bool, u256) {
+        let input = inp
+            .iter()
+            .map(|(ty, val)| (Input::strict_dumb(), cell(*ty, *val)))
+            .collect::<Vec<_>>();
+        let output = out
+            .iter()
+            .map(|(ty, val)| cell(*ty, *val))
+            .collect::<Vec<_>>();
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: input.as_slice(),
+            immutable_input: &[],
+            destructible_output: output.as_slice(),
+            immutable_output: &[],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_MULTI_TRANSFER), &context, resolver)
+            .is_ok();
+        let gfa: GfaCore = vm.core.cx.subcore();
+        let errno = gfa.get(RegE::E1).map(|fe| fe.to_u256()).unwrap_or_default();
+        (res, errno)
+    }
+
+    #[test]
+    fn genesis_correct() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[cell(O_AMOUNT_A, 1000), cell(O_AMOUNT_B, 10)],
+            immutable_output: &[
+                StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, 8_u8),
+                StateData::new(G_SUPPLY, 1000_u64),
+                StateData::new(G_SUPPLY_B, 10_u64),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_MULTI_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(res);
+    }
+
+    #[test]
+    fn genesis_supply_mismatch() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[cell(O_AMOUNT_A, 1000), cell(O_AMOUNT_B, 11)],
+            immutable_output: &[
+                StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, 8_u8),
+                StateData::new(G_SUPPLY, 1000_u64),
+                StateData::new(G_SUPPLY_B, 10_u64),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_MULTI_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(!res);
+    }
+
+    #[test]
+    fn transfer_correct() {
+        let (res, _) = transfer(
+            &[(O_AMOUNT_A, 100), (O_AMOUNT_B, 50)],
+            &[(O_AMOUNT_B, 20), (O_AMOUNT_A, 60), (O_AMOUNT_A, 40), (O_AMOUNT_B, 30)],
+        );
+        assert!(res);
+    }
+
+    #[test]
+    fn transfer_one_unbalanced() {
+        let (res, errno) = transfer(
+            &[(O_AMOUNT_A, 100), (O_AMOUNT_B, 50)],
+            &[(O_AMOUNT_A, 60), (O_AMOUNT_A, 40), (O_AMOUNT_B, 51)],
+        );
+        assert!(!res);
+        assert_eq!(errno, ERRNO_SUM_MISMATCH);
+
+        let (res, errno) = transfer(
+            &[(O_AMOUNT_A, 100), (O_AMOUNT_B, 50)],
+            &[(O_AMOUNT_A, 99), (O_AMOUNT_B, 50)],
+        );
+        assert!(!res);
+        assert_eq!(errno, ERRNO_SUM_MISMATCH);
+    }
+
+    #[test]
+    fn transfer_cross_type() {
+        // The total is conserved, but the value moves from one asset to the other
+        let (res, errno) = transfer(
+            &[(O_AMOUNT_A, 100), (O_AMOUNT_B, 50)],
+            &[(O_AMOUNT_A, 50), (O_AMOUNT_B, 100)],
+        );
+        assert!(!res);
+        assert_eq!(errno, ERRNO_SUM_MISMATCH);
+    }
+
+    #[test]
+    fn transfer_unknown_type() {
+        let unknown = u256::from(2u8);
+        let (res, errno) = transfer(&[(O_AMOUNT_A, 100), (unknown, 1)], &[(O_AMOUNT_A, 100)]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_UNEXPECTED_OWNED_TYPE_IN);
+
+        let (res, errno) = transfer(&[(O_AMOUNT_A, 100)], &[(O_AMOUNT_A, 100), (unknown, 0)]);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_UNEXPECTED_OWNED_TYPE_OUT);
+    }
+}
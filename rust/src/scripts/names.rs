@@ -0,0 +1,206 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Human-readable names of the state types, used to print the VM contexts.
+
+use std::fmt::{self, Display, Formatter};
+
+use amplify::num::u256;
+use hypersonic::{StateValue, VmContext};
+
+use crate::{
+    G_CERTIFICATE, G_CERTIFICATE_FRACTIONS, G_CERTIFICATE_NAME, G_CERTIFICATE_TICKER,
+    G_COLLECTION_SIZE, G_LOT_SIZE, G_MAX_PER_OUTPUT, G_MIN_OUTPUT, G_NAME, G_PRECISION,
+    G_RECIPIENT_CLASSES, G_REFERENCE, G_RETIRED, G_RGB20_DETAILS, G_SUPPLY, G_SUPPLY_B, G_TICKER,
+    O_ALLOWANCE, O_AMOUNT, O_AMOUNT_B, O_CERTIFICATE, O_RETIRE_RIGHT,
+};
+
+const GLOBAL_NAMES: [(u256, &str); 17] = [
+    (G_NAME, "name"),
+    (G_TICKER, "ticker"),
+    (G_PRECISION, "precision"),
+    (G_SUPPLY, "supply"),
+    (G_SUPPLY_B, "supplyB"),
+    (G_COLLECTION_SIZE, "collectionSize"),
+    (G_MAX_PER_OUTPUT, "maxPerOutput"),
+    (G_RGB20_DETAILS, "rgb20Details"),
+    (G_REFERENCE, "reference"),
+    (G_RECIPIENT_CLASSES, "recipientClasses"),
+    (G_LOT_SIZE, "lotSize"),
+    (G_MIN_OUTPUT, "minOutput"),
+    (G_RETIRED, "retired"),
+    (G_CERTIFICATE, "certificate"),
+    (G_CERTIFICATE_TICKER, "certificateTicker"),
+    (G_CERTIFICATE_NAME, "certificateName"),
+    (G_CERTIFICATE_FRACTIONS, "certificateFractions"),
+];
+
+const OWNED_NAMES: [(u256, &str); 5] = [
+    (O_AMOUNT, "amount"),
+    (O_AMOUNT_B, "amountB"),
+    (O_ALLOWANCE, "allowance"),
+    (O_CERTIFICATE, "certificate"),
+    (O_RETIRE_RIGHT, "retireRight"),
+];
+
+/// Returns the name of a global state type field element, if it is one of the `G_*` constants.
+///
+/// The aliased constants (see the table in the crate root) share the name of the constant they
+/// alias: [`crate::G_NFT`] is named `supply` and [`crate::G_DETAILS`] is named `ticker`. Owned
+/// state types use the same field elements as the global ones, and are named by
+/// [`owned_field_name`].
+pub fn field_name(fe: u256) -> Option<&'static str> {
+    GLOBAL_NAMES
+        .iter()
+        .find(|(ty, _)| *ty == fe)
+        .map(|(_, name)| *name)
+}
+
+/// Returns the name of an owned state type field element, if it is one of the `O_*` constants.
+///
+/// [`crate::O_AMOUNT_A`] is named `amount`, as an alias of [`O_AMOUNT`].
+pub fn owned_field_name(fe: u256) -> Option<&'static str> {
+    OWNED_NAMES
+        .iter()
+        .find(|(ty, _)| *ty == fe)
+        .map(|(_, name)| *name)
+}
+
+/// Formats a [`VmContext`] listing the global and owned state of the operation, with the state
+/// types replaced by their names (see [`field_name`] and [`owned_field_name`]).
+///
+/// Each state is printed as `<type>(<value>, ...)`; the state types which are unknown are printed
+/// as numbers.
+pub struct ContextDisplay<'a>(pub &'a VmContext<'a>);
+
+fn fmt_state(
+    f: &mut Formatter,
+    value: &StateValue,
+    name: fn(u256) -> Option<&'static str>,
+) -> fmt::Result {
+    let (ty, elems) = match *value {
+        StateValue::None => return f.write_str("none"),
+        StateValue::Single { first } => (first, vec![]),
+        StateValue::Double { first, second } => (first, vec![second]),
+        StateValue::Triple {
+            first,
+            second,
+            third,
+        } => (first, vec![second, third]),
+        other => return write!(f, "{other:?}"),
+    };
+    let ty = ty.to_u256();
+    match name(ty) {
+        Some(name) => f.write_str(name)?,
+        None => write!(f, "{ty}")?,
+    }
+    let elems = elems
+        .iter()
+        .map(|fe| fe.to_u256().to_string())
+        .collect::<Vec<_>>();
+    write!(f, "({})", elems.join(", "))
+}
+
+fn fmt_list<'v>(
+    f: &mut Formatter,
+    title: &str,
+    values: impl Iterator<Item = &'v StateValue>,
+    name: fn(u256) -> Option<&'static str>,
+) -> fmt::Result {
+    write!(f, "{title:<12}")?;
+    let mut empty = true;
+    for value in values {
+        if !empty {
+            f.write_str(", ")?;
+        }
+        fmt_state(f, value, name)?;
+        empty = false;
+    }
+    if empty {
+        f.write_str("-")?;
+    }
+    writeln!(f)
+}
+
+impl Display for ContextDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let ctx = self.0;
+        fmt_list(f, "global in:", ctx.immutable_input.iter(), field_name)?;
+        fmt_list(
+            f,
+            "global out:",
+            ctx.immutable_output.iter().map(|data| &data.value),
+            field_name,
+        )?;
+        fmt_list(
+            f,
+            "owned in:",
+            ctx.destructible_input.iter().map(|(_, cell)| &cell.data),
+            owned_field_name,
+        )?;
+        fmt_list(
+            f,
+            "owned out:",
+            ctx.destructible_output.iter().map(|cell| &cell.data),
+            owned_field_name,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypersonic::StateData;
+
+    use super::*;
+    use crate::test_helpers::fungible_ctx;
+    use crate::{G_DETAILS, G_NFT, O_AMOUNT_A};
+
+    #[test]
+    fn names() {
+        assert_eq!(field_name(G_SUPPLY), Some("supply"));
+        assert_eq!(field_name(G_NFT), Some("supply"));
+        assert_eq!(field_name(G_DETAILS), Some("ticker"));
+        assert_eq!(field_name(u256::from(0xFFu8)), None);
+        assert_eq!(owned_field_name(O_AMOUNT_A), Some("amount"));
+        assert_eq!(owned_field_name(O_ALLOWANCE), Some("allowance"));
+
+        let names = GLOBAL_NAMES.iter().map(|(ty, _)| *ty).collect::<Vec<_>>();
+        for (no, ty) in names.iter().enumerate() {
+            assert!(!names[no + 1..].contains(ty), "duplicated name of {ty}");
+        }
+    }
+
+    #[test]
+    fn context_display() {
+        let ctx = fungible_ctx(&[1000], &[600, 400]).with_globals([
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_SUPPLY, 1000u64),
+        ]);
+        assert_eq!(
+            ContextDisplay(&ctx.as_context()).to_string(),
+            "global in:  -\n\
+             global out: ticker(0), supply(1000)\n\
+             owned in:   amount(1000)\n\
+             owned out:  amount(600), amount(400)\n"
+        );
+    }
+}
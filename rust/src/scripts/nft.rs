@@ -0,0 +1,393 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use hypersonic::uasm;
+use zkaluvm::alu::CompiledLib;
+
+use super::FieldLayout;
+use crate::{
+    ERRNO_INVALID_TOKEN_ID, ERRNO_NO_TOKEN_ID, ERRNO_UNEXPECTED_GLOBAL_IN, ERRNO_ZERO_FRACTION,
+};
+
+/// Verifies the NFT token declaration loaded from the global state into `EA`-`ED`.
+///
+/// The declaration must keep only the token id, failing with [`ERRNO_INVALID_TOKEN_ID`] if any
+/// other field element is set.
+///
+/// # Returns
+///
+/// Token id in `E3`.
+///
+/// # Reset registers
+///
+/// `EH`.
+pub const FN_GLOBAL_VERIFY_TOKEN: u16 = 0;
+
+/// Reads the NFT token allocation loaded from the owned state into `EA`-`ED`.
+///
+/// # Returns
+///
+/// Token id in `E3`, number of the allocated token fractions in `E4`.
+///
+/// # Reset registers
+///
+/// `EH`.
+///
+/// # Errors
+///
+/// Fails with [`ERRNO_ZERO_FRACTION`] if the allocation holds zero fractions. Other failures keep
+/// the error code set by the caller.
+pub const FN_OWNED_TOKEN: u16 = 1;
+
+/// Verifies the NFT token declaration loaded from the global state into `EA`-`ED`, which may
+/// commit to an attachment in the third field element.
+///
+/// Works like [`FN_GLOBAL_VERIFY_TOKEN`], but doesn't check the third field element. The
+/// attachment commitment itself is verified by
+/// [`FN_VERIFY_ATTACHMENTS`](crate::FN_VERIFY_ATTACHMENTS).
+///
+/// # Returns
+///
+/// Token id in `E3`.
+///
+/// # Reset registers
+///
+/// `EH`.
+pub const FN_GLOBAL_VERIFY_ATTACHED_TOKEN: u16 = 2;
+
+pub fn nft_lib() -> CompiledLib {
+    nft_lib_with(FieldLayout::default())
+}
+
+/// Compiles the library of the token procedures shared by all NFT libraries for a specific layout
+/// of the state field elements.
+pub fn nft_lib_with(layout: FieldLayout) -> CompiledLib {
+    let FieldLayout { nft, amount, .. } = layout;
+
+    let mut code = uasm! {
+    // Verify token spec
+    // Args: global state loaded into `EA`-`ED`
+    // Returns: token id in `E3`
+    proc FN_GLOBAL_VERIFY_TOKEN:
+        call    FN_GLOBAL_VERIFY_ATTACHED_TOKEN; // Verify the token type and id
+
+        put     E1, ERRNO_INVALID_TOKEN_ID; // Set error code for the case of failure
+        test    EC;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        ret;
+
+    // Get token allocation
+    // Args: owned state loaded into `EA`-`ED`
+    // Returns: token id in `E3`, fractions in `E4`
+    proc FN_OWNED_TOKEN:
+        put     EH, amount;     // Set EH to field element representing token allocation
+        eq      EA, EH;         // It must have the correct state type
+        chk     CO;             // Or fail otherwise
+        test    EB;             // Token id must be set
+        chk     CO;             // Or we should fail
+        mov     E3, EB;         // Save token id for returning it
+        test    EC;             // Token fraction must be set
+        chk     CO;             // Or we should fail
+        mov     E4, EC;         // Save token fractions for returning it
+        test    ED;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        put     E1, ERRNO_ZERO_FRACTION; // Set error code for the case of failure
+        put     EH, 0;          // `test` checks only that the fraction is set, thus we compare it
+        eq      E4, EH;         // with zero explicitly
+        not     CO;             // invert CO value (we need the comparison to fail)
+        chk     CO;             // fail if not
+        ret;
+
+    // Verify token spec, which may commit to an attachment
+    // Args: global state loaded into `EA`-`ED`
+    // Returns: token id in `E3`
+    proc FN_GLOBAL_VERIFY_ATTACHED_TOKEN:
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        put     EH, nft;        // Set EH to field element representing token data
+        eq      EA, EH;         // It must have the correct state type
+        chk     CO;             // Or fail otherwise
+
+        put     E1, ERRNO_NO_TOKEN_ID; // Set error code for the case of failure
+        test    EB;             // Token id must be set
+        chk     CO;             // Or we should fail
+        mov     E3, EB;         // Save token id for returning it
+
+        // `EC` may contain an attachment commitment, see `FN_VERIFY_ATTACHMENTS`
+        put     E1, ERRNO_INVALID_TOKEN_ID; // Set error code for the case of failure
+        test    ED;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        ret;
+    };
+
+    CompiledLib::compile(&mut code, &[]).unwrap_or_else(|err| panic!("Invalid script: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nft_allocation, G_NAME, G_NFT, O_AMOUNT};
+    use amplify::num::u256;
+    use hypersonic::{AuthToken, Instr, StateCell, StateData, StateValue, VmContext};
+    use strict_types::StrictDumb;
+    use zkaluvm::alu::{CoreConfig, CoreExt, Lib, LibId, Supercore, Vm};
+    use zkaluvm::{GfaConfig, GfaCore, RegE, FIELD_ORDER_SECP};
+
+    const CONFIG: CoreConfig = CoreConfig {
+        halt: true,
+        complexity_lim: Some(10_000_000_000),
+    };
+
+    const PROBE_GLOBAL: u16 = 0;
+    const PROBE_OWNED: u16 = 1;
+    const PROBE_ATTACHED: u16 = 2;
+
+    // Loads the first global or owned output and calls the corresponding NFT library procedure
+    fn probe_lib() -> CompiledLib {
+        let nft_lib = nft_lib();
+        let nft = nft_lib.as_lib().lib_id();
+        let mut code = uasm! {
+        routine PROBE_GLOBAL:
+            rsto    immutable;
+            ldo     immutable;
+            call    nft, FN_GLOBAL_VERIFY_TOKEN;
+            ret;
+
+        routine PROBE_OWNED:
+            rsto    destructible;
+            ldo     destructible;
+            call    nft, FN_OWNED_TOKEN;
+            ret;
+
+        routine PROBE_ATTACHED:
+            rsto    immutable;
+            ldo     immutable;
+            call    nft, FN_GLOBAL_VERIFY_ATTACHED_TOKEN;
+            ret;
+        };
+        CompiledLib::compile(&mut code, &[&nft_lib])
+            .unwrap_or_else(|err| panic!("Invalid script: {err}"))
+    }
+
+    fn harness() -> (CompiledLib, Vm<Instr<LibId>>, impl Fn(LibId) -> Option<Lib>) {
+        let vm = Vm::<Instr<LibId>>::with(
+            CONFIG,
+            GfaConfig {
+                field_order: FIELD_ORDER_SECP,
+            },
+        );
+        fn resolver(id: LibId) -> Option<Lib> {
+            let probe = probe_lib();
+            let nft = nft_lib();
+            if probe.as_lib().lib_id() == id {
+                return Some(probe.into_lib());
+            }
+            if nft.as_lib().lib_id() == id {
+                return Some(nft.into_lib());
+            }
+            panic!("Unknown library: {id}");
+        }
+        (probe_lib(), vm, resolver)
+    }
+
+    fn register(vm: &Vm<Instr<LibId>>, reg: RegE) -> Option<u256> {
+        let gfa: GfaCore = vm.core.cx.subcore();
+        gfa.get(reg).map(|fe| fe.to_u256())
+    }
+
+    #[test]
+    fn global_token() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[],
+            immutable_output: &[StateData::new(G_NFT, 42u64)],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(PROBE_GLOBAL), &context, resolver)
+            .is_ok();
+        assert!(res);
+        assert_eq!(register(&vm, RegE::E3), Some(u256::from(42u64)));
+    }
+
+    #[test]
+    fn global_token_invalid() {
+        let cases = [
+            (StateValue::new(G_NAME, 42u64), ERRNO_UNEXPECTED_GLOBAL_IN),
+            (
+                StateValue::Single {
+                    first: G_NFT.into(),
+                },
+                ERRNO_NO_TOKEN_ID,
+            ),
+            (
+                StateValue::Quadruple {
+                    first: G_NFT.into(),
+                    second: 42u64.into(),
+                    third: 0u64.into(),
+                    fourth: 1u64.into(),
+                },
+                ERRNO_INVALID_TOKEN_ID,
+            ),
+        ];
+        for (value, errno) in cases {
+            let global = [StateData { value, raw: None }];
+            let context = VmContext {
+                witness: none!(),
+                destructible_input: &[],
+                immutable_input: &[],
+                destructible_output: &[],
+                immutable_output: &global,
+            };
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(lib.routine(PROBE_GLOBAL), &context, resolver)
+                .is_ok();
+            assert!(!res);
+            assert_eq!(register(&vm, RegE::E1), Some(errno));
+        }
+    }
+
+    #[test]
+    fn global_token_attachment() {
+        let global = [StateData {
+            value: StateValue::Triple {
+                first: G_NFT.into(),
+                second: 42u64.into(),
+                third: 0xC0FFEEu64.into(),
+            },
+            raw: None,
+        }];
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[],
+            immutable_output: &global,
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(PROBE_GLOBAL), &context, resolver)
+            .is_ok();
+        assert!(!res);
+        assert_eq!(register(&vm, RegE::E1), Some(ERRNO_INVALID_TOKEN_ID));
+
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(PROBE_ATTACHED), &context, resolver)
+            .is_ok();
+        assert!(res);
+        assert_eq!(register(&vm, RegE::E3), Some(u256::from(42u64)));
+    }
+
+    #[test]
+    fn owned_token() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[StateCell {
+                data: nft_allocation(42, 7),
+                auth: AuthToken::strict_dumb(),
+                lock: None,
+            }],
+            immutable_output: &[],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(PROBE_OWNED), &context, resolver)
+            .is_ok();
+        assert!(res);
+        assert_eq!(register(&vm, RegE::E3), Some(u256::from(42u64)));
+        assert_eq!(register(&vm, RegE::E4), Some(u256::from(7u64)));
+    }
+
+    fn owned_fractions(fractions: u64) -> (bool, Option<u256>) {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[StateCell {
+                data: nft_allocation(42, fractions),
+                auth: AuthToken::strict_dumb(),
+                lock: None,
+            }],
+            immutable_output: &[],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(PROBE_OWNED), &context, resolver)
+            .is_ok();
+        (res, register(&vm, RegE::E1))
+    }
+
+    #[test]
+    fn owned_token_zero_fraction() {
+        assert_eq!(owned_fractions(0), (false, Some(ERRNO_ZERO_FRACTION)));
+        assert!(owned_fractions(1).0);
+    }
+
+    #[test]
+    fn owned_token_missing_fraction() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[StateCell {
+                data: StateValue::new(O_AMOUNT, 42u64),
+                auth: AuthToken::strict_dumb(),
+                lock: None,
+            }],
+            immutable_output: &[],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(PROBE_OWNED), &context, resolver)
+            .is_ok();
+        assert!(!res);
+        assert_ne!(register(&vm, RegE::E1), Some(ERRNO_ZERO_FRACTION));
+    }
+
+    #[test]
+    fn owned_token_invalid() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[StateCell {
+                data: StateValue::new(G_NFT, 42u64),
+                auth: AuthToken::strict_dumb(),
+                lock: None,
+            }],
+            immutable_output: &[],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(PROBE_OWNED), &context, resolver)
+            .is_ok();
+        assert!(!res);
+    }
+}
@@ -0,0 +1,607 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Routine entry points grouped by the library which defines them.
+//!
+//! Routine indices are local to a library: the same index points to unrelated code in another
+//! library. Codexes must take each verifier from the library listed next to its constant here.
+
+/// Checks that all the routine indices are distinct.
+const fn distinct(routines: &[u16]) -> bool {
+    let mut i = 0;
+    while i < routines.len() {
+        let mut j = i + 1;
+        while j < routines.len() {
+            if routines[i] == routines[j] {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Routines of [`shared_lib`](crate::shared_lib).
+pub mod shared {
+    pub use crate::scripts::shared::{
+        FN_ADD_CHECKED, FN_ASSERT_PAIR_VALUE, FN_ASSERT_SIMPLE_VALUE, FN_ASSET_DETAILS,
+        FN_ASSET_SPEC, FN_GLOBAL_ABSENT,
+    };
+
+    /// All the routines exported by the library.
+    pub const ALL: &[u16] = &[
+        FN_ASSET_SPEC,
+        FN_GLOBAL_ABSENT,
+        FN_ASSET_DETAILS,
+        FN_ASSERT_SIMPLE_VALUE,
+        FN_ASSERT_PAIR_VALUE,
+        FN_ADD_CHECKED,
+    ];
+    const _: () = assert!(super::distinct(ALL), "routine index collision");
+}
+
+/// Routines of [`fungible`](crate::fungible).
+pub mod fungible {
+    pub use crate::scripts::fungible::{
+        FN_FUNGIBLE_ALLOWANCE_VALUE, FN_FUNGIBLE_APPROVE, FN_FUNGIBLE_BLANK, FN_FUNGIBLE_ISSUE,
+        FN_FUNGIBLE_ISSUE_ALLOCATION, FN_FUNGIBLE_ISSUE_CAPPED, FN_FUNGIBLE_ISSUE_CLASSED,
+        FN_FUNGIBLE_ISSUE_LOTS, FN_FUNGIBLE_ISSUE_MIN_OUT, FN_FUNGIBLE_ISSUE_RETIRABLE,
+        FN_FUNGIBLE_ISSUE_RGB25, FN_FUNGIBLE_ISSUE_SCALED, FN_FUNGIBLE_ISSUE_SPEC,
+        FN_FUNGIBLE_LOCK_UNSPENDABLE, FN_FUNGIBLE_LOTS_OUTPUTS, FN_FUNGIBLE_LOT_SIZE,
+        FN_FUNGIBLE_MIN_OUTPUT, FN_FUNGIBLE_MIN_OUTPUTS, FN_FUNGIBLE_RENAME, FN_FUNGIBLE_RETIRE,
+        FN_FUNGIBLE_RETIRE_RIGHT, FN_FUNGIBLE_SUM_INPUTS, FN_FUNGIBLE_SUM_INPUTS_CLASSED,
+        FN_FUNGIBLE_SUM_OUTPUTS, FN_FUNGIBLE_SUM_OUTPUTS_CAPPED, FN_FUNGIBLE_SUM_OUTPUTS_CLASSED,
+        FN_FUNGIBLE_SUPPLY_PLACEMENT, FN_FUNGIBLE_SUPPLY_SCALE, FN_FUNGIBLE_TICKER,
+        FN_FUNGIBLE_TRANSFER, FN_FUNGIBLE_TRANSFER_CLASSED, FN_FUNGIBLE_TRANSFER_FROM,
+        FN_FUNGIBLE_TRANSFER_LOTS, FN_FUNGIBLE_TRANSFER_MIN_OUT, FN_FUNGIBLE_TRANSFER_WITH_CHANGE,
+    };
+
+    /// All the routines exported by the library.
+    pub const ALL: &[u16] = &[
+        FN_FUNGIBLE_ISSUE,
+        FN_FUNGIBLE_ISSUE_ALLOCATION,
+        FN_FUNGIBLE_TICKER,
+        FN_FUNGIBLE_ISSUE_SPEC,
+        FN_FUNGIBLE_TRANSFER,
+        FN_FUNGIBLE_SUM_INPUTS,
+        FN_FUNGIBLE_SUM_OUTPUTS,
+        FN_FUNGIBLE_ISSUE_CAPPED,
+        FN_FUNGIBLE_SUM_OUTPUTS_CAPPED,
+        FN_FUNGIBLE_LOCK_UNSPENDABLE,
+        FN_FUNGIBLE_ISSUE_SCALED,
+        FN_FUNGIBLE_SUPPLY_SCALE,
+        FN_FUNGIBLE_BLANK,
+        FN_FUNGIBLE_ISSUE_RGB25,
+        FN_FUNGIBLE_RENAME,
+        FN_FUNGIBLE_SUPPLY_PLACEMENT,
+        FN_FUNGIBLE_ISSUE_CLASSED,
+        FN_FUNGIBLE_SUM_OUTPUTS_CLASSED,
+        FN_FUNGIBLE_TRANSFER_WITH_CHANGE,
+        FN_FUNGIBLE_ISSUE_LOTS,
+        FN_FUNGIBLE_TRANSFER_LOTS,
+        FN_FUNGIBLE_LOTS_OUTPUTS,
+        FN_FUNGIBLE_LOT_SIZE,
+        FN_FUNGIBLE_TRANSFER_FROM,
+        FN_FUNGIBLE_APPROVE,
+        FN_FUNGIBLE_ALLOWANCE_VALUE,
+        FN_FUNGIBLE_ISSUE_MIN_OUT,
+        FN_FUNGIBLE_TRANSFER_MIN_OUT,
+        FN_FUNGIBLE_MIN_OUTPUTS,
+        FN_FUNGIBLE_MIN_OUTPUT,
+        FN_FUNGIBLE_RETIRE,
+        FN_FUNGIBLE_TRANSFER_CLASSED,
+        FN_FUNGIBLE_SUM_INPUTS_CLASSED,
+        FN_FUNGIBLE_ISSUE_RETIRABLE,
+        FN_FUNGIBLE_RETIRE_RIGHT,
+    ];
+    const _: () = assert!(super::distinct(ALL), "routine index collision");
+}
+
+/// Routines of [`multi_fungible`](crate::multi_fungible).
+pub mod multi {
+    pub use crate::scripts::multi::{
+        FN_MULTI_ISSUE, FN_MULTI_TRANSFER, FN_SUM_INPUTS_TYPED, FN_SUM_OUTPUTS_TYPED,
+    };
+
+    /// All the routines exported by the library.
+    pub const ALL: &[u16] = &[
+        FN_MULTI_ISSUE,
+        FN_MULTI_TRANSFER,
+        FN_SUM_INPUTS_TYPED,
+        FN_SUM_OUTPUTS_TYPED,
+    ];
+    const _: () = assert!(super::distinct(ALL), "routine index collision");
+}
+
+/// Routines of [`nft_lib`](crate::nft_lib).
+pub mod nft {
+    pub use crate::scripts::nft::{
+        FN_GLOBAL_VERIFY_ATTACHED_TOKEN, FN_GLOBAL_VERIFY_TOKEN, FN_OWNED_TOKEN,
+    };
+
+    /// All the routines exported by the library.
+    pub const ALL: &[u16] = &[
+        FN_GLOBAL_VERIFY_TOKEN,
+        FN_OWNED_TOKEN,
+        FN_GLOBAL_VERIFY_ATTACHED_TOKEN,
+    ];
+    const _: () = assert!(super::distinct(ALL), "routine index collision");
+}
+
+/// Routines of [`unique`](crate::unique).
+pub mod unique {
+    pub use crate::scripts::unique::{
+        FN_UNIQUE_ISSUE_ATTACHED, FN_UNIQUE_TRANSFER, FN_VERIFY_ATTACHMENTS,
+    };
+    pub use crate::FN_RGB21_ISSUE;
+
+    /// All the routines exported by the library.
+    pub const ALL: &[u16] = &[
+        FN_RGB21_ISSUE,
+        FN_UNIQUE_TRANSFER,
+        FN_VERIFY_ATTACHMENTS,
+        FN_UNIQUE_ISSUE_ATTACHED,
+    ];
+    const _: () = assert!(super::distinct(ALL), "routine index collision");
+}
+
+/// Routines of [`divisible`](crate::divisible).
+pub mod divisible {
+    pub use crate::scripts::divisible::{
+        FN_DIVISIBLE_BATCH_ISSUE, FN_DIVISIBLE_TRANSFER, FN_NFT_MERGE_ONLY_TRANSFER,
+        FN_NFT_SUM_INPUTS, FN_NFT_SUM_OUTPUTS,
+    };
+    pub use crate::FN_RGB21_ISSUE;
+
+    /// All the routines exported by the library.
+    pub const ALL: &[u16] = &[
+        FN_RGB21_ISSUE,
+        FN_DIVISIBLE_TRANSFER,
+        FN_NFT_SUM_INPUTS,
+        FN_NFT_SUM_OUTPUTS,
+        FN_NFT_MERGE_ONLY_TRANSFER,
+        FN_DIVISIBLE_BATCH_ISSUE,
+    ];
+    const _: () = assert!(super::distinct(ALL), "routine index collision");
+}
+
+/// Routines of [`collection`](crate::collection).
+pub mod collection {
+    pub use crate::scripts::collection::FN_FAC_TRANSFER;
+    pub use crate::FN_RGB21_ISSUE;
+
+    /// All the routines exported by the library.
+    pub const ALL: &[u16] = &[FN_RGB21_ISSUE, FN_FAC_TRANSFER];
+    const _: () = assert!(super::distinct(ALL), "routine index collision");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use amplify::num::u256;
+    use hypersonic::{Instr, StateData, StateValue};
+    use zkaluvm::alu::{CompiledLib, CoreConfig, CoreExt, Lib, LibId, Supercore, Vm};
+    use zkaluvm::{GfaConfig, GfaCore, RegE, FIELD_ORDER_SECP};
+
+    use super::{collection, divisible, fungible, multi, nft, shared, unique};
+    use crate::test_helpers::{fungible_ctx, nft_ctx, TestContext};
+    use crate::{G_NFT, *};
+
+    fn resolver(id: LibId) -> Option<Lib> {
+        [
+            shared_lib(),
+            fungible(),
+            multi_fungible(),
+            nft_lib(),
+            unique(),
+            divisible(),
+            collection(),
+        ]
+        .into_iter()
+        .map(CompiledLib::into_lib)
+        .find(|lib| lib.lib_id() == id)
+    }
+
+    fn run(lib: &CompiledLib, routine: u16, context: &TestContext) -> bool {
+        run_errno(lib, routine, context).is_ok()
+    }
+
+    fn run_errno(lib: &CompiledLib, routine: u16, context: &TestContext) -> Result<(), u256> {
+        let mut vm = Vm::<Instr<LibId>>::with(
+            CoreConfig {
+                halt: true,
+                complexity_lim: Some(1_000_000_000),
+            },
+            GfaConfig {
+                field_order: FIELD_ORDER_SECP,
+            },
+        );
+        if vm
+            .exec(lib.routine(routine), &context.as_context(), resolver)
+            .is_ok()
+        {
+            return Ok(());
+        }
+        let gfa: GfaCore = vm.core.cx.subcore();
+        Err(gfa.get(RegE::E1).map(|fe| fe.to_u256()).unwrap_or_default())
+    }
+
+    /// Checks that each of the `routines` enters the library code with the expected instruction,
+    /// given as the mnemonic optionally followed by the first operand, and that all the library
+    /// routines are covered and have distinct entry points.
+    fn check_entries(lib: &CompiledLib, all: &[u16], routines: &[(u16, &str)]) {
+        let mut covered = routines.iter().map(|(no, _)| *no).collect::<Vec<_>>();
+        let mut all = all.to_vec();
+        covered.sort_unstable();
+        all.sort_unstable();
+        assert_eq!(covered, all, "not all the library routines are checked");
+
+        let mut offsets = BTreeSet::new();
+        for (no, expected) in routines {
+            assert!(
+                (*no as usize) < lib.routines_count(),
+                "routine {no} is absent"
+            );
+            assert!(
+                offsets.insert(lib.routine(*no).offset),
+                "routine {no} shares its entry point with another routine"
+            );
+            let instr = entry_instr(lib, *no)
+                .unwrap_or_else(|| panic!("routine {no} doesn't start at an instruction"))
+                .to_string()
+                .replace(',', " ");
+            let expected = expected.split_whitespace().collect::<Vec<_>>();
+            let found = instr
+                .split_whitespace()
+                .take(expected.len())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                found, expected,
+                "routine {no} has unexpected entry `{instr}`"
+            );
+        }
+    }
+
+    #[test]
+    fn shared_entries() {
+        check_entries(
+            &shared_lib(),
+            shared::ALL,
+            &[
+                (shared::FN_ASSET_SPEC, "put E1"),
+                (shared::FN_GLOBAL_ABSENT, "put E1"),
+                (shared::FN_ASSET_DETAILS, "put E1"),
+                (shared::FN_ASSERT_SIMPLE_VALUE, "test EC"),
+                (shared::FN_ASSERT_PAIR_VALUE, "test ED"),
+                (shared::FN_ADD_CHECKED, "put E1"),
+            ],
+        );
+    }
+
+    #[test]
+    fn fungible_entries() {
+        check_entries(
+            &fungible(),
+            fungible::ALL,
+            &[
+                (fungible::FN_FUNGIBLE_ISSUE, "call"),
+                (fungible::FN_FUNGIBLE_ISSUE_ALLOCATION, "call"),
+                (fungible::FN_FUNGIBLE_TICKER, "put E1"),
+                (fungible::FN_FUNGIBLE_ISSUE_SPEC, "put E1"),
+                (fungible::FN_FUNGIBLE_TRANSFER, "call"),
+                (fungible::FN_FUNGIBLE_SUM_INPUTS, "put E2"),
+                (fungible::FN_FUNGIBLE_SUM_OUTPUTS, "put E3"),
+                (fungible::FN_FUNGIBLE_ISSUE_CAPPED, "call"),
+                (fungible::FN_FUNGIBLE_SUM_OUTPUTS_CAPPED, "put E3"),
+                (fungible::FN_FUNGIBLE_LOCK_UNSPENDABLE, "put E1"),
+                (fungible::FN_FUNGIBLE_ISSUE_SCALED, "call"),
+                (fungible::FN_FUNGIBLE_SUPPLY_SCALE, "put E1"),
+                (fungible::FN_FUNGIBLE_BLANK, "call"),
+                (fungible::FN_FUNGIBLE_ISSUE_RGB25, "put E1"),
+                (fungible::FN_FUNGIBLE_RENAME, "put E1"),
+                (fungible::FN_FUNGIBLE_SUPPLY_PLACEMENT, "clr E7"),
+                (fungible::FN_FUNGIBLE_ISSUE_CLASSED, "call"),
+                (fungible::FN_FUNGIBLE_SUM_OUTPUTS_CLASSED, "put E3"),
+                (fungible::FN_FUNGIBLE_TRANSFER_WITH_CHANGE, "call"),
+                (fungible::FN_FUNGIBLE_ISSUE_LOTS, "call"),
+                (fungible::FN_FUNGIBLE_TRANSFER_LOTS, "put E1"),
+                (fungible::FN_FUNGIBLE_LOTS_OUTPUTS, "put E1"),
+                (fungible::FN_FUNGIBLE_LOT_SIZE, "put E8"),
+                (fungible::FN_FUNGIBLE_TRANSFER_FROM, "call"),
+                (fungible::FN_FUNGIBLE_APPROVE, "call"),
+                (fungible::FN_FUNGIBLE_ALLOWANCE_VALUE, "test EB"),
+                (fungible::FN_FUNGIBLE_ISSUE_MIN_OUT, "call"),
+                (fungible::FN_FUNGIBLE_TRANSFER_MIN_OUT, "put E1"),
+                (fungible::FN_FUNGIBLE_MIN_OUTPUTS, "put E1"),
+                (fungible::FN_FUNGIBLE_MIN_OUTPUT, "put E8"),
+                (fungible::FN_FUNGIBLE_RETIRE, "put E1"),
+                (fungible::FN_FUNGIBLE_TRANSFER_CLASSED, "call"),
+                (fungible::FN_FUNGIBLE_SUM_INPUTS_CLASSED, "put E2"),
+                (fungible::FN_FUNGIBLE_ISSUE_RETIRABLE, "call"),
+                (fungible::FN_FUNGIBLE_RETIRE_RIGHT, "test E5"),
+            ],
+        );
+    }
+
+    #[test]
+    fn multi_entries() {
+        check_entries(
+            &multi_fungible(),
+            multi::ALL,
+            &[
+                (multi::FN_MULTI_ISSUE, "put E1"),
+                (multi::FN_MULTI_TRANSFER, "call"),
+                (multi::FN_SUM_INPUTS_TYPED, "put E2"),
+                (multi::FN_SUM_OUTPUTS_TYPED, "put E3"),
+            ],
+        );
+    }
+
+    #[test]
+    fn nft_entries() {
+        check_entries(
+            &nft_lib(),
+            nft::ALL,
+            &[
+                (nft::FN_GLOBAL_VERIFY_TOKEN, "call"),
+                (nft::FN_OWNED_TOKEN, "put EH"),
+                (nft::FN_GLOBAL_VERIFY_ATTACHED_TOKEN, "put E1"),
+            ],
+        );
+    }
+
+    #[test]
+    fn unique_entries() {
+        check_entries(
+            &unique(),
+            unique::ALL,
+            &[
+                (unique::FN_RGB21_ISSUE, "clr EG"),
+                (unique::FN_UNIQUE_TRANSFER, "call"),
+                (unique::FN_VERIFY_ATTACHMENTS, "rsto"),
+                (unique::FN_UNIQUE_ISSUE_ATTACHED, "put EG"),
+            ],
+        );
+    }
+
+    #[test]
+    fn divisible_entries() {
+        check_entries(
+            &divisible(),
+            divisible::ALL,
+            &[
+                (divisible::FN_RGB21_ISSUE, "put E1"),
+                (divisible::FN_DIVISIBLE_TRANSFER, "clr EG"),
+                (divisible::FN_NFT_SUM_INPUTS, "put E2"),
+                (divisible::FN_NFT_SUM_OUTPUTS, "put E3"),
+                (divisible::FN_NFT_MERGE_ONLY_TRANSFER, "put EG"),
+                (divisible::FN_DIVISIBLE_BATCH_ISSUE, "put E1"),
+            ],
+        );
+    }
+
+    #[test]
+    fn collection_entries() {
+        check_entries(
+            &collection(),
+            collection::ALL,
+            &[
+                (collection::FN_RGB21_ISSUE, "put E1"),
+                (collection::FN_FAC_TRANSFER, "cknxo"),
+            ],
+        );
+    }
+
+    #[test]
+    fn genesis_rejects_global_inputs() {
+        let cases = [
+            ("fungible", fungible(), fungible::FN_FUNGIBLE_ISSUE),
+            (
+                "fungible capped",
+                fungible(),
+                fungible::FN_FUNGIBLE_ISSUE_CAPPED,
+            ),
+            (
+                "fungible scaled",
+                fungible(),
+                fungible::FN_FUNGIBLE_ISSUE_SCALED,
+            ),
+            (
+                "fungible RGB25",
+                fungible(),
+                fungible::FN_FUNGIBLE_ISSUE_RGB25,
+            ),
+            ("multi", multi_fungible(), multi::FN_MULTI_ISSUE),
+            ("unique", unique(), unique::FN_RGB21_ISSUE),
+            (
+                "unique attached",
+                unique(),
+                unique::FN_UNIQUE_ISSUE_ATTACHED,
+            ),
+            ("divisible", divisible(), divisible::FN_RGB21_ISSUE),
+            ("collection", collection(), collection::FN_RGB21_ISSUE),
+        ];
+        let context = fungible_ctx(&[], &[100]).with_global_inputs([StateValue::new(G_NFT, 1u64)]);
+        for (name, lib, routine) in cases {
+            assert_eq!(
+                run_errno(&lib, routine, &context),
+                Err(ERRNO_UNEXPECTED_GLOBAL_IN),
+                "{name} accepts global inputs"
+            );
+        }
+    }
+
+    #[test]
+    fn transfers_reject_inflation() {
+        let cases = [
+            (
+                "fungible",
+                fungible(),
+                fungible::FN_FUNGIBLE_TRANSFER,
+                fungible_ctx(&[100], &[100]),
+                fungible_ctx(&[100], &[101]),
+            ),
+            (
+                "fungible classed",
+                fungible(),
+                fungible::FN_FUNGIBLE_TRANSFER_CLASSED,
+                fungible_ctx(&[100], &[100]),
+                fungible_ctx(&[100], &[101]),
+            ),
+            (
+                "multi",
+                multi_fungible(),
+                multi::FN_MULTI_TRANSFER,
+                fungible_ctx(&[100], &[100]),
+                fungible_ctx(&[100], &[101]),
+            ),
+            (
+                "unique",
+                unique(),
+                unique::FN_UNIQUE_TRANSFER,
+                nft_ctx(&[(1, 1)], &[(1, 1)]),
+                nft_ctx(&[(1, 1)], &[(1, 1), (1, 1)]),
+            ),
+            (
+                "divisible",
+                divisible(),
+                divisible::FN_DIVISIBLE_TRANSFER,
+                nft_ctx(&[(1, 10)], &[(1, 4), (1, 6)]),
+                nft_ctx(&[(1, 10)], &[(1, 5), (1, 6)]),
+            ),
+            (
+                "merge-only",
+                divisible(),
+                divisible::FN_NFT_MERGE_ONLY_TRANSFER,
+                nft_ctx(&[(1, 4), (1, 6)], &[(1, 10)]),
+                nft_ctx(&[(1, 4), (1, 6)], &[(1, 11)]),
+            ),
+            (
+                "collection",
+                collection(),
+                collection::FN_FAC_TRANSFER,
+                nft_ctx(&[(1, 1)], &[(1, 1)]),
+                nft_ctx(&[(1, 1)], &[(1, 1), (1, 1)]),
+            ),
+        ];
+        for (name, lib, routine, valid, inflating) in cases {
+            assert!(
+                run(&lib, routine, &valid),
+                "{name} rejects a valid transfer"
+            );
+            assert!(
+                !run(&lib, routine, &inflating),
+                "{name} accepts an inflating transfer"
+            );
+        }
+    }
+
+    #[test]
+    fn sums_reject_overflow() {
+        let asset_spec = || {
+            [
+                StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, 2u8),
+                StateData::new(G_SUPPLY, u64::MAX),
+            ]
+        };
+        let cases = [
+            (
+                "fungible inputs",
+                fungible(),
+                fungible::FN_FUNGIBLE_TRANSFER,
+                fungible_ctx(&[u64::MAX, 1], &[1]),
+            ),
+            (
+                "fungible outputs",
+                fungible(),
+                fungible::FN_FUNGIBLE_TRANSFER,
+                fungible_ctx(&[1], &[u64::MAX, 1]),
+            ),
+            (
+                "fungible capped",
+                fungible(),
+                fungible::FN_FUNGIBLE_ISSUE_CAPPED,
+                fungible_ctx(&[], &[u64::MAX, 1]).with_globals(
+                    asset_spec()
+                        .into_iter()
+                        .chain([StateData::new(G_MAX_PER_OUTPUT, u64::MAX)]),
+                ),
+            ),
+            (
+                "fungible classed",
+                fungible(),
+                fungible::FN_FUNGIBLE_ISSUE_CLASSED,
+                fungible_ctx(&[], &[u64::MAX, 1]).with_globals(
+                    asset_spec()
+                        .into_iter()
+                        .chain([StateData::new(G_RECIPIENT_CLASSES, 10u64)]),
+                ),
+            ),
+            (
+                "multi inputs",
+                multi_fungible(),
+                multi::FN_MULTI_TRANSFER,
+                fungible_ctx(&[u64::MAX, 1], &[1]),
+            ),
+            (
+                "multi outputs",
+                multi_fungible(),
+                multi::FN_MULTI_TRANSFER,
+                fungible_ctx(&[1], &[u64::MAX, 1]),
+            ),
+            (
+                "divisible inputs",
+                divisible(),
+                divisible::FN_DIVISIBLE_TRANSFER,
+                nft_ctx(&[(1, u64::MAX), (1, 1)], &[(1, 1)]),
+            ),
+            (
+                "divisible outputs",
+                divisible(),
+                divisible::FN_DIVISIBLE_TRANSFER,
+                nft_ctx(&[(1, 1)], &[(1, u64::MAX), (1, 1)]),
+            ),
+            (
+                "divisible batch",
+                divisible(),
+                divisible::FN_DIVISIBLE_BATCH_ISSUE,
+                nft_ctx(&[], &[(1, u64::MAX), (1, 1)]).with_globals([
+                    StateData::new(G_DETAILS, 0u8),
+                    StateData::new(G_NAME, 0u8),
+                    StateData::new(G_PRECISION, u64::MAX),
+                    StateData::new(G_NFT, 1u64),
+                ]),
+            ),
+        ];
+        for (name, lib, routine, context) in cases {
+            assert_eq!(
+                run_errno(&lib, routine, &context),
+                Err(ERRNO_SUM_OVERFLOW),
+                "{name} doesn't detect the sum overflow"
+            );
+        }
+    }
+}
@@ -24,31 +24,44 @@ use amplify::num::u256;
 use hypersonic::uasm;
 use zkaluvm::alu::CompiledLib;
 
-use crate::{G_DETAILS, G_NAME, G_PRECISION, G_TICKER, O_AMOUNT};
+use super::FieldLayout;
 
 /// Checks globals defining assent specification to be present and contain the correct state type.
 ///
 /// NB: Doesn't check that the values of that globals fulfill ASCII criteria (like length, use of
 /// specific chars, etc.). This is not enforced by consensus here, and instead, the contract will
-/// just fail to read its state under RGB20, 21, 25 or another interface.
+/// just fail to read its state under RGB20, 21, 25 or another interface. The only exception is the
+/// asset name, which must carry a value, failing with [`ERRNO_EMPTY_NAME`] otherwise. The first
+/// global is not checked the same way, since in RGB25 it holds the details, which have no
+/// verifiable value.
 ///
 /// # Input
 ///
 /// Procedure takes no registry input.
 ///
-/// It accepts the immutable outputs iterator at the current progress, without a reset.
+/// The immutable outputs iterator is restarted after the precision scan, thus the specification
+/// must be the first global state of the operation.
 ///
 /// # Output
 ///
-/// `E4` contains the value of [`G_PRECISION`].
+/// `E4` contains the value of [`crate::G_PRECISION`] (the number of token fractions in RGB21). This
+/// is the only register returning the precision: all the consumers must read it from `E4`, since
+/// `EB` (which held the precision while it was loaded) is cleared before the return.
 ///
 /// # Reset registers
 ///
-/// `EA`-`ED`
+/// `EA`-`ED`, `EH`
+///
+/// # Errors
+///
+/// Besides the errors on a missing or invalid specification, fails with
+/// [`ERRNO_MULTIPLE_PRECISION`] if the precision global is declared more than once, such that all
+/// the routines reading the globals observe the same precision.
 ///
 /// # Side effects
 ///
-/// Progresses immutable outputs iterator for three positions.
+/// Scans all the immutable outputs for the repeated precision and restarts their iteration;
+/// afterward progresses immutable outputs iterator for three positions.
 pub const FN_ASSET_SPEC: u16 = 0;
 
 /// Ensure the global state is absent (both input and output).
@@ -66,6 +79,89 @@ pub const FN_ASSET_SPEC: u16 = 0;
 /// Resets input and output global state iterators
 pub const FN_GLOBAL_ABSENT: u16 = 1;
 
+/// Checks the optional details global ([`crate::G_RGB20_DETAILS`] in the default layout), which
+/// must follow the rest of the asset specification (including the issued supply, if any), and the
+/// optional reference global ([`crate::G_REFERENCE`] in the default layout), which must be the
+/// last one (after the details, if present).
+///
+/// # Input
+///
+/// Procedure takes no registry input.
+///
+/// It accepts the immutable outputs iterator at the current progress, without a reset.
+///
+/// # Output
+///
+/// None
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `EH`
+///
+/// # Side effects
+///
+/// Progresses immutable outputs iterator for up to two positions.
+pub const FN_ASSET_DETAILS: u16 = 2;
+
+/// Ensures the loaded state value is a single field element in `EB`, i.e. it has no extra field
+/// elements in `EC` and `ED`.
+///
+/// This is the canonical check for the owned values of fungible tokens, which must be used by all
+/// the procedures summing them, such that a value with extra field elements is rejected
+/// independently of the code path.
+///
+/// # Input
+///
+/// `EB`-`ED` contain the loaded state value; `E1` contains the error code to fail with.
+///
+/// # Output
+///
+/// None
+///
+/// # Errors
+///
+/// Fails with the error code provided in `E1` if `EC` or `ED` is set.
+pub const FN_ASSERT_SIMPLE_VALUE: u16 = 4;
+
+/// Ensures the loaded state value consists of no more than two field elements in `EB` and `EC`,
+/// i.e. it has no extra field element in `ED`.
+///
+/// This is the check for the owned values carrying a second field element, like token fractions
+/// (with the token id in `EB`) or a recipient class (with the amount in `EB`).
+///
+/// # Input
+///
+/// `EB`-`ED` contain the loaded state value; `E1` contains the error code to fail with.
+///
+/// # Output
+///
+/// None
+///
+/// # Errors
+///
+/// Fails with the error code provided in `E1` if `ED` is set.
+pub const FN_ASSERT_PAIR_VALUE: u16 = 5;
+
+/// Adds a value to a sum accumulator, failing if the sum no longer fits into 64 bits.
+///
+/// This is the canonical overflow check for all the procedures summing owned values, such that the
+/// check can't be forgotten at some of them.
+///
+/// # Input
+///
+/// `EB` contains the value to add, which must fit into 64 bits (this must be checked by the
+/// caller); `E8` contains the accumulator.
+///
+/// # Output
+///
+/// `E8` contains the updated accumulator.
+///
+/// # Errors
+///
+/// Fails with [`ERRNO_SUM_OVERFLOW`] if the sum doesn't fit into 64 bits; leaves `E1` set to
+/// [`ERRNO_SUM_OVERFLOW`] on success as well.
+pub const FN_ADD_CHECKED: u16 = 6;
+
 pub const ERRNO_NO_TICKER: u256 = u256::from_inner([1, 0, 0, 0]);
 pub const ERRNO_NO_NAME: u256 = u256::from_inner([2, 0, 0, 0]);
 pub const ERRNO_NO_PRECISION: u256 = u256::from_inner([3, 0, 0, 0]);
@@ -73,10 +169,44 @@ pub const ERRNO_INVALID_PRECISION: u256 = u256::from_inner([4, 0, 0, 0]);
 pub const ERRNO_UNEXPECTED_OWNED_IN: u256 = u256::from_inner([5, 0, 0, 0]);
 pub const ERRNO_UNEXPECTED_GLOBAL_IN: u256 = u256::from_inner([6, 0, 0, 0]);
 pub const ERRNO_UNEXPECTED_GLOBAL_OUT: u256 = u256::from_inner([7, 0, 0, 0]);
+pub const ERRNO_INVALID_DETAILS: u256 = u256::from_inner([8, 0, 0, 0]);
+pub const ERRNO_INVALID_REFERENCE: u256 = u256::from_inner([9, 0, 0, 0]);
+pub const ERRNO_SUM_OVERFLOW: u256 = u256::from_inner([10, 0, 0, 0]);
+pub const ERRNO_EMPTY_STATE: u256 = u256::from_inner([11, 0, 0, 0]);
+pub const ERRNO_MULTIPLE_PRECISION: u256 = u256::from_inner([12, 0, 0, 0]);
+pub const ERRNO_EMPTY_NAME: u256 = u256::from_inner([13, 0, 0, 0]);
+
+// NB: Uniqueness of the auth tokens (seals) across the operation outputs is not asserted by the
+//     scripts: state loading instructions put only the state data into the registers, and the auth
+//     tokens are not accessible to the VM. Seal uniqueness is left to the client-side validation.
+
+// TODO: Add `FN_CHECK_WITNESS_COMMITMENT` binding spends to transaction templates (covenants). The
+//       covenant hash would be declared by a genesis global and provided to the operation as a
+//       global input, while the operation witness would be `StateValue::Double { first: <covenant
+//       global type>, second: <template hash> }`; the procedure would fail with
+//       `ERRNO_WITNESS_MISMATCH` unless both the type and the hash are equal to the ones of the
+//       global input. This is blocked until the VM instruction reading the operation witness
+//       (`VmContext::witness`) is available to the scripts.
 
 pub fn shared_lib() -> CompiledLib {
-    assert_eq!(O_AMOUNT, G_NAME);
-    assert_eq!(G_TICKER, G_DETAILS);
+    shared_lib_with(FieldLayout::default())
+}
+
+/// Compiles the shared library for a specific layout of the state field elements.
+pub fn shared_lib_with(layout: FieldLayout) -> CompiledLib {
+    let FieldLayout {
+        name,
+        ticker,
+        precision,
+        details,
+        reference,
+        ..
+    } = layout;
+
+    const NO_DETAILS: u16 = 3;
+    const FN_PRECISION_UNIQUE: u16 = 7;
+    const LOOP_PRECISION: u16 = 8;
+    const PRECISION_UNIQUE: u16 = 9;
 
     let mut code = uasm! {
      proc FN_ASSET_SPEC:
@@ -93,24 +223,29 @@ pub fn shared_lib() -> CompiledLib {
         not     CO;
         chk     CO;
 
+        call    FN_PRECISION_UNIQUE; // Check the precision is not repeated
+
         put     E1, ERRNO_NO_TICKER; // Set error code for the case of failure
         ldo     immutable;      // Read the first global state - ticker in RGB20, details in RGB21/25
         chk     CO;             // - it must exist
-        put     EH, G_TICKER;   // - set E1 to the field element representing owned value (also global asset name)
+        put     EH, ticker;     // - set E1 to the field element representing owned value (also global asset name)
         eq      EA, EH;         // - it must have the correct state type
         chk     CO;             // - - or fail otherwise
 
         put     E1, ERRNO_NO_NAME; // Set error code for the case of failure
         ldo     immutable;      // Read the second global state - asset name
         chk     CO;             // - it must exist
-        put     EH, G_NAME;     // - set E1 to a field element representing global asset ticker (or details)
+        put     EH, name;       // - set E1 to a field element representing global asset ticker (or details)
         eq      EA, EH;         // - it must have the correct state type
         chk     CO;             // - - or fail otherwise
+        put     E1, ERRNO_EMPTY_NAME; // Set error code for the case of failure
+        test    EB;             // - it must have a value
+        chk     CO;             // - - or fail otherwise
 
         put     E1, ERRNO_NO_PRECISION; // Set error code for the case of failure
         ldo     immutable;      // The third global state - precision
         chk     CO;             // - it must exist
-        put     EH, G_PRECISION;// - set E1 to a field element representing global fractions
+        put     EH, precision;  // - set E1 to a field element representing global fractions
         eq      EA, EH;         // - it must have the correct state type
         chk     CO;             // - - or fail otherwise
 
@@ -147,6 +282,82 @@ pub fn shared_lib() -> CompiledLib {
         not     CO;
         chk     CO;
         ret;
+
+    proc FN_ASSET_DETAILS:
+        put     E1, ERRNO_INVALID_DETAILS; // Set error code for the case of failure
+        ldo     immutable;      // Read the global state following the asset specification
+        not     CO;
+        jif     CO, NO_DETAILS; // - details are optional
+        put     EH, reference;
+        eq      EA, EH;         // - the reference may follow the asset specification directly
+        jif     CO, NO_DETAILS; // - - and then it must be the last one
+        put     EH, details;
+        eq      EA, EH;         // - if present, it must have the correct state type
+        chk     CO;             // - - or fail otherwise
+
+        put     E1, ERRNO_INVALID_REFERENCE; // Set error code for the case of failure
+        ldo     immutable;      // Read the global state following the details
+        not     CO;
+        jif     CO, NO_DETAILS; // - reference is optional
+        put     EH, reference;
+        eq      EA, EH;         // - if present, it must have the correct state type
+        chk     CO;             // - - or fail otherwise
+
+    label NO_DETAILS:
+        clr     E1;
+        clr     EA;
+        clr     EB;
+        clr     EC;
+        clr     ED;
+        ret;
+
+    proc FN_ASSERT_SIMPLE_VALUE:
+        test    EC;             // ensure EC is not set
+        not     CO;
+        chk     CO;             // fail if not
+
+        test    ED;             // ensure ED is not set
+        not     CO;
+        chk     CO;             // fail if not
+        ret;
+
+    proc FN_ASSERT_PAIR_VALUE:
+        test    ED;             // ensure ED is not set
+        not     CO;
+        chk     CO;             // fail if not
+        ret;
+
+    proc FN_ADD_CHECKED:
+        put     E1, ERRNO_SUM_OVERFLOW; // Set error code for the case of failure
+        add     E8, EB;         // add the value to the accumulator
+        fits    E8, 64.bits;    // ensure we do not overflow
+        chk     CO;             // fail if not
+        ret;
+
+    routine FN_PRECISION_UNIQUE:
+        put     E1, ERRNO_MULTIPLE_PRECISION; // Set error code for the case of failure
+        put     EH, precision;  // EH holds the precision state type
+        clr     E4;             // E4 is set once the precision is seen
+        rsto    immutable;      // Start iteration over the global state
+
+    label LOOP_PRECISION:
+        ldo     immutable;      // Read next global state
+        not     CO;
+        jif     CO, PRECISION_UNIQUE; // Finish if no more elements are present
+        eq      EA, EH;         // Is it a precision?
+        not     CO;
+        jif     CO, LOOP_PRECISION; // - skip any other global state
+        test    E4;             // The precision must not be repeated
+        not     CO;
+        chk     CO;             // - or fail otherwise
+        put     E4, 1;          // Mark that we have seen the precision
+        jmp     LOOP_PRECISION;
+
+    label PRECISION_UNIQUE:
+        rsto    immutable;      // Restart iteration over the global state
+        clr     E4;
+        clr     E1;
+        ret;
     };
 
     CompiledLib::compile(&mut code, &[]).unwrap_or_else(|err| panic!("Invalid script: {err}"))
@@ -155,10 +366,11 @@ pub fn shared_lib() -> CompiledLib {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{G_DETAILS, G_NAME, G_PRECISION, G_TICKER, O_AMOUNT};
     use hypersonic::{AuthToken, Instr, StateCell, StateData, StateValue, VmContext};
     use strict_types::StrictDumb;
-    use zkaluvm::alu::{CoreConfig, Lib, LibId, Vm};
-    use zkaluvm::{GfaConfig, FIELD_ORDER_SECP};
+    use zkaluvm::alu::{CoreConfig, CoreExt, Lib, LibId, Supercore, Vm};
+    use zkaluvm::{GfaConfig, GfaCore, RegE, FIELD_ORDER_SECP};
 
     const CONFIG: CoreConfig = CoreConfig {
         halt: true,
@@ -297,4 +509,104 @@ mod tests {
             .is_ok();
         assert!(res);
     }
+
+    #[test]
+    fn genesis_empty_name() {
+        let run = |name: StateValue| {
+            let globals = [
+                StateData::new(G_TICKER, 0u8),
+                StateData {
+                    value: name,
+                    raw: None,
+                },
+                StateData::new(G_PRECISION, 2u8),
+            ];
+            let context = VmContext {
+                witness: none!(),
+                destructible_input: &[],
+                immutable_input: &[],
+                destructible_output: &[],
+                immutable_output: &globals,
+            };
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(lib.routine(FN_ASSET_SPEC), &context, resolver)
+                .is_ok();
+            let gfa: GfaCore = vm.core.cx.subcore();
+            (res, gfa.get(RegE::E1).map(|fe| fe.to_u256()))
+        };
+        assert_eq!(run(StateValue::new(G_NAME, 1u8)), (true, None));
+        assert_eq!(
+            run(StateValue::Single {
+                first: G_NAME.into()
+            }),
+            (false, Some(ERRNO_EMPTY_NAME))
+        );
+    }
+
+    #[test]
+    fn genesis_multiple_precision() {
+        let spec = [
+            StateData::new(G_TICKER, 0u8),
+            StateData::new(G_NAME, 1u8),
+            StateData::new(G_PRECISION, 2u8),
+        ];
+        let repeated = [
+            [&spec[..], &[StateData::new(G_PRECISION, 2u8)]].concat(),
+            [&spec[..], &[StateData::new(G_PRECISION, 8u8)]].concat(),
+            [
+                &spec[..],
+                &[
+                    StateData::new(G_DETAILS, 0u8),
+                    StateData::new(G_PRECISION, 8u8),
+                ],
+            ]
+            .concat(),
+        ];
+        let run = |globals: &[StateData]| {
+            let context = VmContext {
+                witness: none!(),
+                destructible_input: &[],
+                immutable_input: &[],
+                destructible_output: &[],
+                immutable_output: globals,
+            };
+            let (lib, mut vm, resolver) = harness();
+            let res = vm
+                .exec(lib.routine(FN_ASSET_SPEC), &context, resolver)
+                .is_ok();
+            let gfa: GfaCore = vm.core.cx.subcore();
+            (res, gfa.get(RegE::E1).map(|fe| fe.to_u256()))
+        };
+        assert_eq!(run(&spec), (true, None));
+        for globals in repeated {
+            assert_eq!(run(&globals), (false, Some(ERRNO_MULTIPLE_PRECISION)));
+        }
+    }
+
+    #[test]
+    fn precision_register() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[],
+            immutable_output: &[
+                StateData::new(G_TICKER, 0u8),
+                StateData::new(G_NAME, 1u8),
+                StateData::new(G_PRECISION, 7u8),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_ASSET_SPEC), &context, resolver)
+            .is_ok();
+        assert!(res);
+        let gfa: GfaCore = vm.core.cx.subcore();
+        assert_eq!(
+            gfa.get(RegE::E4).map(|fe| fe.to_u256()),
+            Some(u256::from(7u8))
+        );
+        assert_eq!(gfa.get(RegE::EB), None);
+    }
 }
@@ -0,0 +1,214 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Execution tracing for debugging the verification failures.
+
+use amplify::num::u256;
+use hypersonic::{uasm, Instr, VmContext};
+use zkaluvm::alu::isa::Bytecode;
+use zkaluvm::alu::{CompiledLib, CoreExt, Lib, LibId, LibSite, Supercore, Vm};
+use zkaluvm::{GfaCore, RegE};
+
+use super::consumed_complexity;
+use super::inspect::instructions;
+
+/// Report on a single routine execution, produced by [`exec_traced`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TraceReport {
+    /// Entry point of the executed routine.
+    pub site: LibSite,
+    /// Whether the routine has succeeded.
+    pub success: bool,
+    /// Value of the `E1` register (the error code) after the execution.
+    pub errno: Option<u256>,
+    /// Offset of the check which has failed the routine, if the routine has failed with an error
+    /// code set by the library of the routine.
+    pub failed_at: Option<u16>,
+    /// Complexity consumed by the VM after the execution.
+    pub complexity: u64,
+}
+
+/// Executes a library routine, reporting the error code and the failed check.
+///
+/// The VM doesn't expose the position of the failed instruction, thus the failed check is located
+/// in the library code: it is the first `chk CO` following the first `put E1, <errno>` after the
+/// routine entry point. Since each failure path sets its own error code right before the checks,
+/// this points to the failed check, unless the error code is shared by several checks of the
+/// routine, in which case it points to the first of them.
+///
+/// The search stops at the end of the routine: the first `ret` or the entry point of the next
+/// routine or label, whichever comes first. Thus, no offset is reported if the error code is set
+/// by another routine or library called by the routine, or if the failed check follows a label or
+/// an early return within the routine.
+pub fn exec_traced(
+    vm: &mut Vm<Instr<LibId>>,
+    lib: &CompiledLib,
+    routine: u16,
+    context: &VmContext,
+    resolver: impl Fn(LibId) -> Option<Lib>,
+) -> TraceReport {
+    let site = lib.routine(routine);
+    let success = vm.exec(site, context, resolver).is_ok();
+    let gfa: GfaCore = vm.core.cx.subcore();
+    let errno = gfa.get(RegE::E1).map(|fe| fe.to_u256());
+    let failed_at = match errno {
+        Some(errno) if !success => failed_check(lib, site.offset, errno),
+        _ => None,
+    };
+    TraceReport {
+        site,
+        success,
+        errno,
+        failed_at,
+        complexity: consumed_complexity(vm),
+    }
+}
+
+fn failed_check(lib: &CompiledLib, entry: u16, errno: u256) -> Option<u16> {
+    const PROBE: u16 = 0;
+    let mut code = uasm! {
+     routine PROBE:
+        put     E1, errno;
+        chk     CO;
+        ret;
+    };
+    let probe =
+        CompiledLib::compile(&mut code, &[]).unwrap_or_else(|err| panic!("Invalid script: {err}"));
+    let probe = instructions(&probe);
+    let (set_errno, check, ret) = (&probe[0], &probe[1], &probe[2]);
+
+    let end = (0..lib.routines_count())
+        .map(|no| lib.routine(no as u16).offset)
+        .filter(|offset| *offset > entry)
+        .min()
+        .unwrap_or(u16::MAX);
+    let mut offset = 0u16;
+    let mut errno_set = false;
+    for instr in instructions(lib) {
+        if offset >= end {
+            break;
+        }
+        if offset >= entry {
+            if errno_set && &instr == check {
+                return Some(offset);
+            }
+            if &instr == ret {
+                break;
+            }
+            errno_set |= &instr == set_errno;
+        }
+        offset += instr.code_byte_len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use zkaluvm::alu::CoreConfig;
+    use zkaluvm::{GfaConfig, FIELD_ORDER_SECP};
+
+    use super::*;
+    use crate::test_helpers::fungible_ctx;
+    use crate::{
+        fungible, shared_lib, ERRNO_SUM_MISMATCH, FN_FUNGIBLE_SUM_INPUTS, FN_FUNGIBLE_TRANSFER,
+    };
+
+    fn resolver(id: LibId) -> Option<Lib> {
+        [shared_lib(), fungible()]
+            .into_iter()
+            .map(|lib| lib.into_lib())
+            .find(|lib| lib.lib_id() == id)
+    }
+
+    fn vm() -> Vm<Instr<LibId>> {
+        Vm::<Instr<LibId>>::with(
+            CoreConfig {
+                halt: true,
+                complexity_lim: Some(500_000_000),
+            },
+            GfaConfig {
+                field_order: FIELD_ORDER_SECP,
+            },
+        )
+    }
+
+    #[test]
+    fn trace_sum_mismatch() {
+        let lib = fungible();
+        let context = fungible_ctx(&[100], &[99]);
+        let report = exec_traced(
+            &mut vm(),
+            &lib,
+            FN_FUNGIBLE_TRANSFER,
+            &context.as_context(),
+            resolver,
+        );
+        assert!(!report.success);
+        assert_eq!(report.site, lib.routine(FN_FUNGIBLE_TRANSFER));
+        assert_eq!(report.errno, Some(ERRNO_SUM_MISMATCH));
+        // The failed check belongs to the transfer routine, which is followed by the input sum
+        let failed_at = report.failed_at.expect("no failed check");
+        assert!(failed_at > lib.routine(FN_FUNGIBLE_TRANSFER).offset);
+        assert!(failed_at < lib.routine(FN_FUNGIBLE_SUM_INPUTS).offset);
+        assert!(report.complexity > 0);
+    }
+
+    #[test]
+    fn check_beyond_routine() {
+        const FN_SET: u16 = 0;
+        const FN_CHECK: u16 = 1;
+        const FN_RETURN: u16 = 2;
+        let mut code = uasm! {
+         routine FN_SET:
+            put     E1, ERRNO_SUM_MISMATCH;
+         routine FN_CHECK:
+            chk     CO;
+            ret;
+         routine FN_RETURN:
+            put     E1, ERRNO_SUM_MISMATCH;
+            ret;
+            chk     CO;
+        };
+        let lib = CompiledLib::compile(&mut code, &[])
+            .unwrap_or_else(|err| panic!("Invalid script: {err}"));
+        // Neither the check of the next routine nor the check behind the return are reported
+        let set = lib.routine(FN_SET).offset;
+        assert_eq!(failed_check(&lib, set, ERRNO_SUM_MISMATCH), None);
+        let ret = lib.routine(FN_RETURN).offset;
+        assert_eq!(failed_check(&lib, ret, ERRNO_SUM_MISMATCH), None);
+    }
+
+    #[test]
+    fn trace_success() {
+        let lib = fungible();
+        let context = fungible_ctx(&[100], &[40, 60]);
+        let report = exec_traced(
+            &mut vm(),
+            &lib,
+            FN_FUNGIBLE_TRANSFER,
+            &context.as_context(),
+            resolver,
+        );
+        assert!(report.success);
+        assert_eq!(report.failed_at, None);
+    }
+}
@@ -21,16 +21,40 @@
 // the License.
 
 use amplify::num::u256;
-use hypersonic::uasm;
+use hypersonic::{uasm, StateValue};
 use zkaluvm::alu::CompiledLib;
 
-use super::{shared_lib, FN_ASSET_SPEC, FN_GLOBAL_ABSENT};
-use crate::{ERRNO_UNEXPECTED_GLOBAL_IN, G_NFT, O_AMOUNT};
-
-pub const FN_UNIQUE_TRANSFER: u16 = 3;
-
-pub const FN_GLOBAL_VERIFY_TOKEN: u16 = 1;
-pub const FN_OWNED_TOKEN: u16 = 2;
+use super::{
+    nft_lib_with, shared_lib_with, validate_dependencies, FieldLayout, ERRNO_EMPTY_STATE,
+    FN_ASSET_SPEC, FN_GLOBAL_ABSENT, FN_GLOBAL_VERIFY_ATTACHED_TOKEN, FN_GLOBAL_VERIFY_TOKEN,
+    FN_OWNED_TOKEN,
+};
+use crate::{ERRNO_UNEXPECTED_GLOBAL_IN, ERRNO_UNEXPECTED_OWNED_IN, FN_RGB21_ISSUE, O_AMOUNT};
+
+pub const FN_UNIQUE_TRANSFER: u16 = 2;
+
+/// Checks that each of the NFT token declarations in the global state commits to an attachment.
+///
+/// The token global ([`G_NFT`](crate::G_NFT)) keeps the token id in the second field element, and
+/// the commitment to the attachment hash (the media of the token spec) in the third field element,
+/// which must be set and non-zero.
+///
+/// # Reset registers
+///
+/// `EA`-`ED`, `EH`.
+///
+/// # Side effects
+///
+/// Extinguishes the immutable outputs iterator.
+pub const FN_VERIFY_ATTACHMENTS: u16 = 9;
+
+/// Issue of a unique token which must commit to an attachment.
+///
+/// Works like [`FN_RGB21_ISSUE`], but verifies the token declaration with
+/// [`FN_GLOBAL_VERIFY_ATTACHED_TOKEN`], which allows the attachment commitment, and additionally
+/// runs [`FN_VERIFY_ATTACHMENTS`]. The unique token issue itself requires the token declaration to
+/// have no attachment commitment.
+pub const FN_UNIQUE_ISSUE_ATTACHED: u16 = 11;
 
 pub const ERRNO_FRACTIONALITY: u256 = u256::from_inner([1, 2, 0, 0]);
 pub const ERRNO_NO_TOKEN_ID: u256 = u256::from_inner([2, 2, 0, 0]);
@@ -40,75 +64,84 @@ pub const ERRNO_NO_INPUT: u256 = u256::from_inner([5, 2, 0, 0]);
 pub const ERRNO_TOKEN_EXCESS_IN: u256 = u256::from_inner([6, 2, 0, 0]);
 pub const ERRNO_NO_OUTPUT: u256 = u256::from_inner([7, 2, 0, 0]);
 pub const ERRNO_TOKEN_EXCESS_OUT: u256 = u256::from_inner([8, 2, 0, 0]);
+pub const ERRNO_GLOBAL_FRACTIONS_NOT_ONE: u256 = u256::from_inner([9, 2, 0, 0]);
+pub const ERRNO_OWNED_FRACTIONS_NOT_ONE: u256 = u256::from_inner([10, 2, 0, 0]);
+pub const ERRNO_MISSING_ATTACHMENT: u256 = u256::from_inner([11, 2, 0, 0]);
+pub const ERRNO_ZERO_FRACTION: u256 = u256::from_inner([12, 2, 0, 0]);
+
+/// Constructs owned state for an NFT allocation.
+///
+/// All RGB21 scripts use the same layout of the owned state: the first field element is the
+/// [`O_AMOUNT`] state type, the second is the token id and the third is the number of the token
+/// fractions allocated.
+pub fn nft_allocation(token_id: u64, fractions: u64) -> StateValue {
+    StateValue::Triple {
+        first: O_AMOUNT.into(),
+        second: token_id.into(),
+        third: fractions.into(),
+    }
+}
 
 pub fn unique() -> CompiledLib {
-    let shared = shared_lib().into_lib().lib_id();
+    unique_with(FieldLayout::default())
+}
 
-    const VERIFY_GLOBAL_TOKEN: u16 = 4;
-    const VERIFY_IN_TOKEN: u16 = 5;
-    const VERIFY_OUT_TOKEN: u16 = 6;
-    const VERIFY_TOKEN: u16 = 7;
+/// Compiles the unique token library for a specific layout of the state field elements.
+///
+/// The library calls the shared and the NFT libraries compiled for the same layout (see
+/// [`shared_lib_with`] and [`nft_lib_with`]).
+pub fn unique_with(layout: FieldLayout) -> CompiledLib {
+    let shared_lib = shared_lib_with(layout);
+    let shared = shared_lib.as_lib().lib_id();
+    let nft_lib = nft_lib_with(layout);
+    let nft_id = nft_lib.as_lib().lib_id();
+    let FieldLayout { nft, .. } = layout;
+
+    const ISSUE_TOKEN: u16 = 1;
+    const VERIFY_GLOBAL_TOKEN: u16 = 3;
+    const GLOBAL_ATTACHED_TOKEN: u16 = 4;
+    const GLOBAL_TOKEN_VERIFIED: u16 = 5;
+    const VERIFY_IN_TOKEN: u16 = 6;
+    const VERIFY_OUT_TOKEN: u16 = 7;
+    const VERIFY_TOKEN: u16 = 8;
+    const LOOP_ATTACHMENTS: u16 = 10;
 
     let mut code = uasm! {
     // Verification of unique token issue
     // Args: no
     // Returns: nothing
     proc FN_RGB21_ISSUE:
+        clr     EG;             // The token must not commit to an attachment
+    label ISSUE_TOKEN:
+        // Genesis must not use global state; we do not rely on `FN_ASSET_SPEC` checking this
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        rsti    immutable;
+        cknxi   immutable;
+        not     CO;
+        chk     CO;
+
         call    shared, FN_ASSET_SPEC; // Call asset check.
 
-        // Check that there is no fractionality
-        put     E1, ERRNO_FRACTIONALITY; // Set error code for the case of failure
+        // Check that there is no fractionality declared in the global state
+        put     E1, ERRNO_GLOBAL_FRACTIONS_NOT_ONE; // Set error code for the case of failure
         put     EH, 1;
         eq      E4, EH;             // `E4` is returned from `FN_ASSET_SPEC` and contains fractions
         chk     CO;
         clr     EH;
 
         call    VERIFY_GLOBAL_TOKEN;// Verify token spec
+        // Genesis must allocate the token to exactly one output: `VERIFY_OUT_TOKEN` fails with
+        // `ERRNO_NO_OUTPUT` if there is none and with `ERRNO_TOKEN_EXCESS_OUT` if there are more
         call    VERIFY_OUT_TOKEN;   // Verify the output token
-        ret;
 
-    // TODO: Put FN_GLOBAL_VERIFY_TOKEN and FN_OWNED_TOKEN into a separate library
-    // Verify token spec
-    // We export this procedure to be used in other libraries
-    // Args: no
-    // Returns: token id in `E3`
-    proc FN_GLOBAL_VERIFY_TOKEN:
-        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
-        put     EH, G_NFT;      // Set E7 to field element representing token data
-        eq      EA, EH;         // It must have the correct state type
-        chk     CO;             // Or fail otherwise
-
-        put     E1, ERRNO_NO_TOKEN_ID; // Set error code for the case of failure
-        test    EB;             // Token id must be set
-        chk     CO;             // Or we should fail
-        mov     E3, EB;         // Save token id for returning it (used in VERIFY_AMOUNT)
-
-        put     E1, ERRNO_INVALID_TOKEN_ID; // Set error code for the case of failure
-        test    EC;             // ensure other field elements are empty
-        not     CO;             // invert CO value (we need the test to fail)
-        chk     CO;             // fail if not
-        test    ED;             // ensure other field elements are empty
-        not     CO;             // invert CO value (we need the test to fail)
-        chk     CO;             // fail if not
-        ret;
+        // Genesis must not spend anything; we do not rely on `FN_ASSET_SPEC` checking this
+        put     E1, ERRNO_UNEXPECTED_OWNED_IN; // Set error code for the case of failure
+        rsti    destructible;
+        cknxi   destructible;
+        not     CO;
+        chk     CO;
 
-    // Get token allocation
-    // We export this procedure to be used in other libraries
-    // Args: none
-    // Returns: token id in `E3`, fractions in `E4`
-    proc FN_OWNED_TOKEN:
-        put     EH, O_AMOUNT;   // Set E7 to field element representing token data
-        eq      EA, EH;         // It must have the correct state type
-        chk     CO;             // Or fail otherwise
-        test    EB;             // Token id must be set
-        chk     CO;             // Or we should fail
-        mov     E3, EB;         // Save token id for returning it
-        test    EC;             // Token fraction must be set
-        chk     CO;             // Or we should fail
-        mov     E4, EC;         // Save token fractions for returning it
-        test    ED;             // ensure other field elements are empty
-        not     CO;             // invert CO value (we need the test to fail)
-        chk     CO;             // fail if not
+        clr     E1;             // Clear the error code
         ret;
 
     // Verification of unique token transfer
@@ -125,7 +158,13 @@ pub fn unique() -> CompiledLib {
 
     routine VERIFY_GLOBAL_TOKEN:
         ldo     immutable;      // Read the fourth global state: token information
-        call    FN_GLOBAL_VERIFY_TOKEN;// Verify token spec
+        test    EG;             // May the token commit to an attachment?
+        jif     CO, GLOBAL_ATTACHED_TOKEN;
+        call    nft_id, FN_GLOBAL_VERIFY_TOKEN;// Verify token spec
+        jmp     GLOBAL_TOKEN_VERIFIED;
+    label GLOBAL_ATTACHED_TOKEN:
+        call    nft_id, FN_GLOBAL_VERIFY_ATTACHED_TOKEN;// Verify token spec with an attachment
+    label GLOBAL_TOKEN_VERIFIED:
         put     E1, ERRNO_TOKEN_EXCESS; // Set error code for the case of failure
         cknxo   immutable;      // Verify there are no more tokens
         not     CO;
@@ -137,6 +176,9 @@ pub fn unique() -> CompiledLib {
         put     E1, ERRNO_NO_INPUT; // Set error code for the case of failure
         ldi     destructible;   // Read input token information
         chk     CO;
+        put     E1, ERRNO_EMPTY_STATE; // Set error code for the case of failure
+        test    EA;             // a genuine owned state must carry a type tag
+        chk     CO;
         call    VERIFY_TOKEN;   // Verify token fractions
         put     E1, ERRNO_TOKEN_EXCESS_IN; // Set error code for the case of failure
         cknxi   destructible;   // Verify there are no more tokens
@@ -156,27 +198,68 @@ pub fn unique() -> CompiledLib {
         chk     CO;
         ret;
 
+    // Check that there is no fractionality in the owned state
     routine VERIFY_TOKEN:
-        call    FN_OWNED_TOKEN; // Get token fractions
-        put     E1, ERRNO_FRACTIONALITY; // Set error code for the case of failure
+        call    nft_id, FN_OWNED_TOKEN; // Get token fractions
+        put     E1, ERRNO_OWNED_FRACTIONS_NOT_ONE; // Set error code for the case of failure
         put     EH, 1;
         eq      E4, EH;         // Check there is no fractionality
         chk     CO;
         ret;
+
+    proc FN_VERIFY_ATTACHMENTS:
+        rsto    immutable;      // Start iteration over the global state
+    label LOOP_ATTACHMENTS:
+        ldo     immutable;      // Read next global state
+        not     CO;
+        jif     CO, +3;         // Return if no more state is left
+        ret;
+        put     EH, nft;
+        eq      EA, EH;         // Skip everything except token declarations
+        not     CO;
+        jif     CO, LOOP_ATTACHMENTS;
+        put     E1, ERRNO_MISSING_ATTACHMENT; // Set error code for the case of failure
+        test    EC;             // The attachment commitment must be set
+        chk     CO;
+        put     EH, 0;
+        eq      EC, EH;         // - and must not be zero
+        not     CO;
+        chk     CO;
+        jmp     LOOP_ATTACHMENTS;
+
+    routine FN_UNIQUE_ISSUE_ATTACHED:
+        put     EG, 1;          // The token may commit to an attachment
+        call    ISSUE_TOKEN;    // Verify the token issue
+        call    FN_VERIFY_ATTACHMENTS; // Verify the token commits to an attachment
+        clr     E1;             // Clear the error code
+        ret;
     };
 
-    CompiledLib::compile(&mut code, &[&shared_lib()])
-        .unwrap_or_else(|err| panic!("Invalid script: {err}"))
+    let lib = CompiledLib::compile(&mut code, &[&shared_lib, &nft_lib])
+        .unwrap_or_else(|err| panic!("Invalid script: {err}"));
+    debug_assert_eq!(
+        validate_dependencies(&lib, &[&shared_lib, &nft_lib]),
+        Ok(())
+    );
+    debug_assert_eq!(
+        lib.routine(FN_RGB21_ISSUE).offset,
+        0,
+        "RGB21 issue must be the first routine"
+    );
+    lib
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{FN_RGB21_ISSUE, G_DETAILS, G_NAME, G_PRECISION, G_SUPPLY};
+    use crate::{
+        nft_lib, shared_lib, shared_lib_with, FN_RGB21_ISSUE, G_DETAILS, G_NAME, G_NFT,
+        G_PRECISION, G_SUPPLY,
+    };
     use hypersonic::{AuthToken, Input, Instr, StateCell, StateData, StateValue, VmContext};
     use strict_types::StrictDumb;
-    use zkaluvm::alu::{CoreConfig, Lib, LibId, Vm};
-    use zkaluvm::{GfaConfig, FIELD_ORDER_SECP};
+    use zkaluvm::alu::{CoreConfig, CoreExt, Lib, LibId, Supercore, Vm};
+    use zkaluvm::{GfaConfig, GfaCore, RegE, FIELD_ORDER_SECP};
 
     const CONFIG: CoreConfig = CoreConfig {
         halt: true,
@@ -222,6 +305,7 @@ mod tests {
             let lib = unique();
             let unique = unique();
             let shared = shared_lib();
+            let nft = nft_lib();
             if lib.as_lib().lib_id() == id {
                 return Some(lib.into_lib());
             }
@@ -231,6 +315,9 @@ mod tests {
             if shared.as_lib().lib_id() == id {
                 return Some(shared.into_lib());
             }
+            if nft.as_lib().lib_id() == id {
+                return Some(nft.into_lib());
+            }
             panic!("Unknown library: {id}");
         }
         (unique(), vm, resolver)
@@ -312,7 +399,32 @@ mod tests {
         let res = vm
             .exec(lib.routine(FN_RGB21_ISSUE), &context, resolver)
             .is_ok();
+        let gfa: GfaCore = vm.core.cx.subcore();
+        assert!(!res);
+        assert_eq!(gfa.get(RegE::E1).unwrap().to_u256(), ERRNO_NO_OUTPUT);
+    }
+
+    #[test]
+    fn genesis_two_outputs() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[unique_token_out!(), unique_token_out!()],
+            immutable_output: &[
+                StateData::new(G_DETAILS, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, TOKEN_FRACTIONS),
+                StateData::new(G_SUPPLY, TOKEN_ID),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_RGB21_ISSUE), &context, resolver)
+            .is_ok();
+        let gfa: GfaCore = vm.core.cx.subcore();
         assert!(!res);
+        assert_eq!(gfa.get(RegE::E1).unwrap().to_u256(), ERRNO_TOKEN_EXCESS_OUT);
     }
 
     #[test]
@@ -395,6 +507,208 @@ mod tests {
         assert!(res);
     }
 
+    fn genesis_attached(attachment: Option<u64>) -> (bool, u256) {
+        genesis_token(FN_UNIQUE_ISSUE_ATTACHED, attachment)
+    }
+
+    fn genesis_token(routine: u16, attachment: Option<u64>) -> (bool, u256) {
+        let token = match attachment {
+            Some(hash) => StateData {
+                value: StateValue::Triple {
+                    first: G_NFT.into(),
+                    second: TOKEN_ID.into(),
+                    third: hash.into(),
+                },
+                raw: None,
+            },
+            None => StateData::new(G_NFT, TOKEN_ID),
+        };
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[unique_token_out!()],
+            immutable_output: &[
+                StateData::new(G_DETAILS, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, TOKEN_FRACTIONS),
+                token,
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm.exec(lib.routine(routine), &context, resolver).is_ok();
+        let gfa: GfaCore = vm.core.cx.subcore();
+        (
+            res,
+            gfa.get(RegE::E1).map(|fe| fe.to_u256()).unwrap_or_default(),
+        )
+    }
+
+    #[test]
+    fn genesis_attachment() {
+        assert!(genesis_attached(Some(0xC0FFEE)).0);
+    }
+
+    #[test]
+    fn genesis_no_attachment() {
+        for attachment in [None, Some(0)] {
+            let (res, errno) = genesis_attached(attachment);
+            assert!(!res);
+            assert_eq!(errno, ERRNO_MISSING_ATTACHMENT);
+        }
+    }
+
+    #[test]
+    fn genesis_unexpected_attachment() {
+        assert!(genesis_token(FN_RGB21_ISSUE, None).0);
+        assert_eq!(
+            genesis_token(FN_RGB21_ISSUE, Some(0xC0FFEE)),
+            (false, ERRNO_INVALID_TOKEN_ID)
+        );
+    }
+
+    #[test]
+    fn genesis_permuted_layout() {
+        let layout = FieldLayout {
+            name: u256::from(10u8),
+            ticker: u256::from(11u8),
+            precision: u256::from(12u8),
+            nft: u256::from(14u8),
+            details: u256::from(15u8),
+            reference: u256::from(16u8),
+            amount: u256::from(17u8),
+        };
+        let owned = [StateCell {
+            data: StateValue::Triple {
+                first: layout.amount.into(),
+                second: TOKEN_ID.into(),
+                third: TOKEN_FRACTIONS.into(),
+            },
+            auth: AuthToken::strict_dumb(),
+            lock: None,
+        }];
+        let globals = [
+            StateData::new(layout.ticker, 0u8),
+            StateData::new(layout.name, 0u8),
+            StateData::new(layout.precision, TOKEN_FRACTIONS),
+            StateData::new(layout.nft, TOKEN_ID),
+        ];
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &owned,
+            immutable_output: &globals,
+        };
+
+        let resolver = move |id: LibId| -> Option<Lib> {
+            [
+                unique_with(layout),
+                shared_lib_with(layout),
+                nft_lib_with(layout),
+                unique(),
+                shared_lib(),
+                nft_lib(),
+            ]
+            .into_iter()
+            .map(CompiledLib::into_lib)
+            .find(|lib| lib.lib_id() == id)
+        };
+        let mut vm = Vm::<Instr<LibId>>::with(
+            CONFIG,
+            GfaConfig {
+                field_order: FIELD_ORDER_SECP,
+            },
+        );
+        let lib = unique_with(layout);
+        assert_ne!(lib.as_lib().lib_id(), unique().as_lib().lib_id());
+        let res = vm
+            .exec(lib.routine(FN_RGB21_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(res);
+
+        // The default layout doesn't accept the permuted state
+        let (lib, mut vm, _) = harness();
+        let res = vm
+            .exec(lib.routine(FN_RGB21_ISSUE), &context, resolver)
+            .is_ok();
+        assert!(!res);
+    }
+
+    #[test]
+    fn genesis_with_input() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[unique_token_in!()],
+            immutable_input: &[],
+            destructible_output: &[unique_token_out!()],
+            immutable_output: &[
+                StateData::new(G_DETAILS, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, TOKEN_FRACTIONS),
+                StateData::new(G_SUPPLY, TOKEN_ID),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_RGB21_ISSUE), &context, resolver)
+            .is_ok();
+        let gfa: GfaCore = vm.core.cx.subcore();
+        assert!(!res);
+        assert_eq!(
+            gfa.get(RegE::E1).unwrap().to_u256(),
+            ERRNO_UNEXPECTED_OWNED_IN
+        );
+    }
+
+    fn genesis_fractions(global: u64, owned: u64) -> (bool, u256) {
+        let mut token = unique_token_out!();
+        token.data = StateValue::Triple {
+            first: O_AMOUNT.into(),
+            second: TOKEN_ID.into(),
+            third: owned.into(),
+        };
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[],
+            immutable_input: &[],
+            destructible_output: &[token],
+            immutable_output: &[
+                StateData::new(G_DETAILS, 0u8),
+                StateData::new(G_NAME, 0u8),
+                StateData::new(G_PRECISION, global),
+                StateData::new(G_SUPPLY, TOKEN_ID),
+            ],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_RGB21_ISSUE), &context, resolver)
+            .is_ok();
+        let gfa: GfaCore = vm.core.cx.subcore();
+        let errno = gfa.get(RegE::E1).map(|fe| fe.to_u256()).unwrap_or_default();
+        (res, errno)
+    }
+
+    #[test]
+    fn genesis_global_fractions() {
+        let (res, errno) = genesis_fractions(2, 1);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_GLOBAL_FRACTIONS_NOT_ONE);
+    }
+
+    #[test]
+    fn genesis_owned_fractions() {
+        let (res, errno) = genesis_fractions(1, 2);
+        assert!(!res);
+        assert_eq!(errno, ERRNO_OWNED_FRACTIONS_NOT_ONE);
+    }
+
+    #[test]
+    fn genesis_fractions_one() {
+        let (res, _) = genesis_fractions(1, 1);
+        assert!(res);
+    }
+
     #[test]
     fn transfer_contains_globals() {
         let context = VmContext {
@@ -424,6 +738,34 @@ mod tests {
         assert!(!res);
     }
 
+    #[test]
+    fn transfer_empty_input() {
+        let context = VmContext {
+            witness: none!(),
+            destructible_input: &[(
+                Input {
+                    addr: strict_dumb!(),
+                    witness: StateValue::None,
+                },
+                StateCell {
+                    data: StateValue::None,
+                    auth: AuthToken::strict_dumb(),
+                    lock: None,
+                },
+            )],
+            immutable_input: &[],
+            destructible_output: &[unique_token_out!()],
+            immutable_output: &[],
+        };
+        let (lib, mut vm, resolver) = harness();
+        let res = vm
+            .exec(lib.routine(FN_UNIQUE_TRANSFER), &context, resolver)
+            .is_ok();
+        let gfa: GfaCore = vm.core.cx.subcore();
+        assert!(!res);
+        assert_eq!(gfa.get(RegE::E1).unwrap().to_u256(), ERRNO_EMPTY_STATE);
+    }
+
     #[test]
     fn transfer_no_input() {
         let context = VmContext {
@@ -581,4 +923,13 @@ mod tests {
             .is_ok();
         assert!(res);
     }
+
+    #[test]
+    fn issue_is_first() {
+        let lib = unique();
+        assert_eq!(lib.routine(FN_RGB21_ISSUE).offset, 0);
+        // The first routine verifies genesis, which is rejected by the transfer
+        assert!(genesis_token(FN_RGB21_ISSUE, None).0);
+        assert!(!genesis_token(FN_UNIQUE_TRANSFER, None).0);
+    }
 }
@@ -0,0 +1,398 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Script libraries as they were released under [`ScriptVersion::V0`].
+//!
+//! The codices of the released issuers (FNA, NFU and UFA) commit to the ids of these libraries,
+//! thus the code here must never change: it is a verbatim copy of the released scripts, including
+//! their routine indices, such that the later changes to the current libraries can't leak in. New
+//! routines and fixes go to the current libraries under a newer script version.
+//!
+//! [`ScriptVersion::V0`]: super::ScriptVersion::V0
+
+use hypersonic::uasm;
+use zkaluvm::alu::CompiledLib;
+
+use crate::{
+    ERRNO_FRACTIONALITY, ERRNO_INVALID_BALANCE_IN, ERRNO_INVALID_BALANCE_OUT,
+    ERRNO_INVALID_PRECISION, ERRNO_INVALID_TOKEN_ID, ERRNO_NO_INPUT, ERRNO_NO_ISSUED,
+    ERRNO_NO_NAME, ERRNO_NO_OUTPUT, ERRNO_NO_PRECISION, ERRNO_NO_TICKER, ERRNO_NO_TOKEN_ID,
+    ERRNO_PRECISION_OVERFLOW, ERRNO_SUM_ISSUE_MISMATCH, ERRNO_SUM_MISMATCH, ERRNO_TOKEN_EXCESS,
+    ERRNO_TOKEN_EXCESS_IN, ERRNO_TOKEN_EXCESS_OUT, ERRNO_UNEXPECTED_GLOBAL,
+    ERRNO_UNEXPECTED_GLOBAL_IN, ERRNO_UNEXPECTED_GLOBAL_OUT, ERRNO_UNEXPECTED_OWNED_IN,
+    ERRNO_UNEXPECTED_OWNED_TYPE_IN, ERRNO_UNEXPECTED_OWNED_TYPE_OUT, G_DETAILS, G_NAME, G_NFT,
+    G_PRECISION, G_SUPPLY, G_TICKER, O_AMOUNT,
+};
+
+pub const FN_ASSET_SPEC: u16 = 0;
+pub const FN_GLOBAL_ABSENT: u16 = 1;
+
+pub const FN_FUNGIBLE_ISSUE: u16 = 0;
+pub const FN_FUNGIBLE_TRANSFER: u16 = 1;
+pub const FN_FUNGIBLE_SUM_INPUTS: u16 = 2;
+pub const FN_FUNGIBLE_SUM_OUTPUTS: u16 = 4;
+
+pub const FN_RGB21_ISSUE: u16 = 0;
+pub const FN_GLOBAL_VERIFY_TOKEN: u16 = 1;
+pub const FN_OWNED_TOKEN: u16 = 2;
+pub const FN_UNIQUE_TRANSFER: u16 = 3;
+
+pub fn shared_lib() -> CompiledLib {
+    assert_eq!(O_AMOUNT, G_NAME);
+    assert_eq!(G_TICKER, G_DETAILS);
+
+    let mut code = uasm! {
+     proc FN_ASSET_SPEC:
+        // There must be no inputs
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        rsti    immutable;
+        cknxi   immutable;
+        not     CO;
+        chk     CO;
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_IN; // Set error code for the case of failure
+        rsti    destructible;
+        cknxi   destructible;
+        not     CO;
+        chk     CO;
+
+        put     E1, ERRNO_NO_TICKER; // Set error code for the case of failure
+        ldo     immutable;      // Read the first global state - ticker in RGB20, details in RGB21/25
+        chk     CO;             // - it must exist
+        put     EH, G_TICKER;   // - set E1 to the field element representing owned value (also global asset name)
+        eq      EA, EH;         // - it must have the correct state type
+        chk     CO;             // - - or fail otherwise
+
+        put     E1, ERRNO_NO_NAME; // Set error code for the case of failure
+        ldo     immutable;      // Read the second global state - asset name
+        chk     CO;             // - it must exist
+        put     EH, G_NAME;     // - set E1 to a field element representing global asset ticker (or details)
+        eq      EA, EH;         // - it must have the correct state type
+        chk     CO;             // - - or fail otherwise
+
+        put     E1, ERRNO_NO_PRECISION; // Set error code for the case of failure
+        ldo     immutable;      // The third global state - precision
+        chk     CO;             // - it must exist
+        put     EH, G_PRECISION;// - set E1 to a field element representing global fractions
+        eq      EA, EH;         // - it must have the correct state type
+        chk     CO;             // - - or fail otherwise
+
+        put     E1, ERRNO_INVALID_PRECISION; // Set error code for the case of failure
+        test    EB;             // - there must be a value for the precision
+        chk     CO;             // - or fail otherwise
+        mov     E4, EB;         // Return G_PRECISION in `E4`
+        test    EC;             // - there must be no other field elements than in EC in the precision
+        not     CO;
+        chk     CO;             // - or fail otherwise
+        test    ED;             // - there must be no other field elements than in ED in the precision
+        not     CO;
+        chk     CO;             // - or fail otherwise
+
+        // Clear up
+        clr     E1;
+        clr     EA;
+        clr     EB;
+        clr     EC;
+        clr     ED;
+
+        ret;
+
+    proc FN_GLOBAL_ABSENT:
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        rsti    immutable;
+        cknxi   immutable;
+        not     CO;
+        chk     CO;
+
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_OUT; // Set error code for the case of failure
+        rsto    immutable;
+        cknxo   immutable;
+        not     CO;
+        chk     CO;
+        ret;
+    };
+
+    CompiledLib::compile(&mut code, &[]).unwrap_or_else(|err| panic!("Invalid script: {err}"))
+}
+
+pub fn fungible() -> CompiledLib {
+    const LOOP_INPUTS: u16 = 3;
+    const LOOP_OUTPUTS: u16 = 5;
+
+    let shared = shared_lib().into_lib().lib_id();
+
+    let mut code = uasm! {
+     routine FN_FUNGIBLE_ISSUE:
+        call    shared, FN_ASSET_SPEC;// Call asset check
+
+        put     E1, ERRNO_PRECISION_OVERFLOW; // Set error code for the case of failure
+        fits    E4, 8.bits;     // The precision must fit into a byte
+        chk     CO;             // - or fail otherwise
+
+        // Validate circulating supply
+        put     E1, ERRNO_NO_ISSUED; // Set error code for the case of failure
+        ldo     immutable;      // Read last global state - circulating supply
+        chk     CO;             // It must exist
+        put     E8, G_SUPPLY;   // Load supply type
+        eq      EA, E8;         // It must have a correct state type
+        chk     CO;             // Or fail otherwise
+        test    EB;             // It must be set
+        chk     CO;             // Or we should fail
+        mov     E2, EB;         // Save supply
+        test    EC;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        test    ED;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+
+        // Validate that the issued amount is equal to the sum of the outputs
+        put     E3, 0;          // E3 will contain the sum of outputs
+        call    FN_FUNGIBLE_SUM_OUTPUTS;// Compute a sum of outputs
+        put     E1, ERRNO_SUM_ISSUE_MISMATCH; // Set error code for the case of failure
+        eq      E2, E3;         // check that circulating supply equals to the sum of outputs
+        chk     CO;             // fail if not
+
+        // Check there is no more global state
+        put     E1, ERRNO_UNEXPECTED_GLOBAL; // Set error code for the case of failure
+        ldo     immutable;
+        not     CO;
+        chk     CO;
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     routine FN_FUNGIBLE_TRANSFER:
+        // Verify that no global state is defined
+        call    shared, FN_GLOBAL_ABSENT;
+
+        // Verify owned state
+        call    FN_FUNGIBLE_SUM_INPUTS; // Compute a sum of inputs into E2
+        call    FN_FUNGIBLE_SUM_OUTPUTS; // Compute a sum of outputs into E3
+        put     E1, ERRNO_SUM_MISMATCH; // Set error code for the case of failure
+        // TODO: Check the sum is not zero
+        eq      E2, E3;         // check that the sum of inputs equals the sum of outputs
+        chk     CO;             // fail if not
+
+        clr     E1;             // Clear the error code
+        ret;
+
+     proc FN_FUNGIBLE_SUM_INPUTS:
+        put     E2, 0;          // Set initial sum to zero
+        put     EH, O_AMOUNT;   // Set EH to the field element representing the owned value
+        rsti    destructible;   // Start iteration over inputs
+
+     label LOOP_INPUTS:
+        ldi     destructible;   // load next state value
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_IN; // Set error code for the case of failure
+        eq      EA, EH;         // do we have a correct state type?
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_IN; // Set error code for the case of failure
+        eq      EC, EE;         // ensure EC is not set
+        not     CO;
+        chk     CO;             // fail if not
+
+        test    ED;             // ensure ED is not set
+        not     CO;
+        chk     CO;             // fail if not
+
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+        add     E2, EB;         // add input to input accumulator
+        fits    E2, 64.bits;    // ensure we do not overflow
+        chk     CO;             // fail if not
+
+        jmp     LOOP_INPUTS;    // loop
+
+     proc FN_FUNGIBLE_SUM_OUTPUTS:
+        put     E3, 0;          // Set initial sum to zero
+        put     EH, O_AMOUNT;   // Set EH to the field element representing the owned value
+        rsto    destructible;   // Start iteration over outputs
+
+     label LOOP_OUTPUTS:
+        ldo     destructible;   // load next state value
+
+        // Finish if no more elements are present
+        not     CO;
+        jif     CO, +3;
+        ret;
+
+        put     E1, ERRNO_UNEXPECTED_OWNED_TYPE_OUT; // Set error code for the case of failure
+        eq      EA, EH;         // do we have a correct state type?
+        chk     CO;             // fail if not
+
+        put     E1, ERRNO_INVALID_BALANCE_OUT; // Set error code for the case of failure
+        test    EC;             // ensure EC is not set
+        not     CO;
+        chk     CO;             // fail if not
+
+        test    ED;             // ensure ED is not set
+        not     CO;
+        chk     CO;             // fail if not
+
+        fits    EB, 64.bits;    // ensure the value fits in u64
+        chk     CO;             // fail if not
+        add     E3, EB;         // add input to input accumulator
+        fits    E3, 64.bits;    // ensure we do not overflow
+        chk     CO;             // fail if not
+
+        jmp     LOOP_OUTPUTS;   // loop
+    };
+
+    CompiledLib::compile(&mut code, &[&shared_lib()])
+        .unwrap_or_else(|err| panic!("Invalid script: {err}"))
+}
+
+pub fn unique() -> CompiledLib {
+    let shared = shared_lib().into_lib().lib_id();
+
+    const VERIFY_GLOBAL_TOKEN: u16 = 4;
+    const VERIFY_IN_TOKEN: u16 = 5;
+    const VERIFY_OUT_TOKEN: u16 = 6;
+    const VERIFY_TOKEN: u16 = 7;
+
+    let mut code = uasm! {
+    // Verification of unique token issue
+    // Args: no
+    // Returns: nothing
+    proc FN_RGB21_ISSUE:
+        call    shared, FN_ASSET_SPEC; // Call asset check.
+
+        // Check that there is no fractionality
+        put     E1, ERRNO_FRACTIONALITY; // Set error code for the case of failure
+        put     EH, 1;
+        eq      E4, EH;             // `E4` is returned from `FN_ASSET_SPEC` and contains fractions
+        chk     CO;
+        clr     EH;
+
+        call    VERIFY_GLOBAL_TOKEN;// Verify token spec
+        call    VERIFY_OUT_TOKEN;   // Verify the output token
+        ret;
+
+    // TODO: Put FN_GLOBAL_VERIFY_TOKEN and FN_OWNED_TOKEN into a separate library
+    // Verify token spec
+    // We export this procedure to be used in other libraries
+    // Args: no
+    // Returns: token id in `E3`
+    proc FN_GLOBAL_VERIFY_TOKEN:
+        put     E1, ERRNO_UNEXPECTED_GLOBAL_IN; // Set error code for the case of failure
+        put     EH, G_NFT;      // Set E7 to field element representing token data
+        eq      EA, EH;         // It must have the correct state type
+        chk     CO;             // Or fail otherwise
+
+        put     E1, ERRNO_NO_TOKEN_ID; // Set error code for the case of failure
+        test    EB;             // Token id must be set
+        chk     CO;             // Or we should fail
+        mov     E3, EB;         // Save token id for returning it (used in VERIFY_AMOUNT)
+
+        put     E1, ERRNO_INVALID_TOKEN_ID; // Set error code for the case of failure
+        test    EC;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        test    ED;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        ret;
+
+    // Get token allocation
+    // We export this procedure to be used in other libraries
+    // Args: none
+    // Returns: token id in `E3`, fractions in `E4`
+    proc FN_OWNED_TOKEN:
+        put     EH, O_AMOUNT;   // Set E7 to field element representing token data
+        eq      EA, EH;         // It must have the correct state type
+        chk     CO;             // Or fail otherwise
+        test    EB;             // Token id must be set
+        chk     CO;             // Or we should fail
+        mov     E3, EB;         // Save token id for returning it
+        test    EC;             // Token fraction must be set
+        chk     CO;             // Or we should fail
+        mov     E4, EC;         // Save token fractions for returning it
+        test    ED;             // ensure other field elements are empty
+        not     CO;             // invert CO value (we need the test to fail)
+        chk     CO;             // fail if not
+        ret;
+
+    // Verification of unique token transfer
+    // Args: no
+    // Returns: nothing
+    proc FN_UNIQUE_TRANSFER:
+        call    shared, FN_GLOBAL_ABSENT;
+        call    VERIFY_IN_TOKEN;
+        mov     E5, E3;         // Save the token id
+        call    VERIFY_OUT_TOKEN;
+        eq      E3, E5;         // Check that the tokens have the same id
+        chk     CO;
+        ret;
+
+    routine VERIFY_GLOBAL_TOKEN:
+        ldo     immutable;      // Read the fourth global state: token information
+        call    FN_GLOBAL_VERIFY_TOKEN;// Verify token spec
+        put     E1, ERRNO_TOKEN_EXCESS; // Set error code for the case of failure
+        cknxo   immutable;      // Verify there are no more tokens
+        not     CO;
+        chk     CO;
+        ret;
+
+    routine VERIFY_IN_TOKEN:
+        rsti    destructible;   // Restart the state iterator
+        put     E1, ERRNO_NO_INPUT; // Set error code for the case of failure
+        ldi     destructible;   // Read input token information
+        chk     CO;
+        call    VERIFY_TOKEN;   // Verify token fractions
+        put     E1, ERRNO_TOKEN_EXCESS_IN; // Set error code for the case of failure
+        cknxi   destructible;   // Verify there are no more tokens
+        not     CO;
+        chk     CO;
+        ret;
+
+    routine VERIFY_OUT_TOKEN:
+        rsto    destructible;   // Restart the state iterator
+        put     E1, ERRNO_NO_OUTPUT; // Set error code for the case of failure
+        ldo     destructible;   // Read input token information
+        chk     CO;
+        call    VERIFY_TOKEN;   // Verify token fractions
+        put     E1, ERRNO_TOKEN_EXCESS_OUT; // Set error code for the case of failure
+        cknxo   destructible;   // Verify there are no more tokens
+        not     CO;
+        chk     CO;
+        ret;
+
+    routine VERIFY_TOKEN:
+        call    FN_OWNED_TOKEN; // Get token fractions
+        put     E1, ERRNO_FRACTIONALITY; // Set error code for the case of failure
+        put     EH, 1;
+        eq      E4, EH;         // Check there is no fractionality
+        chk     CO;
+        ret;
+    };
+
+    CompiledLib::compile(&mut code, &[&shared_lib()])
+        .unwrap_or_else(|err| panic!("Invalid script: {err}"))
+}
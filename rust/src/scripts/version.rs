@@ -0,0 +1,134 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+use zkaluvm::alu::CompiledLib;
+
+use super::{fungible, shared_lib, unique, v0};
+
+/// Version of the script libraries.
+///
+/// Once a contract is issued, its codex commits to the exact ids of the libraries it was compiled
+/// with, so any change to a script produces a new library which old contracts can't use. To
+/// evolve the scripts while keeping old contracts verifiable, each change to a library goes under
+/// a new version, and the library constructors versioned with this enum keep producing the
+/// historical libraries, such that a resolver may serve the one matching the contract codex.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Display)]
+#[display(lowercase)]
+#[repr(u16)]
+pub enum ScriptVersion {
+    /// The initial version of the scripts, used by the released FNA, NFU and UFA codices.
+    ///
+    /// The libraries of this version are frozen (see [`v0`]).
+    #[default]
+    V0 = 0,
+
+    /// The scripts evolved after the initial release, with the hardened asset specification checks
+    /// and the routines added since.
+    V1 = 1,
+}
+
+impl ScriptVersion {
+    /// The most recent version of the scripts, used by the issuers shipped with the crate.
+    pub const LATEST: Self = Self::V1;
+}
+
+/// Shared library of a specific script version.
+pub fn shared_lib_v(version: ScriptVersion) -> CompiledLib {
+    match version {
+        ScriptVersion::V0 => v0::shared_lib(),
+        ScriptVersion::V1 => shared_lib(),
+    }
+}
+
+/// Fungible asset library of a specific script version.
+pub fn fungible_v(version: ScriptVersion) -> CompiledLib {
+    match version {
+        ScriptVersion::V0 => v0::fungible(),
+        ScriptVersion::V1 => fungible(),
+    }
+}
+
+/// Unique token library of a specific script version.
+pub fn unique_v(version: ScriptVersion) -> CompiledLib {
+    match version {
+        ScriptVersion::V0 => v0::unique(),
+        ScriptVersion::V1 => unique(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amplify::hex::ToHex;
+    use amplify::ByteArray;
+
+    use super::*;
+    use crate::validate_dependencies;
+
+    // Ids of the libraries released under `V0`, as found in the compiled issuers
+    const SHARED_LIB_ID: &str = "5b05875eec71f4929554c90f3133de4863c15b598e7f17404e13f3a557d509cb";
+    const FUNGIBLE_LIB_ID: &str =
+        "ef5556f7c391fd8b7b69f3463d247dd93f3461170084d30c746281b60f7ecce2";
+    const UNIQUE_LIB_ID: &str = "b398ad33375401e1d6032103ac7b66fe1abba34bb3323040553dbcad6080ebb5";
+
+    fn libs(version: ScriptVersion) -> [CompiledLib; 3] {
+        [
+            shared_lib_v(version),
+            fungible_v(version),
+            unique_v(version),
+        ]
+    }
+
+    #[test]
+    fn v0_frozen() {
+        let pinned = [SHARED_LIB_ID, FUNGIBLE_LIB_ID, UNIQUE_LIB_ID];
+        for (lib, pinned) in libs(ScriptVersion::V0).iter().zip(pinned) {
+            assert_eq!(lib.as_lib().lib_id().to_byte_array().to_hex(), pinned);
+        }
+    }
+
+    #[test]
+    fn versions_differ() {
+        for (old, new) in libs(ScriptVersion::V0)
+            .iter()
+            .zip(libs(ScriptVersion::V1).iter())
+        {
+            assert_ne!(old.as_lib().lib_id(), new.as_lib().lib_id());
+        }
+    }
+
+    #[test]
+    fn latest() {
+        assert_eq!(ScriptVersion::LATEST, ScriptVersion::V1);
+        assert_eq!(
+            fungible_v(ScriptVersion::LATEST).as_lib().lib_id(),
+            fungible().as_lib().lib_id()
+        );
+    }
+
+    #[test]
+    fn v0_self_contained() {
+        let [shared, fungible, unique] = libs(ScriptVersion::V0);
+        for lib in [&fungible, &unique] {
+            assert_eq!(validate_dependencies(lib, &[&shared]), Ok(()));
+        }
+    }
+}
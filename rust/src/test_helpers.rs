@@ -0,0 +1,192 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Helpers constructing VM contexts for testing and fuzzing the issuer scripts.
+
+use hypersonic::{AuthToken, Input, Instr, Issuer, StateCell, StateData, StateValue, VmContext};
+use strict_types::StrictDumb;
+use zkaluvm::alu::{CoreConfig, Lib, LibId, LibSite, Vm};
+use zkaluvm::{GfaConfig, FIELD_ORDER_SECP};
+
+use crate::{
+    collection, consumed_complexity, nft_allocation, IssuerLibs, G_COLLECTION_SIZE, G_DETAILS,
+    G_NAME, G_NFT, G_PRECISION, G_SUPPLY, G_TICKER, O_AMOUNT,
+};
+
+/// Owned data for a [`VmContext`], which borrows all its state.
+#[derive(Clone, Debug, Default)]
+pub struct TestContext {
+    pub destructible_input: Vec<(Input, StateCell)>,
+    pub immutable_input: Vec<StateValue>,
+    pub destructible_output: Vec<StateCell>,
+    pub immutable_output: Vec<StateData>,
+}
+
+impl TestContext {
+    /// Constructs context with the given owned state inputs and outputs.
+    pub fn with(inputs: &[StateValue], outputs: &[StateValue]) -> Self {
+        Self {
+            destructible_input: inputs
+                .iter()
+                .map(|data| (Input::strict_dumb(), cell(*data)))
+                .collect(),
+            immutable_input: vec![],
+            destructible_output: outputs.iter().copied().map(cell).collect(),
+            immutable_output: vec![],
+        }
+    }
+
+    /// Adds global state to the context outputs.
+    pub fn with_globals(mut self, globals: impl IntoIterator<Item = StateData>) -> Self {
+        self.immutable_output.extend(globals);
+        self
+    }
+
+    /// Adds global state to the context inputs.
+    pub fn with_global_inputs(mut self, globals: impl IntoIterator<Item = StateValue>) -> Self {
+        self.immutable_input.extend(globals);
+        self
+    }
+
+    /// Returns VM context borrowing the data.
+    pub fn as_context(&self) -> VmContext<'_> {
+        VmContext {
+            witness: none!(),
+            destructible_input: &self.destructible_input,
+            immutable_input: &self.immutable_input,
+            destructible_output: &self.destructible_output,
+            immutable_output: &self.immutable_output,
+        }
+    }
+}
+
+/// Constructs owned state cell with no lock.
+pub fn cell(data: StateValue) -> StateCell {
+    StateCell {
+        data,
+        auth: AuthToken::strict_dumb(),
+        lock: None,
+    }
+}
+
+/// Constructs context for fungible operations spending `inputs` and creating `outputs`.
+pub fn fungible_ctx(inputs: &[u64], outputs: &[u64]) -> TestContext {
+    let amount = |val: &u64| StateValue::new(O_AMOUNT, *val);
+    TestContext::with(
+        &inputs.iter().map(amount).collect::<Vec<_>>(),
+        &outputs.iter().map(amount).collect::<Vec<_>>(),
+    )
+}
+
+/// Constructs context for NFT operations, where each input and output is a tuple of token id and
+/// the number of fractions.
+pub fn nft_ctx(inputs: &[(u64, u64)], outputs: &[(u64, u64)]) -> TestContext {
+    let alloc = |(token_id, fractions): &(u64, u64)| nft_allocation(*token_id, *fractions);
+    TestContext::with(
+        &inputs.iter().map(alloc).collect::<Vec<_>>(),
+        &outputs.iter().map(alloc).collect::<Vec<_>>(),
+    )
+}
+
+/// Constructs a minimal genesis context passing the genesis verifier of a standard `issuer`,
+/// selected by the interface the issuer conforms to.
+///
+/// The global state follows the order expected by [`FN_ASSET_SPEC`](crate::FN_ASSET_SPEC):
+/// - RGB20: ticker, name, precision and the issued supply;
+/// - RGB21: details, name, the maximal number of fractions (one), the collection size for the
+///   collection issuers, and a single token;
+/// - RGB25: details, name, precision and the issued supply.
+///
+/// The owned state allocates the whole issued supply, or the whole token, to a single output.
+///
+/// Returns `None` if the issuer doesn't conform to any of the interfaces above.
+pub fn sample_genesis(issuer: &Issuer) -> Option<TestContext> {
+    const SUPPLY: u64 = 1000;
+    const TOKEN_ID: u64 = 1;
+
+    let conforms = &issuer.default_api().conforms;
+    let fungible = |first| {
+        TestContext::with(&[], &[StateValue::new(O_AMOUNT, SUPPLY)]).with_globals([
+            StateData::new(first, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 8u8),
+            StateData::new(G_SUPPLY, SUPPLY),
+        ])
+    };
+
+    if conforms.contains(&20) {
+        Some(fungible(G_TICKER))
+    } else if conforms.contains(&25) {
+        Some(fungible(G_DETAILS))
+    } else if conforms.contains(&21) {
+        // Collections declare their size right after the fractionality
+        let size = issuer
+            .lib_ids()
+            .contains(&collection().into_lib().lib_id())
+            .then(|| StateData::new(G_COLLECTION_SIZE, 1u64));
+        let spec = [
+            StateData::new(G_DETAILS, 0u8),
+            StateData::new(G_NAME, 0u8),
+            StateData::new(G_PRECISION, 1u64),
+        ];
+        Some(
+            TestContext::with(&[], &[nft_allocation(TOKEN_ID, 1)]).with_globals(
+                spec.into_iter()
+                    .chain(size)
+                    .chain([StateData::new(G_NFT, TOKEN_ID)]),
+            ),
+        )
+    } else {
+        None
+    }
+}
+
+/// Runs the routine under the complexity limit of `max_complexity`, asserting that it terminates,
+/// either successfully or with a failure, before it hits the limit.
+///
+/// The VM doesn't count the executed instructions, so the limit is set in complexity units (see
+/// [`consumed_complexity`]); it must be large enough for the routine to process the `context`.
+///
+/// Returns whether the routine succeeded.
+pub fn assert_terminates(
+    site: LibSite,
+    context: &VmContext,
+    resolver: impl Fn(LibId) -> Option<Lib>,
+    max_complexity: u64,
+) -> bool {
+    let mut vm = Vm::<Instr<LibId>>::with(
+        CoreConfig {
+            halt: true,
+            complexity_lim: Some(max_complexity),
+        },
+        GfaConfig {
+            field_order: FIELD_ORDER_SECP,
+        },
+    );
+    let res = vm.exec(site, context, resolver).is_ok();
+    let consumed = consumed_complexity(&vm);
+    assert!(
+        consumed < max_complexity,
+        "routine {site} hit the complexity limit {max_complexity} (consumed {consumed})"
+    );
+    res
+}
@@ -0,0 +1,87 @@
+// RGB issuers
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Designed in 2019-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+// Written in 2024-2025 by Dr Maxim Orlovsky <orlovsky@pandoraprime.ch>
+//
+// Copyright (C) 2019-2022 Pandora Core SA, Neuchatel, Switzerland.
+// Copyright (C) 2022-2025 Pandora Prime Inc, Neuchatel, Switzerland.
+// Copyright (C) 2019-2025 Dr Maxim Orlovsky.
+// All rights under the above copyrights are reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except
+// in compliance with the License. You may obtain a copy of the License at
+//
+//        http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License
+// is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express
+// or implied. See the License for the specific language governing permissions and limitations under
+// the License.
+
+//! Golden values for the codex and library ids.
+//!
+//! Codex id commits to the codex timestamp, field order and verifier entry points, while each
+//! library id commits to the exact bytecode of its routines. Thus, any change to a script changes
+//! these ids, invalidating all contracts which were already issued under the previous codex.
+//!
+//! UPDATING THE VALUES BELOW IS A CONSENSUS-BREAKING CHANGE. Do it only when a new version of the
+//! codex is deliberately released, and never as a part of a refactoring.
+//!
+//! Only the released codices are pinned; the codices which are still under development (NFC,
+//! NDC) are not covered until they are released.
+
+use amplify::hex::ToHex;
+use amplify::ByteArray;
+use issuers::{all_issuers, v0};
+
+const CODEX_IDS: &[(&str, &str)] = &[
+    (
+        "FNA",
+        "ec2d79c375b52f44ffcd7c007a1e7f91566abb3ef6f475d014a416fb9957f4ef",
+    ),
+    (
+        "NFU",
+        "d53f6e46c541f80f28c26566bfd48c885be01458cdda4c5df4067396953c2b50",
+    ),
+    (
+        "UFA",
+        "9aa986be874d5b1a71c916d1b061e24d08c3a11bccc82961ad01b114c6b90a9c",
+    ),
+];
+
+const SHARED_LIB_ID: &str = "5b05875eec71f4929554c90f3133de4863c15b598e7f17404e13f3a557d509cb";
+const FUNGIBLE_LIB_ID: &str = "ef5556f7c391fd8b7b69f3463d247dd93f3461170084d30c746281b60f7ecce2";
+const UNIQUE_LIB_ID: &str = "b398ad33375401e1d6032103ac7b66fe1abba34bb3323040553dbcad6080ebb5";
+
+fn assert_pinned(name: &str, actual: String, pinned: &str) {
+    assert_eq!(
+        actual, pinned,
+        "id of `{name}` has changed, which is a consensus-breaking change; if this is intended, \
+         update the pinned value to `{actual}`"
+    );
+}
+
+#[test]
+fn codex_ids() {
+    let issuers = all_issuers();
+    for (name, pinned) in CODEX_IDS {
+        let (abbr, issuer) = issuers
+            .iter()
+            .find(|(abbr, _)| abbr == name)
+            .unwrap_or_else(|| panic!("no {name} issuer"));
+        let id = issuer.codex().codex_id().to_byte_array().to_hex();
+        assert_pinned(abbr, id, pinned);
+    }
+}
+
+#[test]
+fn lib_ids() {
+    let id = v0::shared_lib().as_lib().lib_id().to_byte_array().to_hex();
+    assert_pinned("shared_lib", id, SHARED_LIB_ID);
+    let id = v0::fungible().as_lib().lib_id().to_byte_array().to_hex();
+    assert_pinned("fungible", id, FUNGIBLE_LIB_ID);
+    let id = v0::unique().as_lib().lib_id().to_byte_array().to_hex();
+    assert_pinned("unique", id, UNIQUE_LIB_ID);
+}